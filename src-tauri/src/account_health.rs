@@ -0,0 +1,395 @@
+// アカウントの健全性の監視と自動停止
+//
+// 実行ログに記録された API エラーの HTTP ステータスから、アカウントごとの連続失敗回数を数える。
+// 対象は認証エラー（401、トークンの失効）・アカウントの凍結/ロック（403）・投稿系のレート制限（429）で、
+// タイムライン・メンション・フォロワー数の取得で受けたレート制限や重複投稿などの他のエラーは数えず、投稿・返信・削除の成功で回数を0に戻す。
+// 連続失敗が閾値に達したアカウントは status を "error" にし、理由と日時を account_health に記録して、
+// そのアカウントのスケジュール投稿と返信設定を停止する（paused_by_health で自動停止したものを区別する）。
+// /2/users/me による確認に成功すると status を "active" に戻し、自動停止したものだけを再開する。
+// レート制限による停止は一定時間後に自動で確認し、認証エラー・凍結は利用者が確認するまで停止したままにする。
+
+use rusqlite::{Connection, params, OptionalExtension, Result as SqliteResult};
+use serde::{Serialize, Deserialize};
+use chrono::{DateTime, Duration, Utc};
+use tauri::{AppHandle, Manager, State};
+
+use crate::{AppState, ExecutionLog};
+use crate::log_details::LogDetails;
+use crate::app_events;
+use crate::notifications;
+use crate::webhooks::{self, WebhookEvent};
+use crate::reply_engine::write_log;
+
+// 停止するまでの連続失敗回数
+const AUTH_FAILURE_THRESHOLD: i64 = 2;
+const SUSPENDED_FAILURE_THRESHOLD: i64 = 2;
+const RATE_LIMIT_FAILURE_THRESHOLD: i64 = 5;
+
+// レート制限で停止したアカウントを自動で確認するまでの時間
+const RATE_LIMIT_RECHECK_MINUTES: i64 = 60;
+const LOOP_TICK: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+
+// 403 のうちアカウント自体の問題を示すエラーコード（v1.1 の 64: 凍結、326: ロック）と文言
+const SUSPENDED_ERROR_CODES: &[&str] = &["64", "326"];
+const SUSPENDED_KEYWORDS: &[&str] = &["suspend", "locked"];
+
+// 失敗の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FailureKind {
+    Auth,
+    Suspended,
+    RateLimit,
+}
+
+impl FailureKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            FailureKind::Auth => "auth",
+            FailureKind::Suspended => "suspended",
+            FailureKind::RateLimit => "rate_limit",
+        }
+    }
+
+    fn threshold(&self) -> i64 {
+        match self {
+            FailureKind::Auth => AUTH_FAILURE_THRESHOLD,
+            FailureKind::Suspended => SUSPENDED_FAILURE_THRESHOLD,
+            FailureKind::RateLimit => RATE_LIMIT_FAILURE_THRESHOLD,
+        }
+    }
+
+    fn reason(&self) -> &'static str {
+        match self {
+            FailureKind::Auth => "認証エラーが続いたため停止しました（アクセストークンが失効している可能性があります）",
+            FailureKind::Suspended => "アカウントが凍結またはロックされているため停止しました",
+            FailureKind::RateLimit => "レート制限が続いたため停止しました",
+        }
+    }
+}
+
+// アカウントの健全性（UI用）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountHealth {
+    pub account_id: i64,
+    pub account_name: String,
+    pub status: String,
+    pub consecutive_failures: i64,
+    pub failure_kind: Option<String>,
+    pub last_failure_at: Option<String>,
+    pub last_failure_message: Option<String>,
+    pub last_success_at: Option<String>,
+    pub disabled_at: Option<String>,       // 自動停止した日時
+    pub disabled_reason: Option<String>,
+    pub paused_schedules: i64,             // 自動停止したスケジュール投稿の数
+    pub paused_reply_settings: i64,        // 自動停止した返信設定の数
+}
+
+// 確認の結果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountVerification {
+    pub account_id: i64,
+    pub verified: bool,
+    pub reactivated: bool,   // 自動停止から再開した
+    pub username: Option<String>,
+    pub message: String,
+}
+
+// 健全性のテーブルと、自動停止を区別する列（マイグレーションから呼ばれる）
+pub fn create_health_tables(conn: &Connection) -> SqliteResult<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS account_health (
+            account_id INTEGER PRIMARY KEY,
+            consecutive_failures INTEGER NOT NULL DEFAULT 0,
+            failure_kind TEXT,
+            last_failure_at TEXT,
+            last_failure_message TEXT,
+            last_success_at TEXT,
+            disabled_at TEXT,
+            disabled_reason TEXT,
+            FOREIGN KEY (account_id) REFERENCES bot_accounts(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    for table in ["scheduled_tweets", "reply_settings"] {
+        let exists: i32 = conn.query_row(
+            &format!("SELECT COUNT(*) FROM pragma_table_info('{}') WHERE name='paused_by_health'", table),
+            [],
+            |row| row.get(0)
+        ).unwrap_or(0);
+
+        if exists == 0 {
+            conn.execute(&format!("ALTER TABLE {} ADD COLUMN paused_by_health BOOLEAN NOT NULL DEFAULT 0", table), [])?;
+            println!("Added paused_by_health column to {} table", table);
+        }
+    }
+
+    Ok(())
+}
+
+// 実行ログから失敗の種類を判定（健全性に関係しないものは None）
+fn classify_failure(log: &ExecutionLog) -> Option<FailureKind> {
+    if log.status != "error" {
+        return None;
+    }
+    match log.details.http_status? {
+        401 => Some(FailureKind::Auth),
+        429 => is_write_request(log).then_some(FailureKind::RateLimit),
+        403 => {
+            let code_matches = log.details.api_error_code.as_deref()
+                .is_some_and(|code| SUSPENDED_ERROR_CODES.contains(&code));
+            let message = log.message.to_lowercase();
+            let message_matches = SUSPENDED_KEYWORDS.iter().any(|k| message.contains(k));
+            (code_matches || message_matches).then_some(FailureKind::Suspended)
+        }
+        _ => None,
+    }
+}
+
+// 投稿・返信・削除のログか（取得系のログは投稿内容・ツイート・ルールのいずれも持たない）
+fn is_write_request(log: &ExecutionLog) -> bool {
+    log.tweet_content.is_some() || log.tweet_id.is_some()
+        || log.details.is_automated_post() || log.details.target_tweet_id.is_some()
+}
+
+// 実行ログを書き込んだ後に呼ばれ、連続失敗回数を更新する（閾値に達したらアカウントを停止）
+pub(crate) fn observe_log(conn: &Connection, log: &ExecutionLog) -> SqliteResult<()> {
+    if log.status == "success" {
+        return record_success(conn, log.account_id);
+    }
+    let Some(kind) = classify_failure(log) else { return Ok(()) };
+
+    let previous: Option<(i64, Option<String>)> = conn.query_row(
+        "SELECT consecutive_failures, failure_kind FROM account_health WHERE account_id = ?",
+        params![log.account_id],
+        |row| Ok((row.get(0)?, row.get(1)?))
+    ).optional()?;
+
+    // 種類が変わったら数え直す
+    let failures = match previous {
+        Some((count, Some(previous_kind))) if previous_kind == kind.as_str() => count + 1,
+        _ => 1,
+    };
+
+    conn.execute(
+        "INSERT INTO account_health (account_id, consecutive_failures, failure_kind, last_failure_at, last_failure_message)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(account_id) DO UPDATE SET
+            consecutive_failures = ?2, failure_kind = ?3, last_failure_at = ?4, last_failure_message = ?5",
+        params![log.account_id, failures, kind.as_str(), log.created_at, log.message],
+    )?;
+
+    if failures >= kind.threshold() {
+        disable_account(conn, log.account_id, kind)?;
+    }
+    Ok(())
+}
+
+fn record_success(conn: &Connection, account_id: i64) -> SqliteResult<()> {
+    conn.execute(
+        "INSERT INTO account_health (account_id, consecutive_failures, last_success_at) VALUES (?1, 0, ?2)
+         ON CONFLICT(account_id) DO UPDATE SET consecutive_failures = 0, last_success_at = ?2",
+        params![account_id, Utc::now().to_rfc3339()],
+    )?;
+    Ok(())
+}
+
+// アカウントを停止し、スケジュール投稿と返信設定を止める（稼働中のアカウントのみ）
+fn disable_account(conn: &Connection, account_id: i64, kind: FailureKind) -> SqliteResult<()> {
+    let now = Utc::now().to_rfc3339();
+    let updated = conn.execute(
+        "UPDATE bot_accounts SET status = 'error', updated_at = ? WHERE id = ? AND status = 'active'",
+        params![now, account_id],
+    )?;
+    if updated == 0 {
+        return Ok(());
+    }
+
+    conn.execute(
+        "UPDATE account_health SET disabled_at = ?, disabled_reason = ? WHERE account_id = ?",
+        params![now, kind.reason(), account_id],
+    )?;
+    let schedules = conn.execute(
+        "UPDATE scheduled_tweets SET is_active = 0, paused_by_health = 1, updated_at = ? WHERE account_id = ? AND is_active = 1",
+        params![now, account_id],
+    )?;
+    let reply_settings = conn.execute(
+        "UPDATE reply_settings SET is_active = 0, paused_by_health = 1, updated_at = ? WHERE reply_bot_id = ? AND is_active = 1",
+        params![now, account_id],
+    )?;
+
+    app_events::account_status_changed(conn, account_id, Some("active".to_string()), "error", Some(kind.reason().to_string()));
+    write_log(conn, account_id, "error", "error",
+        format!("{}（スケジュール投稿 {} 件・返信設定 {} 件を停止）", kind.reason(), schedules, reply_settings),
+        None, None, LogDetails::default())?;
+    webhooks::enqueue(conn, webhooks::payload(conn, WebhookEvent::AccountError, Some(account_id), kind.reason()), None)?;
+    notifications::notify_account_disabled(conn, account_id, kind.reason())?;
+    println!("Account {} disabled by health monitor: {}", account_id, kind.as_str());
+    Ok(())
+}
+
+// 自動停止したアカウントを再開し、自動停止したスケジュール投稿と返信設定を元に戻す
+// （自動停止していなければ何もしない。再開したかを返す）
+pub(crate) fn resume_account(conn: &Connection, account_id: i64) -> SqliteResult<bool> {
+    let disabled: Option<String> = conn.query_row(
+        "SELECT disabled_at FROM account_health WHERE account_id = ?",
+        params![account_id],
+        |row| row.get(0)
+    ).optional()?.flatten();
+    if disabled.is_none() {
+        return Ok(false);
+    }
+
+    let now = Utc::now().to_rfc3339();
+    let reactivated = conn.execute(
+        "UPDATE bot_accounts SET status = 'active', updated_at = ? WHERE id = ? AND status = 'error'",
+        params![now, account_id],
+    )?;
+    if reactivated > 0 {
+        app_events::account_status_changed(conn, account_id, Some("error".to_string()), "active", None);
+    }
+    conn.execute(
+        "UPDATE account_health SET consecutive_failures = 0, failure_kind = NULL, disabled_at = NULL, disabled_reason = NULL
+         WHERE account_id = ?",
+        params![account_id],
+    )?;
+    let schedules = conn.execute(
+        "UPDATE scheduled_tweets SET is_active = 1, paused_by_health = 0, updated_at = ? WHERE account_id = ? AND paused_by_health = 1",
+        params![now, account_id],
+    )?;
+    let reply_settings = conn.execute(
+        "UPDATE reply_settings SET is_active = 1, paused_by_health = 0, updated_at = ? WHERE reply_bot_id = ? AND paused_by_health = 1",
+        params![now, account_id],
+    )?;
+
+    write_log(conn, account_id, "info", "success",
+        format!("アカウントを再開しました（スケジュール投稿 {} 件・返信設定 {} 件を再開）", schedules, reply_settings),
+        None, None, LogDetails::default())?;
+    Ok(true)
+}
+
+// /2/users/me で認証を確認し、成功したら自動停止から再開する
+pub(crate) async fn verify_account(state: &AppState, account_id: i64) -> Result<AccountVerification, String> {
+    let account = {
+        let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+        crate::get_bot_account(&conn, account_id).map_err(|e| format!("アカウント取得エラー: {}", e))?
+    };
+
+    let started = std::time::Instant::now();
+    let result = state.api.get_me(&account).await;
+
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    match result {
+        Ok(user) => {
+            conn.execute(
+                "UPDATE bot_accounts SET twitter_user_id = ? WHERE id = ?",
+                params![user.id, account_id],
+            ).map_err(|e| e.to_string())?;
+            record_success(&conn, account_id).map_err(|e| e.to_string())?;
+            let reactivated = resume_account(&conn, account_id).map_err(|e| e.to_string())?;
+
+            Ok(AccountVerification {
+                account_id,
+                verified: true,
+                reactivated,
+                message: format!("@{} として認証を確認しました", user.username),
+                username: Some(user.username),
+            })
+        }
+        Err(e) => {
+            let details = LogDetails::default().with_duration(started.elapsed()).with_api_error(&e);
+            write_log(&conn, account_id, "error", "error",
+                format!("{} の認証確認に失敗しました: {}", account.account_name, e), None, None, details)
+                .map_err(|e| e.to_string())?;
+
+            Ok(AccountVerification {
+                account_id,
+                verified: false,
+                reactivated: false,
+                username: None,
+                message: format!("認証を確認できませんでした: {}", e),
+            })
+        }
+    }
+}
+
+// レート制限で自動停止してから一定時間経ったアカウント
+fn rate_limited_accounts_to_recheck(conn: &Connection, now: DateTime<Utc>) -> SqliteResult<Vec<i64>> {
+    let disabled_before = (now - Duration::minutes(RATE_LIMIT_RECHECK_MINUTES)).to_rfc3339();
+    let mut stmt = conn.prepare(
+        "SELECT ah.account_id FROM account_health ah
+         INNER JOIN bot_accounts ba ON ah.account_id = ba.id
+         WHERE ba.status = 'error' AND ah.failure_kind = 'rate_limit'
+           AND ah.disabled_at IS NOT NULL AND ah.disabled_at <= ?"
+    )?;
+    let ids = stmt.query_map(params![disabled_before], |row| row.get(0))?;
+    ids.collect()
+}
+
+// バックグラウンドの健全性確認ループを起動（レート制限で停止したアカウントを一定時間後に確認）
+pub fn spawn_health_monitor(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(LOOP_TICK).await;
+
+            let state = app.state::<AppState>();
+            let account_ids = match state.db.lock() {
+                Ok(conn) => rate_limited_accounts_to_recheck(&conn, Utc::now()).unwrap_or_default(),
+                Err(_) => continue,
+            };
+
+            for account_id in account_ids {
+                if let Err(e) = verify_account(&state, account_id).await {
+                    eprintln!("Health monitor error: {}", e);
+                }
+            }
+        }
+    });
+}
+
+pub(crate) fn load_account_health(conn: &Connection) -> Result<Vec<AccountHealth>, String> {
+    let mut stmt = conn.prepare(
+        "SELECT ba.id, ba.account_name, COALESCE(ba.status, 'inactive'),
+                COALESCE(ah.consecutive_failures, 0), ah.failure_kind, ah.last_failure_at, ah.last_failure_message,
+                ah.last_success_at, ah.disabled_at, ah.disabled_reason,
+                (SELECT COUNT(*) FROM scheduled_tweets st WHERE st.account_id = ba.id AND st.paused_by_health = 1),
+                (SELECT COUNT(*) FROM reply_settings rs WHERE rs.reply_bot_id = ba.id AND rs.paused_by_health = 1)
+         FROM bot_accounts ba
+         LEFT JOIN account_health ah ON ah.account_id = ba.id
+         ORDER BY ba.account_name"
+    ).map_err(|e| e.to_string())?;
+
+    let health = stmt.query_map([], |row| {
+        Ok(AccountHealth {
+            account_id: row.get(0)?,
+            account_name: row.get(1)?,
+            status: row.get(2)?,
+            consecutive_failures: row.get(3)?,
+            failure_kind: row.get(4)?,
+            last_failure_at: row.get(5)?,
+            last_failure_message: row.get(6)?,
+            last_success_at: row.get(7)?,
+            disabled_at: row.get(8)?,
+            disabled_reason: row.get(9)?,
+            paused_schedules: row.get(10)?,
+            paused_reply_settings: row.get(11)?,
+        })
+    })
+    .map_err(|e| e.to_string())?
+    .collect::<SqliteResult<Vec<_>>>()
+    .map_err(|e| e.to_string())?;
+
+    Ok(health)
+}
+
+#[tauri::command]
+pub fn get_account_health(state: State<AppState>) -> Result<Vec<AccountHealth>, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    load_account_health(&conn)
+}
+
+// アカウントの認証を確認（自動停止中なら成功時に再開）
+#[tauri::command]
+pub async fn verify_bot_account(account_id: i64, state: State<'_, AppState>) -> Result<AccountVerification, String> {
+    verify_account(&state, account_id).await
+}
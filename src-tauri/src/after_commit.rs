@@ -0,0 +1,80 @@
+// コミット後に行う処理（画面へのイベント・通知の送信）
+//
+// 実行ログの追加やアカウントの停止は呼び出し元のトランザクションの中で行われることがあり、
+// その場でイベントや通知を送ると、ロールバックされた変更やまだコミットされていない行を
+// 画面・デスクトップ通知に知らせてしまう。
+// そこで送信処理は defer でいったん接続ごとに溜め、トランザクションがコミットされた後にまとめて実行する
+// （コミットせずに破棄・ロールバックされた場合は捨てる）。トランザクションの外（自動コミット）ならその場で実行する。
+// 溜めた処理を確実に扱うため、トランザクションは conn.transaction() ではなく begin(conn) で始める。
+
+use rusqlite::{Connection, Result as SqliteResult};
+use std::collections::HashMap;
+use std::ops::Deref;
+use std::sync::{Mutex, OnceLock};
+
+type Pending = Box<dyn FnOnce() + Send>;
+
+// 接続（のアドレス）ごとの、コミット待ちの処理
+fn pending() -> &'static Mutex<HashMap<usize, Vec<Pending>>> {
+    static PENDING: OnceLock<Mutex<HashMap<usize, Vec<Pending>>>> = OnceLock::new();
+    PENDING.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn key(conn: &Connection) -> usize {
+    conn as *const Connection as usize
+}
+
+fn take(key: usize) -> Vec<Pending> {
+    pending().lock()
+        .map(|mut pending| pending.remove(&key).unwrap_or_default())
+        .unwrap_or_default()
+}
+
+// トランザクション中ならコミット後に、そうでなければすぐに実行する
+pub(crate) fn defer(conn: &Connection, f: impl FnOnce() + Send + 'static) {
+    if conn.is_autocommit() {
+        f();
+        return;
+    }
+    if let Ok(mut pending) = pending().lock() {
+        pending.entry(key(conn)).or_default().push(Box::new(f));
+    }
+}
+
+// コミットしたときだけ溜めた処理を実行するトランザクション
+pub(crate) struct Transaction<'conn> {
+    tx: Option<rusqlite::Transaction<'conn>>,
+    key: usize,
+}
+
+pub(crate) fn begin(conn: &mut Connection) -> SqliteResult<Transaction<'_>> {
+    let key = key(conn);
+    Ok(Transaction { tx: Some(conn.transaction()?), key })
+}
+
+impl Transaction<'_> {
+    pub(crate) fn commit(mut self) -> SqliteResult<()> {
+        if let Some(tx) = self.tx.take() {
+            tx.commit()?;
+        }
+        for f in take(self.key) {
+            f();
+        }
+        Ok(())
+    }
+}
+
+impl Deref for Transaction<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.tx.as_ref().expect("transaction already finished")
+    }
+}
+
+impl Drop for Transaction<'_> {
+    // コミットしなかった（またはコミットに失敗した）場合は溜めた処理を捨てる
+    fn drop(&mut self) {
+        take(self.key);
+    }
+}
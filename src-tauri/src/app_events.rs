@@ -0,0 +1,185 @@
+// フロントエンドへのイベント通知
+//
+// 実行ログの追加・投稿・返信・スケジュールの実行・アカウント状態の変化を Tauri のイベントとして送り、
+// 画面がポーリングせずに更新できるようにする。
+// 送信側は AppHandle を持たないため、イベントはいったんバス（tokio の broadcast）に流し、
+// 起動時に始めた転送ループが app.emit で画面に送る（テストではバスを直接購読して確認できる）。
+// 実行ログ・アカウント状態のイベントは呼び出し元のトランザクションがコミットされてから流す（after_commit）。
+//
+// イベント名と内容:
+//   log_added               … ExecutionLog（id つき）
+//   post_sent               … PostSent
+//   reply_sent              … ReplySent
+//   schedule_fired          … ScheduleFired
+//   account_status_changed  … AccountStatusChanged
+
+use serde::{Serialize, Deserialize};
+use std::sync::OnceLock;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::broadcast;
+
+use rusqlite::Connection;
+
+use crate::ExecutionLog;
+use crate::after_commit;
+
+const BUS_CAPACITY: usize = 256;
+
+// 投稿が成功した（スケジュール投稿・テスト投稿）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostSent {
+    pub account_id: i64,
+    pub tweet_id: Option<String>,
+    pub content: Option<String>,
+    pub schedule_id: Option<i64>,
+    pub log_id: i64,
+}
+
+// 返信が成功した（返信設定・メンションへの返信）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplySent {
+    pub account_id: i64,
+    pub tweet_id: Option<String>,
+    pub content: Option<String>,
+    pub target_tweet_id: Option<String>,
+    pub reply_setting_id: Option<i64>,
+    pub mention_rule_id: Option<i64>,
+    pub log_id: i64,
+}
+
+// スケジュールの投稿を始めた（結果は post_sent か log_added で届く）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleFired {
+    pub schedule_id: i64,
+    pub account_id: i64,
+    pub content_index: usize,
+    pub content: String,
+    pub dry_run: bool,
+    pub slot: String,
+}
+
+// アカウントの状態（active / inactive / error）が変わった
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountStatusChanged {
+    pub account_id: i64,
+    pub previous_status: Option<String>,
+    pub status: String,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum AppEvent {
+    LogAdded(ExecutionLog),
+    PostSent(PostSent),
+    ReplySent(ReplySent),
+    ScheduleFired(ScheduleFired),
+    AccountStatusChanged(AccountStatusChanged),
+}
+
+impl AppEvent {
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            AppEvent::LogAdded(_) => "log_added",
+            AppEvent::PostSent(_) => "post_sent",
+            AppEvent::ReplySent(_) => "reply_sent",
+            AppEvent::ScheduleFired(_) => "schedule_fired",
+            AppEvent::AccountStatusChanged(_) => "account_status_changed",
+        }
+    }
+
+    fn emit(&self, app: &AppHandle) -> tauri::Result<()> {
+        match self {
+            AppEvent::LogAdded(log) => app.emit(self.name(), log),
+            AppEvent::PostSent(event) => app.emit(self.name(), event),
+            AppEvent::ReplySent(event) => app.emit(self.name(), event),
+            AppEvent::ScheduleFired(event) => app.emit(self.name(), event),
+            AppEvent::AccountStatusChanged(event) => app.emit(self.name(), event),
+        }
+    }
+}
+
+fn bus() -> &'static broadcast::Sender<AppEvent> {
+    static BUS: OnceLock<broadcast::Sender<AppEvent>> = OnceLock::new();
+    BUS.get_or_init(|| broadcast::channel(BUS_CAPACITY).0)
+}
+
+pub(crate) fn subscribe() -> broadcast::Receiver<AppEvent> {
+    bus().subscribe()
+}
+
+// イベントを流す（画面が開いていなければ捨てられる）
+pub(crate) fn publish(event: AppEvent) {
+    let _ = bus().send(event);
+}
+
+// トランザクション中ならコミットされてから流す
+fn publish_after_commit(conn: &Connection, events: Vec<AppEvent>) {
+    after_commit::defer(conn, move || events.into_iter().for_each(publish));
+}
+
+// 実行ログを書き込んだ後に呼ばれ、log_added と投稿・返信のイベントを流す
+pub(crate) fn observe_log(conn: &Connection, log_id: i64, log: &ExecutionLog) {
+    let mut added = log.clone();
+    added.id = Some(log_id);
+    let mut events = vec![AppEvent::LogAdded(added)];
+
+    if log.status == "success" {
+        events.extend(log_sent_event(log_id, log));
+    }
+    publish_after_commit(conn, events);
+}
+
+// 成功した投稿・返信のログなら post_sent / reply_sent
+fn log_sent_event(log_id: i64, log: &ExecutionLog) -> Option<AppEvent> {
+    match log.log_type.as_str() {
+        "tweet" => Some(AppEvent::PostSent(PostSent {
+            account_id: log.account_id,
+            tweet_id: log.tweet_id.clone(),
+            content: log.tweet_content.clone(),
+            schedule_id: log.details.schedule_id,
+            log_id,
+        })),
+        "reply" => Some(AppEvent::ReplySent(ReplySent {
+            account_id: log.account_id,
+            tweet_id: log.tweet_id.clone(),
+            content: log.tweet_content.clone(),
+            target_tweet_id: log.details.target_tweet_id.clone(),
+            reply_setting_id: log.details.reply_setting_id,
+            mention_rule_id: log.details.mention_rule_id,
+            log_id,
+        })),
+        _ => None,
+    }
+}
+
+pub(crate) fn account_status_changed(conn: &Connection, account_id: i64, previous_status: Option<String>, status: &str, reason: Option<String>) {
+    if previous_status.as_deref() == Some(status) {
+        return;
+    }
+    publish_after_commit(conn, vec![AppEvent::AccountStatusChanged(AccountStatusChanged {
+        account_id,
+        previous_status,
+        status: status.to_string(),
+        reason,
+    })]);
+}
+
+// バスのイベントを画面に送る転送ループを起動
+pub fn spawn_event_forwarder(app: AppHandle) {
+    let mut receiver = subscribe();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) => {
+                    if let Err(e) = event.emit(&app) {
+                        eprintln!("Event emit error ({}): {}", event.name(), e);
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    eprintln!("Event forwarder skipped {} events", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
@@ -0,0 +1,296 @@
+// スケジュール投稿内容の承認フロー
+//
+// 承認が必要なアカウント（bot_accounts.requires_approval）では、投稿リストの各投稿内容に
+// 下書き（draft）・承認待ち（pending）・承認済み（approved）・却下（rejected）の状態を持たせ、
+// スケジュール投稿は承認済みの内容だけを投稿する（承認済みでない内容は飛ばして次の承認済みを投稿する）。
+// 状態はスケジュールと投稿内容の文言の組で記録するため、並べ替えや挿入では状態が保たれ、
+// 文言を書き換えた内容は新しい内容として承認待ちに戻る。記録の無い内容は承認待ちとして扱う。
+// 承認が不要なアカウントでは従来どおり保存した内容がそのまま投稿される。
+
+use rusqlite::{Connection, params, OptionalExtension, Result as SqliteResult};
+use serde::{Serialize, Deserialize};
+use chrono::Utc;
+use std::collections::HashMap;
+use tauri::State;
+
+use crate::AppState;
+use crate::schedule_content;
+
+pub const DRAFT: &str = "draft";
+pub const PENDING: &str = "pending";
+pub const APPROVED: &str = "approved";
+pub const REJECTED: &str = "rejected";
+const STATES: [&str; 4] = [DRAFT, PENDING, APPROVED, REJECTED];
+
+// 投稿内容1件の承認状態
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentReview {
+    pub schedule_id: i64,
+    pub account_id: i64,
+    pub account_name: String,
+    pub content_index: usize,
+    pub content: String,
+    pub state: String,
+    pub reviewer_note: Option<String>,
+    pub submitted_at: Option<String>,
+    pub reviewed_at: Option<String>,
+}
+
+// アカウントごとの承認設定
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AccountApproval {
+    pub account_id: i64,
+    pub account_name: String,
+    pub requires_approval: bool,
+    pub pending_count: usize,
+}
+
+// 承認状態のテーブルと、アカウントの承認要否の列（マイグレーションから呼ばれる）
+pub fn create_approval_tables(conn: &Connection) -> SqliteResult<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS schedule_content_reviews (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            schedule_id INTEGER NOT NULL,
+            content TEXT NOT NULL,
+            state TEXT NOT NULL,
+            reviewer_note TEXT,
+            submitted_at TEXT,
+            reviewed_at TEXT,
+            updated_at TEXT NOT NULL,
+            UNIQUE(schedule_id, content),
+            FOREIGN KEY (schedule_id) REFERENCES scheduled_tweets(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    let exists: i32 = conn.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info('bot_accounts') WHERE name='requires_approval'",
+        [],
+        |row| row.get(0)
+    ).unwrap_or(0);
+
+    if exists == 0 {
+        conn.execute("ALTER TABLE bot_accounts ADD COLUMN requires_approval BOOLEAN NOT NULL DEFAULT 0", [])?;
+        println!("Added requires_approval column to bot_accounts table");
+    }
+
+    Ok(())
+}
+
+pub(crate) fn requires_approval(conn: &Connection, account_id: i64) -> SqliteResult<bool> {
+    conn.query_row(
+        "SELECT requires_approval FROM bot_accounts WHERE id = ?",
+        params![account_id],
+        |row| row.get(0)
+    ).optional().map(|r| r.unwrap_or(false))
+}
+
+type ReviewRow = (String, Option<String>, Option<String>, Option<String>);
+
+// スケジュールの投稿内容ごとの記録（文言 → 状態・メモ・申請日時・確認日時）
+fn load_review_rows(conn: &Connection, schedule_id: i64) -> SqliteResult<HashMap<String, ReviewRow>> {
+    let mut stmt = conn.prepare(
+        "SELECT content, state, reviewer_note, submitted_at, reviewed_at FROM schedule_content_reviews WHERE schedule_id = ?"
+    )?;
+    let rows = stmt.query_map(params![schedule_id], |row| {
+        Ok((row.get::<_, String>(0)?, (row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)))
+    })?.collect();
+    rows
+}
+
+// current から順に見て、最初に投稿できる内容の位置（承認が不要なら current のまま）
+pub(crate) fn next_postable_index(
+    conn: &Connection,
+    account_id: i64,
+    schedule_id: i64,
+    content_list: &[String],
+    current: usize,
+) -> SqliteResult<Option<usize>> {
+    if content_list.is_empty() {
+        return Ok(None);
+    }
+    let current = current % content_list.len();
+    if !requires_approval(conn, account_id)? {
+        return Ok(Some(current));
+    }
+
+    let reviews = load_review_rows(conn, schedule_id)?;
+    Ok((0..content_list.len())
+        .map(|offset| (current + offset) % content_list.len())
+        .find(|&i| reviews.get(&content_list[i]).is_some_and(|(state, ..)| state == APPROVED)))
+}
+
+// 外部（GitHub Actions の設定ファイル）へ書き出す投稿リスト
+// 承認済みの内容だけと、次に投稿する内容のその中での位置（承認済みが無ければ None）
+pub(crate) fn approved_schedule_content(conn: &Connection, schedule_id: i64) -> Result<Option<(Vec<String>, usize)>, String> {
+    let content = schedule_content::load_schedule_content(conn, schedule_id)?;
+    if content.content_list.is_empty() {
+        return Ok(None);
+    }
+    let reviews = load_review_rows(conn, schedule_id).map_err(|e| e.to_string())?;
+    let approved: Vec<usize> = (0..content.content_list.len())
+        .filter(|&i| reviews.get(&content.content_list[i]).is_some_and(|(state, ..)| state == APPROVED))
+        .collect();
+    if approved.is_empty() {
+        return Ok(None);
+    }
+
+    let current = content.current_index.max(0) as usize % content.content_list.len();
+    let position = approved.iter().position(|&i| i >= current).unwrap_or(0);
+    let list = approved.into_iter().map(|i| content.content_list[i].clone()).collect();
+    Ok(Some((list, position)))
+}
+
+// スケジュールの投稿内容と承認状態（記録が無ければ承認待ち）
+pub(crate) fn schedule_reviews(conn: &Connection, schedule_id: i64) -> Result<Vec<ContentReview>, String> {
+    let (account_id, account_name): (i64, String) = conn.query_row(
+        "SELECT st.account_id, ba.account_name FROM scheduled_tweets st
+         JOIN bot_accounts ba ON st.account_id = ba.id
+         WHERE st.id = ?",
+        params![schedule_id],
+        |row| Ok((row.get(0)?, row.get(1)?))
+    ).map_err(|_| format!("スケジュール ID {} が存在しません", schedule_id))?;
+
+    let content = schedule_content::load_schedule_content(conn, schedule_id)?;
+    let reviews = load_review_rows(conn, schedule_id).map_err(|e| e.to_string())?;
+
+    Ok(content.content_list.into_iter().enumerate()
+        .map(|(content_index, content)| {
+            let (state, reviewer_note, submitted_at, reviewed_at) = reviews.get(&content).cloned()
+                .unwrap_or_else(|| (PENDING.to_string(), None, None, None));
+            ContentReview {
+                schedule_id,
+                account_id,
+                account_name: account_name.clone(),
+                content_index,
+                content,
+                state,
+                reviewer_note,
+                submitted_at,
+                reviewed_at,
+            }
+        })
+        .collect())
+}
+
+// 承認が必要なアカウントの有効なスケジュールから、指定した状態（既定は承認待ち）の内容を集める
+pub(crate) fn review_queue(conn: &Connection, account_id: Option<i64>, states: &[String]) -> Result<Vec<ContentReview>, String> {
+    let schedule_ids: Vec<i64> = conn.prepare(
+        "SELECT st.id FROM scheduled_tweets st
+         JOIN bot_accounts ba ON st.account_id = ba.id
+         WHERE st.is_active = 1 AND ba.requires_approval = 1 AND (?1 IS NULL OR st.account_id = ?1)
+         ORDER BY ba.account_name, st.id"
+    )
+    .and_then(|mut stmt| stmt.query_map(params![account_id], |row| row.get(0))?.collect::<SqliteResult<Vec<_>>>())
+    .map_err(|e| e.to_string())?;
+
+    let mut queue = Vec::new();
+    for schedule_id in schedule_ids {
+        queue.extend(schedule_reviews(conn, schedule_id)?.into_iter()
+            .filter(|review| states.iter().any(|s| s == &review.state)));
+    }
+    Ok(queue)
+}
+
+// 投稿内容の状態を変える（承認・却下・承認待ちへの申請・下書きへの取り下げ）
+pub(crate) fn set_review_state(
+    conn: &Connection,
+    schedule_id: i64,
+    content_indices: &[usize],
+    state: &str,
+    reviewer_note: Option<String>,
+) -> Result<Vec<ContentReview>, String> {
+    if !STATES.contains(&state) {
+        return Err(format!("状態「{}」は指定できません（draft / pending / approved / rejected）", state));
+    }
+    if state == REJECTED && !reviewer_note.as_deref().is_some_and(|n| !n.trim().is_empty()) {
+        return Err("却下する場合は理由をメモに入力してください".to_string());
+    }
+
+    let content = schedule_content::load_schedule_content(conn, schedule_id)?;
+    let now = Utc::now().to_rfc3339();
+    let reviewed_at = [APPROVED, REJECTED].contains(&state).then(|| now.clone());
+    let submitted_at = (state == PENDING).then(|| now.clone());
+    let reviewer_note = reviewer_note.filter(|n| !n.trim().is_empty());
+
+    for &index in content_indices {
+        let text = content.content_list.get(index)
+            .ok_or_else(|| format!("投稿インデックス {} が範囲外です", index))?;
+        conn.execute(
+            "INSERT INTO schedule_content_reviews (schedule_id, content, state, reviewer_note, submitted_at, reviewed_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+             ON CONFLICT(schedule_id, content) DO UPDATE SET
+                state = excluded.state,
+                reviewer_note = excluded.reviewer_note,
+                submitted_at = COALESCE(excluded.submitted_at, submitted_at),
+                reviewed_at = excluded.reviewed_at,
+                updated_at = excluded.updated_at",
+            params![schedule_id, text, state, reviewer_note, submitted_at, reviewed_at, now],
+        ).map_err(|e| e.to_string())?;
+    }
+
+    schedule_reviews(conn, schedule_id)
+}
+
+#[tauri::command]
+pub fn get_approval_settings(state: State<AppState>) -> Result<Vec<AccountApproval>, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    let accounts: Vec<(i64, String, bool)> = conn.prepare(
+        "SELECT id, account_name, requires_approval FROM bot_accounts ORDER BY account_name"
+    )
+    .and_then(|mut stmt| stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?.collect::<SqliteResult<Vec<_>>>())
+    .map_err(|e| e.to_string())?;
+
+    let pending = [PENDING.to_string()];
+    accounts.into_iter()
+        .map(|(account_id, account_name, requires_approval)| {
+            let pending_count = if requires_approval {
+                review_queue(&conn, Some(account_id), &pending)?.len()
+            } else {
+                0
+            };
+            Ok(AccountApproval { account_id, account_name, requires_approval, pending_count })
+        })
+        .collect()
+}
+
+// アカウントの承認要否を切り替える
+#[tauri::command]
+pub fn update_account_requires_approval(account_id: i64, requires_approval: bool, state: State<AppState>) -> Result<(), String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    let updated = conn.execute(
+        "UPDATE bot_accounts SET requires_approval = ?, updated_at = ? WHERE id = ?",
+        params![requires_approval, Utc::now().to_rfc3339(), account_id],
+    ).map_err(|e| e.to_string())?;
+    if updated == 0 {
+        return Err(format!("アカウント ID {} が存在しません", account_id));
+    }
+    Ok(())
+}
+
+// 承認待ちの一覧（states を指定すると、その状態の内容を返す）
+#[tauri::command]
+pub fn get_review_queue(account_id: Option<i64>, states: Option<Vec<String>>, state: State<AppState>) -> Result<Vec<ContentReview>, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    let states = states.filter(|s| !s.is_empty()).unwrap_or_else(|| vec![PENDING.to_string()]);
+    review_queue(&conn, account_id, &states)
+}
+
+#[tauri::command]
+pub fn get_schedule_content_reviews(schedule_id: i64, state: State<AppState>) -> Result<Vec<ContentReview>, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    schedule_reviews(&conn, schedule_id)
+}
+
+// 投稿内容を承認・却下する（review_state は approved / rejected / pending / draft）
+#[tauri::command]
+pub fn review_schedule_content(
+    schedule_id: i64,
+    content_indices: Vec<usize>,
+    review_state: String,
+    reviewer_note: Option<String>,
+    state: State<AppState>
+) -> Result<Vec<ContentReview>, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    set_review_state(&conn, schedule_id, &content_indices, &review_state, reviewer_note)
+}
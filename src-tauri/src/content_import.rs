@@ -0,0 +1,642 @@
+// スケジュール投稿内容の一括インポート
+//
+// CSV / TSV（列: アカウント, 時刻, 本文, メディアパス, タグ）またはテキストファイル
+// （1行1投稿、または空行区切りで複数行の投稿）を読み込み、全行を検証してから
+// アカウントごとのスケジュールを1トランザクションで作成・更新する。
+// 1行でもエラーがあれば何も書き込まず、行番号つきのエラー一覧を返す。
+// 投稿時刻はアカウント単位のため、同じアカウントの行で異なる時刻を指定した場合と、
+// 未対応のメディアパスを指定した場合はエラーにする（黙って無視・統合しない）。
+
+use rusqlite::{Connection, params, OptionalExtension};
+use serde::{Serialize, Deserialize};
+use regex::Regex;
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use std::sync::OnceLock;
+use tauri::State;
+
+use crate::AppState;
+use crate::after_commit;
+use crate::schedule_content;
+
+// 投稿の最大長（Twitter の重み付き文字数。日本語などは1文字2、URLは23として数える）
+const MAX_TWEET_LENGTH: usize = 280;
+const URL_LENGTH: usize = 23;
+
+// インポートリクエスト
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ScheduleImportRequest {
+    pub file_path: String,
+    pub format: Option<String>,          // "csv", "tsv", "text"（省略時は拡張子から判定）
+    pub account_id: Option<i64>,         // アカウント列が無い・空の行に使うアカウント（テキストでは必須）
+    pub scheduled_times: Option<String>, // 時刻列が無い・空の場合に使う投稿時刻（"09:00,12:00"）
+    #[serde(default)]
+    pub append: bool,                    // 既存の投稿リストの末尾に追加する（false なら置き換える）
+}
+
+// 行ごとのエラー
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportLineError {
+    pub line: usize,
+    pub message: String,
+}
+
+// アカウントごとのインポート内容
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportedSchedule {
+    pub account_id: i64,
+    pub account_name: String,
+    pub scheduled_times: String,
+    pub content_list: Vec<String>,
+    pub existing_count: usize,           // 既存の投稿リストの件数（追加時は残り、置き換え時は置き換えられる）
+}
+
+// インポート結果
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ScheduleImportReport {
+    pub format: String,
+    pub rows: usize,
+    pub schedules: Vec<ImportedSchedule>,
+    pub errors: Vec<ImportLineError>,
+    pub warnings: Vec<String>,
+    pub imported: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ImportFormat {
+    Csv,
+    Tsv,
+    Text,
+}
+
+impl ImportFormat {
+    fn resolve(format: Option<&str>, file_path: &str) -> Result<Self, String> {
+        let format = match format {
+            Some(format) => format.to_lowercase(),
+            None => Path::new(file_path)
+                .extension()
+                .map(|ext| ext.to_string_lossy().to_lowercase())
+                .unwrap_or_default(),
+        };
+
+        match format.as_str() {
+            "csv" => Ok(ImportFormat::Csv),
+            "tsv" | "tab" => Ok(ImportFormat::Tsv),
+            "text" | "txt" => Ok(ImportFormat::Text),
+            other => Err(format!("未対応のファイル形式です: {}（csv, tsv, text のいずれかを指定してください）", other)),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            ImportFormat::Csv => "csv",
+            ImportFormat::Tsv => "tsv",
+            ImportFormat::Text => "text",
+        }
+    }
+}
+
+// ファイルから読み込んだ1投稿分
+struct ImportRow {
+    line: usize,
+    account: Option<String>,
+    time: Option<String>,
+    text: String,
+    media: Option<String>,
+    tags: Option<String>,
+}
+
+// 列の位置
+struct Columns {
+    account: Option<usize>,
+    time: Option<usize>,
+    text: usize,
+    media: Option<usize>,
+    tags: Option<usize>,
+}
+
+impl Columns {
+    // 見出し行なら列名から位置を決める（本文の列が見つからなければ見出し行ではない）
+    fn from_header(record: &csv::StringRecord) -> Option<Self> {
+        let find = |names: &[&str]| {
+            record.iter().position(|cell| names.contains(&cell.trim().to_lowercase().as_str()))
+        };
+
+        Some(Columns {
+            text: find(&["text", "content", "本文", "投稿内容"])?,
+            account: find(&["account", "account_name", "アカウント"]),
+            time: find(&["time", "times", "scheduled_times", "時刻", "投稿時刻"]),
+            media: find(&["media", "media_path", "メディア", "画像"]),
+            tags: find(&["tags", "tag", "タグ"]),
+        })
+    }
+
+    // 見出し行が無い場合は アカウント, 時刻, 本文, メディアパス, タグ の順（1列のみなら本文）
+    fn positional(len: usize) -> Self {
+        if len == 1 {
+            return Columns { account: None, time: None, text: 0, media: None, tags: None };
+        }
+        Columns { account: Some(0), time: Some(1), text: 2, media: Some(3), tags: Some(4) }
+    }
+}
+
+fn cell(record: &csv::StringRecord, index: Option<usize>) -> Option<String> {
+    index
+        .and_then(|i| record.get(i))
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+// レコードの開始行（csv の位置は直前の空行を指すことがあるため、空行を読み飛ばして数える）
+fn record_line(content: &str, position: &csv::Position) -> usize {
+    let (before, after) = content.split_at((position.byte() as usize).min(content.len()));
+    let blank = &after[..after.len() - after.trim_start_matches(['\r', '\n']).len()];
+    before.matches('\n').count() + blank.matches('\n').count() + 1
+}
+
+// CSV / TSV を読み込む（ダブルクォートで囲まれたセル内の改行・区切り文字に対応）
+fn parse_delimited(content: &str, delimiter: u8, errors: &mut Vec<ImportLineError>) -> Vec<ImportRow> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .delimiter(delimiter)
+        .from_reader(content.as_bytes());
+
+    let mut columns: Option<Columns> = None;
+    let mut rows = Vec::new();
+
+    for record in reader.records() {
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                let line = e.position().map(|p| record_line(content, p)).unwrap_or(0);
+                errors.push(ImportLineError { line, message: format!("行を読み込めません: {}", e) });
+                continue;
+            }
+        };
+        let line = record.position().map(|p| record_line(content, p)).unwrap_or(0);
+
+        if record.iter().all(|v| v.trim().is_empty()) {
+            continue;
+        }
+
+        // 最初の行が見出し行ならそれを使い、データとしては扱わない
+        if columns.is_none() {
+            if let Some(header) = Columns::from_header(&record) {
+                columns = Some(header);
+                continue;
+            }
+        }
+        let columns = columns.get_or_insert_with(|| Columns::positional(record.len()));
+
+        rows.push(ImportRow {
+            line,
+            account: cell(&record, columns.account),
+            time: cell(&record, columns.time),
+            text: cell(&record, Some(columns.text)).unwrap_or_default(),
+            media: cell(&record, columns.media),
+            tags: cell(&record, columns.tags),
+        });
+    }
+
+    rows
+}
+
+// テキストファイルを読み込む（空行を含む場合は空行区切り、含まない場合は1行1投稿）
+fn parse_text(content: &str) -> Vec<ImportRow> {
+    let lines: Vec<&str> = content.lines().collect();
+    let first = lines.iter().position(|l| !l.trim().is_empty());
+    let last = lines.iter().rposition(|l| !l.trim().is_empty());
+    let blank_separated = match (first, last) {
+        (Some(first), Some(last)) => lines[first..=last].iter().any(|l| l.trim().is_empty()),
+        _ => false,
+    };
+
+    let mut rows = Vec::new();
+    let mut block: Vec<&str> = Vec::new();
+    let mut block_start = 0;
+
+    for (i, line) in lines.iter().enumerate() {
+        if line.trim().is_empty() {
+            if !block.is_empty() {
+                rows.push(text_row(block_start, &block));
+                block.clear();
+            }
+            continue;
+        }
+
+        if blank_separated {
+            if block.is_empty() {
+                block_start = i + 1;
+            }
+            block.push(line.trim_end());
+        } else {
+            rows.push(text_row(i + 1, &[*line]));
+        }
+    }
+    if !block.is_empty() {
+        rows.push(text_row(block_start, &block));
+    }
+
+    rows
+}
+
+fn text_row(line: usize, lines: &[&str]) -> ImportRow {
+    ImportRow {
+        line,
+        account: None,
+        time: None,
+        text: lines.join("\n").trim().to_string(),
+        media: None,
+        tags: None,
+    }
+}
+
+fn url_pattern() -> &'static Regex {
+    static URL_PATTERN: OnceLock<Regex> = OnceLock::new();
+    URL_PATTERN.get_or_init(|| Regex::new(r"https?://\S+").expect("valid url pattern"))
+}
+
+// Twitter の重み付き文字数（twitter-text の既定設定に準拠）
+fn tweet_length(text: &str) -> usize {
+    let urls = url_pattern().find_iter(text).count();
+    let rest = url_pattern().replace_all(text, "");
+
+    let weighted: usize = rest.chars().map(|c| match c as u32 {
+        0..=0x10FF | 0x2000..=0x200D | 0x2010..=0x201F | 0x2032..=0x2037 => 1,
+        _ => 2,
+    }).sum();
+
+    weighted + urls * URL_LENGTH
+}
+
+// 時刻の指定（"9:00 12:30" や "09:00,12:00"）を "HH:MM" の一覧に正規化
+fn parse_times(value: &str) -> Result<Vec<String>, String> {
+    value
+        .split(|c: char| c == ',' || c == '、' || c == ';' || c.is_whitespace())
+        .filter(|t| !t.is_empty())
+        .map(|t| {
+            let parsed = t.split_once(':').and_then(|(h, m)| {
+                let hour: u32 = h.parse().ok()?;
+                let minute: u32 = m.parse().ok()?;
+                (hour < 24 && minute < 60 && m.len() == 2).then(|| format!("{:02}:{:02}", hour, minute))
+            });
+            parsed.ok_or_else(|| format!("時刻「{}」の形式が正しくありません（HH:MM で指定してください）", t))
+        })
+        .collect()
+}
+
+// タグを本文の末尾にハッシュタグとして追加
+fn append_tags(text: &str, tags: &str) -> String {
+    let hashtags: Vec<String> = tags
+        .split(|c: char| c == ',' || c == '、' || c.is_whitespace())
+        .map(|t| t.trim_start_matches(['#', '＃']))
+        .filter(|t| !t.is_empty())
+        .map(|t| format!("#{}", t))
+        .collect();
+
+    if hashtags.is_empty() {
+        text.to_string()
+    } else {
+        format!("{}\n\n{}", text, hashtags.join(" "))
+    }
+}
+
+// アカウント名（先頭の @ は省略可）またはアカウントIDからアカウントを探す
+fn find_account(conn: &Connection, value: &str) -> Result<Option<(i64, String)>, String> {
+    let name = value.trim_start_matches('@');
+    let by_name = conn.query_row(
+        "SELECT id, account_name FROM bot_accounts WHERE account_name = ? OR account_name = ?",
+        params![value, name],
+        |row| Ok((row.get(0)?, row.get(1)?))
+    ).optional().map_err(|e| e.to_string())?;
+
+    match (by_name, value.parse::<i64>()) {
+        (Some(account), _) => Ok(Some(account)),
+        (None, Ok(id)) => account_by_id(conn, id),
+        (None, Err(_)) => Ok(None),
+    }
+}
+
+fn account_by_id(conn: &Connection, id: i64) -> Result<Option<(i64, String)>, String> {
+    conn.query_row(
+        "SELECT id, account_name FROM bot_accounts WHERE id = ?",
+        params![id],
+        |row| Ok((row.get(0)?, row.get(1)?))
+    ).optional().map_err(|e| e.to_string())
+}
+
+fn existing_schedule(conn: &Connection, account_id: i64) -> Result<Option<schedule_content::ScheduleContent>, String> {
+    let schedule_id: Option<i64> = conn.query_row(
+        "SELECT id FROM scheduled_tweets WHERE account_id = ? AND is_active = 1 ORDER BY created_at DESC LIMIT 1",
+        params![account_id],
+        |row| row.get(0)
+    ).optional().map_err(|e| e.to_string())?;
+
+    schedule_id.map(|id| schedule_content::load_schedule_content(conn, id)).transpose()
+}
+
+// DBを参照しない検証（時刻・文字数・メディア）を済ませた1投稿分
+struct CheckedRow {
+    line: usize,
+    account: Option<String>,
+    times: Vec<String>,
+    text: String,           // タグを追加済みの本文
+    errors: Vec<String>,
+}
+
+// 読み込み・検証済みのファイル（DBのロックを取らずに作る）
+struct ImportFile {
+    format: ImportFormat,
+    rows: Vec<CheckedRow>,
+    errors: Vec<ImportLineError>,
+    default_times: Vec<String>,
+}
+
+fn check_row(row: ImportRow) -> CheckedRow {
+    let mut errors = Vec::new();
+
+    let times = match row.time.as_deref().map(parse_times) {
+        Some(Ok(times)) => times.into_iter().collect::<BTreeSet<_>>().into_iter().collect(),
+        Some(Err(e)) => {
+            errors.push(e);
+            Vec::new()
+        }
+        None => Vec::new(),
+    };
+
+    let text = match &row.tags {
+        Some(tags) => append_tags(&row.text, tags),
+        None => row.text.clone(),
+    };
+    if row.text.is_empty() {
+        errors.push("投稿内容が空です".to_string());
+    } else {
+        let length = tweet_length(&text);
+        if length > MAX_TWEET_LENGTH {
+            errors.push(format!("投稿内容が長すぎます（{}/{}）", length, MAX_TWEET_LENGTH));
+        }
+    }
+
+    if let Some(media) = &row.media {
+        errors.push(format!("メディア添付には未対応です（メディアパス「{}」を削除してください）", media));
+    }
+
+    CheckedRow { line: row.line, account: row.account, times, text, errors }
+}
+
+// ファイルを読み込み、DBを参照しない検証を行う
+fn read_import_file(request: &ScheduleImportRequest) -> Result<ImportFile, String> {
+    let format = ImportFormat::resolve(request.format.as_deref(), &request.file_path)?;
+    if format == ImportFormat::Text && request.account_id.is_none() {
+        return Err("テキストファイルのインポートにはアカウントの指定が必要です".to_string());
+    }
+
+    let bytes = std::fs::read(&request.file_path)
+        .map_err(|e| format!("ファイル {} を読み込めません: {}", request.file_path, e))?;
+    let content = String::from_utf8(bytes)
+        .map_err(|_| "ファイルを UTF-8 として読み込めません（UTF-8 で保存してください）".to_string())?;
+    let content = content.trim_start_matches('\u{feff}');
+
+    let default_times = match request.scheduled_times.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+        Some(times) => parse_times(times)?,
+        None => Vec::new(),
+    };
+
+    let mut errors = Vec::new();
+    let rows = match format {
+        ImportFormat::Csv => parse_delimited(content, b',', &mut errors),
+        ImportFormat::Tsv => parse_delimited(content, b'\t', &mut errors),
+        ImportFormat::Text => parse_text(content),
+    };
+
+    Ok(ImportFile {
+        format,
+        rows: rows.into_iter().map(check_row).collect(),
+        errors,
+        default_times,
+    })
+}
+
+// アカウントを照合し、アカウントごとのスケジュールにまとめる
+fn build_report(conn: &Connection, request: &ScheduleImportRequest, file: ImportFile) -> Result<ScheduleImportReport, String> {
+    let ImportFile { format, rows, mut errors, default_times } = file;
+
+    let default_account = match request.account_id {
+        Some(id) => Some(account_by_id(conn, id)?.ok_or_else(|| format!("アカウント ID {} が存在しません", id))?),
+        None => None,
+    };
+
+    // アカウントごとに投稿内容と投稿時刻を集める（ファイル内の出現順）
+    struct Group {
+        account_name: String,
+        first_line: usize,
+        contents: Vec<(usize, String)>,
+        times: Vec<String>,
+    }
+    let mut groups: Vec<(i64, Group)> = Vec::new();
+    let mut warnings = Vec::new();
+    // アカウントごとに最初に時刻を指定した行とその時刻
+    let mut account_times: HashMap<i64, (usize, Vec<String>)> = HashMap::new();
+
+    for row in &rows {
+        let mut row_errors = row.errors.clone();
+
+        let account = match &row.account {
+            Some(value) => match find_account(conn, value)? {
+                Some(account) => Some(account),
+                None => {
+                    row_errors.push(format!("アカウント「{}」が存在しません", value));
+                    None
+                }
+            },
+            None => {
+                if default_account.is_none() {
+                    row_errors.push("アカウントが指定されていません".to_string());
+                }
+                default_account.clone()
+            }
+        };
+
+        // 時刻は投稿ごとではなくアカウントの投稿時刻として扱うため、同じアカウントでは揃っている必要がある
+        if let Some((account_id, account_name)) = account.as_ref().filter(|_| !row.times.is_empty()) {
+            let (first_line, first_times) = account_times.entry(*account_id).or_insert((row.line, row.times.clone()));
+            if *first_times != row.times {
+                row_errors.push(format!(
+                    "時刻は投稿ごとには指定できません（アカウント「{}」の投稿時刻は{}行目で {} と指定されています）",
+                    account_name, first_line, first_times.join(",")
+                ));
+            }
+        }
+
+        if !row_errors.is_empty() {
+            errors.extend(row_errors.into_iter().map(|message| ImportLineError { line: row.line, message }));
+            continue;
+        }
+
+        let Some((account_id, account_name)) = account else { continue };
+        let index = match groups.iter().position(|(id, _)| *id == account_id) {
+            Some(index) => index,
+            None => {
+                groups.push((account_id, Group {
+                    account_name,
+                    first_line: row.line,
+                    contents: Vec::new(),
+                    times: Vec::new(),
+                }));
+                groups.len() - 1
+            }
+        };
+        let group = &mut groups[index].1;
+
+        if let Some((line, _)) = group.contents.iter().find(|(_, c)| *c == row.text) {
+            warnings.push(format!("{}行目: {}行目と同じ投稿内容です（Twitterでは重複投稿として拒否されます）", row.line, line));
+        }
+        group.contents.push((row.line, row.text.clone()));
+        if group.times.is_empty() {
+            group.times = row.times.clone();
+        }
+    }
+
+    let mut schedules = Vec::new();
+    for (account_id, group) in groups {
+        let existing = existing_schedule(conn, account_id)?;
+        let mut times: BTreeSet<String> = group.times.into_iter().collect();
+        if times.is_empty() {
+            times.extend(default_times.iter().cloned());
+        }
+        if request.append {
+            if let Some(existing) = &existing {
+                times.extend(parse_times(&existing.scheduled_times).unwrap_or_default());
+            }
+        }
+        if times.is_empty() {
+            errors.push(ImportLineError {
+                line: group.first_line,
+                message: format!("アカウント「{}」の投稿時刻が指定されていません", group.account_name),
+            });
+            continue;
+        }
+
+        // 追加する場合、既存の投稿リストと同じ内容は追加しない（同じファイルを再度取り込んでも重複しない）
+        let mut content_list = Vec::new();
+        if request.append {
+            if let Some(existing) = &existing {
+                content_list.extend(existing.content_list.iter().cloned());
+            }
+        }
+        let existing_len = content_list.len();
+        for (line, content) in group.contents {
+            if content_list[..existing_len].contains(&content) {
+                warnings.push(format!("{}行目: 既存の投稿リストに同じ投稿内容があるため追加しません", line));
+                continue;
+            }
+            content_list.push(content);
+        }
+
+        schedules.push(ImportedSchedule {
+            account_id,
+            account_name: group.account_name,
+            scheduled_times: times.into_iter().collect::<Vec<_>>().join(","),
+            content_list,
+            existing_count: existing.map(|e| e.content_list.len()).unwrap_or(0),
+        });
+    }
+
+    if rows.is_empty() && errors.is_empty() {
+        errors.push(ImportLineError { line: 0, message: "インポートする投稿がありません".to_string() });
+    }
+    errors.sort_by_key(|e| e.line);
+
+    Ok(ScheduleImportReport {
+        format: format.name().to_string(),
+        rows: rows.len(),
+        schedules,
+        errors,
+        warnings,
+        imported: false,
+    })
+}
+
+// ファイルを検証し、エラーが無ければ全アカウントのスケジュールを1トランザクションで保存
+// ファイルの読み込みと検証はロックの外で行い、DBはアカウントの照合から保存までの間だけロックする
+// （追加時に照合した既存の投稿リストが保存までに変わらないよう、照合と保存は同じロックの中で行う）
+pub(crate) fn import_schedules(state: &AppState, request: &ScheduleImportRequest, commit: bool) -> Result<ScheduleImportReport, String> {
+    let file = read_import_file(request)?;
+
+    let mut conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    let mut report = build_report(&conn, request, file)?;
+    if !commit || !report.errors.is_empty() {
+        return Ok(report);
+    }
+
+    let tx = after_commit::begin(&mut conn).map_err(|e| e.to_string())?;
+    for schedule in &report.schedules {
+        schedule_content::save_content_list(
+            &tx,
+            schedule.account_id,
+            schedule.scheduled_times.clone(),
+            schedule.content_list.clone(),
+        )?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+    drop(conn);
+
+    report.imported = true;
+    println!("Imported {} posts for {} accounts from {}", report.rows, report.schedules.len(), request.file_path);
+    Ok(report)
+}
+
+// インポート内容を確認（書き込みは行わない）
+#[tauri::command]
+pub fn preview_schedule_import(request: ScheduleImportRequest, state: State<AppState>) -> Result<ScheduleImportReport, String> {
+    import_schedules(&state, &request, false)
+}
+
+// ファイルからスケジュール投稿の内容を一括インポート
+#[tauri::command]
+pub fn import_schedule_content(request: ScheduleImportRequest, state: State<AppState>) -> Result<ScheduleImportReport, String> {
+    import_schedules(&state, &request, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delimited_rows_keep_their_starting_line() {
+        let content = "account,time,text\nbot,09:00,一行目\n\nbot,12:00,\"改行を\n含む投稿\"\nbot,18:00,最後\n";
+        let mut errors = Vec::new();
+        let rows = parse_delimited(content, b',', &mut errors);
+        assert!(errors.is_empty());
+
+        // 見出し行・空行も行番号に数え、セル内の改行は次の行の番号をずらす
+        let lines: Vec<(usize, &str)> = rows.iter().map(|r| (r.line, r.text.as_str())).collect();
+        assert_eq!(lines, [(2, "一行目"), (4, "改行を\n含む投稿"), (6, "最後")]);
+        assert_eq!(rows[1].time.as_deref(), Some("12:00"));
+    }
+
+    #[test]
+    fn text_rows_are_split_by_line_or_blank_line() {
+        let per_line = parse_text("一つ目\n二つ目\n");
+        assert_eq!(per_line.iter().map(|r| (r.line, r.text.as_str())).collect::<Vec<_>>(), [(1, "一つ目"), (2, "二つ目")]);
+
+        let blocks = parse_text("\n一つ目の\n投稿\n\n\n二つ目\n");
+        assert_eq!(blocks.iter().map(|r| (r.line, r.text.as_str())).collect::<Vec<_>>(), [(2, "一つ目の\n投稿"), (6, "二つ目")]);
+    }
+
+    #[test]
+    fn tweet_length_weights_cjk_and_urls() {
+        assert_eq!(tweet_length("hello"), 5);
+        assert_eq!(tweet_length("こんにちは"), 10);
+        assert_eq!(tweet_length("見て https://example.com/a/very/long/path?query=1"), 2 * 2 + 1 + URL_LENGTH);
+        assert_eq!(tweet_length(&"あ".repeat(140)), MAX_TWEET_LENGTH);
+    }
+
+    #[test]
+    fn times_are_normalized_and_invalid_times_rejected() {
+        assert_eq!(parse_times("9:00 12:30、18:05").unwrap(), ["09:00", "12:30", "18:05"]);
+        assert!(parse_times("24:00").is_err());
+        assert!(parse_times("9:5").is_err());
+    }
+}
@@ -0,0 +1,175 @@
+// スケジュール・実行ログの CSV エクスポート
+//
+// export_data の JSON とは別に、表計算ソフトでそのまま開ける CSV を出力する。
+// Excel で日本語が文字化けしないよう UTF-8 BOM 付き・CRLF 改行で書き出し、日時は JST で表示する。
+
+use rusqlite::{Connection, Result as SqliteResult};
+use chrono::{DateTime, Utc};
+use std::path::Path;
+use tauri::State;
+
+use crate::AppState;
+use crate::log_query::{self, ExecutionLogFilter};
+use crate::reply_filters::to_jst;
+use crate::schedule_content;
+use crate::scheduler;
+
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+const DISPLAY_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+// RFC3339 の日時を JST の表示形式に変換（解析できなければそのまま）
+fn display_time(value: &str) -> String {
+    DateTime::parse_from_rfc3339(value)
+        .map(|t| to_jst(t.with_timezone(&Utc)).format(DISPLAY_FORMAT).to_string())
+        .unwrap_or_else(|_| value.to_string())
+}
+
+fn create_writer(path: &str) -> Result<csv::Writer<std::fs::File>, String> {
+    if let Some(parent) = Path::new(path).parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).map_err(|e| format!("ディレクトリ作成エラー: {}", e))?;
+    }
+
+    let mut file = std::fs::File::create(path).map_err(|e| format!("ファイル作成エラー: {}", e))?;
+    std::io::Write::write_all(&mut file, UTF8_BOM).map_err(|e| format!("ファイル書き込みエラー: {}", e))?;
+
+    Ok(csv::WriterBuilder::new()
+        .terminator(csv::Terminator::CRLF)
+        .from_writer(file))
+}
+
+// スケジュールの投稿内容を1行1件で書き出す（次に投稿する内容と、各内容の投稿予定日時つき）
+pub(crate) fn write_schedules_csv(conn: &Connection, path: &str, account_id: Option<i64>, now: DateTime<Utc>) -> Result<usize, String> {
+    let schedules: Vec<(i64, i64, String, String)> = conn.prepare(
+        "SELECT st.id, st.account_id, ba.account_name, ba.status
+         FROM scheduled_tweets st
+         INNER JOIN bot_accounts ba ON st.account_id = ba.id
+         WHERE st.is_active = 1 AND (?1 IS NULL OR st.account_id = ?1)
+         ORDER BY ba.account_name, st.id"
+    )
+    .and_then(|mut stmt| {
+        stmt.query_map([account_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))?
+            .collect::<SqliteResult<Vec<_>>>()
+    })
+    .map_err(|e| e.to_string())?;
+
+    let mut writer = create_writer(path)?;
+    writer.write_record([
+        "アカウント", "アカウントID", "スケジュールID", "投稿時刻", "現在のインデックス",
+        "番号", "次に投稿", "投稿内容", "投稿予定日時",
+    ]).map_err(|e| e.to_string())?;
+
+    let mut rows = 0;
+    for (schedule_id, schedule_account_id, account_name, account_status) in schedules {
+        let content = schedule_content::load_schedule_content(conn, schedule_id)?;
+        let len = content.content_list.len();
+        if len == 0 {
+            continue;
+        }
+        let current = content.current_index.max(0) as usize % len;
+
+        // 停止中のアカウントは投稿されないため予定日時を空欄にする
+        let fire_times = if account_status == "active" {
+            scheduler::upcoming_fire_times(&content.scheduled_times, now, len)
+        } else {
+            Vec::new()
+        };
+
+        for (index, text) in content.content_list.iter().enumerate() {
+            let fire_time = fire_times.get((index + len - current) % len)
+                .map(|t| t.format(DISPLAY_FORMAT).to_string())
+                .unwrap_or_default();
+
+            writer.write_record([
+                account_name.clone(),
+                schedule_account_id.to_string(),
+                schedule_id.to_string(),
+                content.scheduled_times.clone(),
+                current.to_string(),
+                (index + 1).to_string(),
+                if index == current { "○".to_string() } else { String::new() },
+                text.clone(),
+                fire_time,
+            ]).map_err(|e| e.to_string())?;
+            rows += 1;
+        }
+    }
+
+    writer.flush().map_err(|e| e.to_string())?;
+    Ok(rows)
+}
+
+// 絞り込み条件に合う実行ログを古い順に書き出す
+pub(crate) fn write_execution_logs_csv(conn: &Connection, path: &str, filter: &ExecutionLogFilter) -> Result<usize, String> {
+    let (conditions, values) = filter.conditions()?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT el.*, ba.account_name
+         FROM execution_logs el
+         LEFT JOIN bot_accounts ba ON el.account_id = ba.id
+         {}
+         ORDER BY el.created_at, el.id",
+        log_query::where_clause(&conditions)
+    )).map_err(|e| e.to_string())?;
+
+    let logs = stmt.query_map(rusqlite::params_from_iter(values.iter()), |row| {
+        Ok((crate::execution_log_from_row(row)?, row.get::<_, Option<String>>("account_name")?))
+    })
+    .map_err(|e| e.to_string())?
+    .collect::<SqliteResult<Vec<_>>>()
+    .map_err(|e| e.to_string())?;
+
+    let mut writer = create_writer(path)?;
+    writer.write_record([
+        "ID", "日時", "アカウント", "種類", "ステータス", "メッセージ", "ツイートID", "ツイートURL", "投稿内容",
+        "スケジュールID", "投稿番号", "返信設定ID", "メンションルールID", "対象ツイートID",
+        "HTTPステータス", "エラーコード", "所要時間(ms)",
+    ]).map_err(|e| e.to_string())?;
+
+    fn optional<T: ToString>(value: &Option<T>) -> String {
+        value.as_ref().map(|v| v.to_string()).unwrap_or_default()
+    }
+
+    for (log, account_name) in &logs {
+        let tweet_url = log.tweet_id.as_ref()
+            .map(|id| format!("https://twitter.com/i/web/status/{}", id))
+            .unwrap_or_default();
+        let details = &log.details;
+
+        writer.write_record([
+            optional(&log.id),
+            display_time(&log.created_at),
+            account_name.clone().unwrap_or_default(),
+            log.log_type.clone(),
+            log.status.clone(),
+            log.message.clone(),
+            optional(&log.tweet_id),
+            tweet_url,
+            optional(&log.tweet_content),
+            optional(&details.schedule_id),
+            // 投稿番号は1始まりで表示
+            optional(&details.content_index.map(|i| i + 1)),
+            optional(&details.reply_setting_id),
+            optional(&details.mention_rule_id),
+            optional(&details.target_tweet_id),
+            optional(&details.http_status),
+            optional(&details.api_error_code),
+            optional(&details.duration_ms),
+        ]).map_err(|e| e.to_string())?;
+    }
+
+    writer.flush().map_err(|e| e.to_string())?;
+    Ok(logs.len())
+}
+
+// スケジュール投稿の内容と投稿予定を CSV に出力（出力した行数を返す）
+#[tauri::command]
+pub fn export_schedules_csv(path: String, account_id: Option<i64>, state: State<AppState>) -> Result<usize, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    write_schedules_csv(&conn, &path, account_id, Utc::now())
+}
+
+// 実行ログを CSV に出力（出力した行数を返す）
+#[tauri::command]
+pub fn export_execution_logs_csv(path: String, filter: Option<ExecutionLogFilter>, state: State<AppState>) -> Result<usize, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    write_execution_logs_csv(&conn, &path, &filter.unwrap_or_default())
+}
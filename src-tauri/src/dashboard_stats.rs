@@ -0,0 +1,282 @@
+// ダッシュボードの時系列統計
+//
+// 指定した期間（JST の日付、両端を含む）とアカウントについて、実行ログから投稿・返信・エラー・
+// スキップ・ドライランの件数を日別または時間別（JST）に集計する。件数の無い区間も0件として返し、グラフが途切れないようにする。
+// あわせてアカウントごとの件数・成功率と、次に予定されているスケジュール投稿を返す。
+
+use rusqlite::{Connection, Result as SqliteResult, ToSql};
+use serde::{Serialize, Deserialize};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+use tauri::State;
+
+use crate::AppState;
+use crate::content_approval;
+use crate::log_query::{self, jst_day_start};
+use crate::reply_filters::to_jst;
+use crate::schedule_content;
+use crate::scheduler;
+
+const DEFAULT_RANGE_DAYS: i64 = 7;
+const MAX_DAILY_RANGE_DAYS: i64 = 366;
+const MAX_HOURLY_RANGE_DAYS: i64 = 31;
+
+// 種類ごとの件数（SQLite の比較式は 0/1 を返すため SUM で数える）
+const COUNT_COLUMNS: &str =
+    "SUM(el.log_type = 'tweet' AND el.status = 'success'),
+     SUM(el.log_type = 'reply' AND el.status = 'success'),
+     SUM(el.status = 'error'),
+     SUM(el.status = 'skipped'),
+     SUM(el.status = 'simulated')";
+
+// 統計の取得条件
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DashboardStatsQuery {
+    pub date_from: Option<String>,       // JST の "YYYY-MM-DD"（省略時は date_to の6日前）
+    pub date_to: Option<String>,         // 省略時は今日（JST）
+    pub granularity: Option<String>,     // "day"（既定）または "hour"
+    pub account_ids: Option<Vec<i64>>,   // 省略時は全アカウント
+}
+
+// 種類ごとの件数
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ActivityCounts {
+    pub posts: i64,
+    pub replies: i64,
+    pub errors: i64,
+    pub skipped: i64,
+    pub simulated: i64,   // ドライラン
+}
+
+// 1区間分の件数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityBucket {
+    pub bucket: String,   // 日別は "2024-01-01"、時間別は "2024-01-01 09:00"（JST）
+    #[serde(flatten)]
+    pub counts: ActivityCounts,
+}
+
+// 次に予定されているスケジュール投稿
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NextScheduledPost {
+    pub schedule_id: i64,
+    pub scheduled_at: String,   // JST
+    pub content: String,
+}
+
+// アカウントごとの統計
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountActivity {
+    pub account_id: i64,
+    pub account_name: String,
+    pub status: String,
+    #[serde(flatten)]
+    pub counts: ActivityCounts,
+    pub success_rate: Option<f64>,   // 投稿・返信の成功 ÷（成功 + エラー）、対象が無ければ None
+    pub next_scheduled_post: Option<NextScheduledPost>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DashboardTimeSeries {
+    pub granularity: String,
+    pub date_from: String,
+    pub date_to: String,
+    pub series: Vec<ActivityBucket>,
+    pub totals: ActivityCounts,
+    pub accounts: Vec<AccountActivity>,
+}
+
+fn counts_from_row(row: &rusqlite::Row, offset: usize) -> SqliteResult<ActivityCounts> {
+    Ok(ActivityCounts {
+        posts: row.get::<_, Option<i64>>(offset)?.unwrap_or(0),
+        replies: row.get::<_, Option<i64>>(offset + 1)?.unwrap_or(0),
+        errors: row.get::<_, Option<i64>>(offset + 2)?.unwrap_or(0),
+        skipped: row.get::<_, Option<i64>>(offset + 3)?.unwrap_or(0),
+        simulated: row.get::<_, Option<i64>>(offset + 4)?.unwrap_or(0),
+    })
+}
+
+impl ActivityCounts {
+    fn add(&mut self, other: &ActivityCounts) {
+        self.posts += other.posts;
+        self.replies += other.replies;
+        self.errors += other.errors;
+        self.skipped += other.skipped;
+        self.simulated += other.simulated;
+    }
+
+    fn success_rate(&self) -> Option<f64> {
+        let succeeded = self.posts + self.replies;
+        let attempted = succeeded + self.errors;
+        (attempted > 0).then(|| succeeded as f64 / attempted as f64)
+    }
+}
+
+// アカウントの次のスケジュール投稿（有効なスケジュールのうち最も早いもの）
+pub(crate) fn next_scheduled_post(conn: &Connection, account_id: i64, now: DateTime<Utc>) -> Result<Option<NextScheduledPost>, String> {
+    let schedule_ids: Vec<i64> = conn.prepare(
+        "SELECT id FROM scheduled_tweets WHERE account_id = ? AND is_active = 1 ORDER BY id"
+    )
+    .and_then(|mut stmt| stmt.query_map([account_id], |row| row.get(0))?.collect::<SqliteResult<Vec<_>>>())
+    .map_err(|e| e.to_string())?;
+
+    let mut next: Option<(DateTime<Utc>, NextScheduledPost)> = None;
+    for schedule_id in schedule_ids {
+        let content = schedule_content::load_schedule_content(conn, schedule_id)?;
+        if content.content_list.is_empty() {
+            continue;
+        }
+        let Some(fire_time) = scheduler::upcoming_fire_times(&content.scheduled_times, now, 1).pop() else { continue };
+        let fire_time_utc = fire_time.with_timezone(&Utc);
+        if next.as_ref().is_some_and(|(earliest, _)| *earliest <= fire_time_utc) {
+            continue;
+        }
+
+        let index = content_approval::next_postable_index(
+            conn, account_id, schedule_id, &content.content_list, content.current_index.max(0) as usize,
+        ).map_err(|e| e.to_string())?;
+        let Some(index) = index else { continue };
+        next = Some((fire_time_utc, NextScheduledPost {
+            schedule_id,
+            scheduled_at: fire_time.to_rfc3339(),
+            content: content.content_list[index].clone(),
+        }));
+    }
+
+    Ok(next.map(|(_, post)| post))
+}
+
+// 期間内の各区間のキー（JST）を古い順に列挙
+fn bucket_keys(start: DateTime<Utc>, end: DateTime<Utc>, hourly: bool) -> Vec<String> {
+    let (step, format) = if hourly {
+        (Duration::hours(1), "%Y-%m-%d %H:00")
+    } else {
+        (Duration::days(1), "%Y-%m-%d")
+    };
+
+    let mut keys = Vec::new();
+    let mut time = start;
+    while time < end {
+        keys.push(to_jst(time).format(format).to_string());
+        time += step;
+    }
+    keys
+}
+
+pub(crate) fn dashboard_time_series(conn: &Connection, query: &DashboardStatsQuery, now: DateTime<Utc>) -> Result<DashboardTimeSeries, String> {
+    let hourly = match query.granularity.as_deref().unwrap_or("day") {
+        "day" => false,
+        "hour" => true,
+        other => return Err(format!("集計単位「{}」は指定できません（day / hour）", other)),
+    };
+
+    let date_to = query.date_to.clone().filter(|d| !d.is_empty())
+        .unwrap_or_else(|| to_jst(now).format("%Y-%m-%d").to_string());
+    let end = jst_day_start(&date_to)? + Duration::days(1);
+    let date_from = query.date_from.clone().filter(|d| !d.is_empty())
+        .unwrap_or_else(|| to_jst(end - Duration::days(DEFAULT_RANGE_DAYS)).format("%Y-%m-%d").to_string());
+    let start = jst_day_start(&date_from)?;
+
+    let days = (end - start).num_days();
+    let max_days = if hourly { MAX_HOURLY_RANGE_DAYS } else { MAX_DAILY_RANGE_DAYS };
+    if days < 1 {
+        return Err("期間の開始日は終了日以前にしてください".to_string());
+    }
+    if days > max_days {
+        return Err(format!("期間は{}日以内で指定してください", max_days));
+    }
+
+    let mut conditions = vec!["el.created_at >= ?".to_string(), "el.created_at < ?".to_string()];
+    let mut values: Vec<Box<dyn ToSql>> = vec![Box::new(start.to_rfc3339()), Box::new(end.to_rfc3339())];
+    if let Some(account_ids) = query.account_ids.as_ref().filter(|ids| !ids.is_empty()) {
+        conditions.push(format!("el.account_id IN ({})", vec!["?"; account_ids.len()].join(", ")));
+        values.extend(account_ids.iter().map(|id| Box::new(*id) as Box<dyn ToSql>));
+    }
+    let where_clause = log_query::where_clause(&conditions);
+
+    // 区間のキーは JST に変換してから切り捨てる
+    let bucket_format = if hourly { "%Y-%m-%d %H:00" } else { "%Y-%m-%d" };
+    let jst_modifier = format!("{:+} seconds", to_jst(now).offset().local_minus_utc());
+    let mut bucket_values: Vec<&dyn ToSql> = vec![&bucket_format, &jst_modifier];
+    bucket_values.extend(values.iter().map(|v| v.as_ref()));
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT strftime(?, el.created_at, ?) AS bucket, {}
+         FROM execution_logs el
+         {}
+         GROUP BY bucket",
+        COUNT_COLUMNS, where_clause
+    )).map_err(|e| e.to_string())?;
+    let mut counted: HashMap<String, ActivityCounts> = stmt.query_map(bucket_values.as_slice(), |row| {
+        Ok((row.get::<_, Option<String>>(0)?.unwrap_or_default(), counts_from_row(row, 1)?))
+    })
+    .map_err(|e| e.to_string())?
+    .collect::<SqliteResult<_>>()
+    .map_err(|e| e.to_string())?;
+
+    let mut totals = ActivityCounts::default();
+    let series: Vec<ActivityBucket> = bucket_keys(start, end, hourly).into_iter()
+        .map(|bucket| {
+            let counts = counted.remove(&bucket).unwrap_or_default();
+            totals.add(&counts);
+            ActivityBucket { bucket, counts }
+        })
+        .collect();
+
+    // アカウントごとの件数
+    let mut stmt = conn.prepare(&format!(
+        "SELECT el.account_id, {} FROM execution_logs el {} GROUP BY el.account_id",
+        COUNT_COLUMNS, where_clause
+    )).map_err(|e| e.to_string())?;
+    let mut per_account: HashMap<i64, ActivityCounts> = stmt.query_map(rusqlite::params_from_iter(values.iter()), |row| {
+        Ok((row.get::<_, i64>(0)?, counts_from_row(row, 1)?))
+    })
+    .map_err(|e| e.to_string())?
+    .collect::<SqliteResult<_>>()
+    .map_err(|e| e.to_string())?;
+
+    let account_rows: Vec<(i64, String, String)> = conn.prepare(
+        "SELECT id, account_name, COALESCE(status, 'inactive') FROM bot_accounts ORDER BY account_name"
+    )
+    .and_then(|mut stmt| stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?.collect::<SqliteResult<Vec<_>>>())
+    .map_err(|e| e.to_string())?;
+
+    let mut accounts = Vec::new();
+    for (account_id, account_name, status) in account_rows {
+        if query.account_ids.as_ref().is_some_and(|ids| !ids.is_empty() && !ids.contains(&account_id)) {
+            continue;
+        }
+        let counts = per_account.remove(&account_id).unwrap_or_default();
+        // 停止中のアカウントは投稿されないため予定を出さない
+        let next_scheduled_post = if status == "active" {
+            next_scheduled_post(conn, account_id, now)?
+        } else {
+            None
+        };
+
+        accounts.push(AccountActivity {
+            account_id,
+            account_name,
+            status,
+            success_rate: counts.success_rate(),
+            counts,
+            next_scheduled_post,
+        });
+    }
+
+    Ok(DashboardTimeSeries {
+        granularity: if hourly { "hour" } else { "day" }.to_string(),
+        date_from,
+        date_to,
+        series,
+        totals,
+        accounts,
+    })
+}
+
+// ダッシュボードの時系列統計（期間・集計単位・アカウントを指定）
+#[tauri::command]
+pub fn get_dashboard_time_series(query: Option<DashboardStatsQuery>, state: State<AppState>) -> Result<DashboardTimeSeries, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    dashboard_time_series(&conn, &query.unwrap_or_default(), Utc::now())
+}
@@ -0,0 +1,181 @@
+// ドライラン（シミュレーション）モード
+//
+// 全体設定（app_settings の dry_run_enabled）またはアカウントごとの設定（bot_accounts.dry_run）が
+// 有効な場合、スケジュール投稿と返信監視は投稿内容の選択・フィルタ・上限判定まで通常どおり行い、
+// Twitter API を呼ばずに実行ログへ status "simulated" として記録する。
+// シミュレーションで進んだローテーション位置・チェックポイント・返信候補の順番は dry_run_progress に
+// 本番とは別に保存し、本番の位置（GitHub Actions へのエクスポートも同じ位置を使う）と1日の返信数は変えない。
+// ドライランを切り替えると、次のシミュレーションは本番の位置から始まるよう別保存の進行状況を消す。
+
+use rusqlite::{Connection, params, OptionalExtension, Result as SqliteResult};
+use serde::{Serialize, Deserialize};
+use chrono::Utc;
+use tauri::State;
+
+use crate::AppState;
+use crate::reply_checkpoints::WatchTarget;
+
+const SETTING_ENABLED: &str = "dry_run_enabled";
+
+// dry_run_progress の種類
+const SCHEDULE_INDEX: &str = "schedule_index";
+const REPLY_CHECKPOINT: &str = "reply_checkpoint";
+const CANDIDATE_ROTATION: &str = "candidate_rotation";
+
+// ドライランで記録する実行ログのステータス
+pub(crate) const SIMULATED: &str = "simulated";
+
+// ドライランの設定（UI用）
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DryRunSettings {
+    pub enabled: bool,              // 全アカウントでドライラン
+    pub account_ids: Vec<i64>,      // 個別にドライランを有効にしたアカウント
+}
+
+// アカウントごとの列を追加（マイグレーションから呼ばれる）
+pub fn create_dry_run_column(conn: &Connection) -> SqliteResult<()> {
+    let dry_run_exists: i32 = conn.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info('bot_accounts') WHERE name='dry_run'",
+        [],
+        |row| row.get(0)
+    ).unwrap_or(0);
+
+    if dry_run_exists == 0 {
+        conn.execute(
+            "ALTER TABLE bot_accounts ADD COLUMN dry_run BOOLEAN NOT NULL DEFAULT 0",
+            [],
+        )?;
+        println!("Added dry_run column to bot_accounts table");
+    }
+
+    Ok(())
+}
+
+// シミュレーションの進行状況のテーブル（owner_id はスケジュールまたは返信設定、target は監視対象）
+pub fn create_progress_table(conn: &Connection) -> SqliteResult<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS dry_run_progress (
+            kind TEXT NOT NULL,
+            owner_id INTEGER NOT NULL,
+            target TEXT NOT NULL DEFAULT '',
+            value TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            PRIMARY KEY (kind, owner_id, target)
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+fn load_progress(conn: &Connection, kind: &str, owner_id: i64, target: &str) -> SqliteResult<Option<String>> {
+    conn.query_row(
+        "SELECT value FROM dry_run_progress WHERE kind = ? AND owner_id = ? AND target = ?",
+        params![kind, owner_id, target],
+        |row| row.get(0)
+    ).optional()
+}
+
+fn save_progress(conn: &Connection, kind: &str, owner_id: i64, target: &str, value: &str) -> SqliteResult<()> {
+    conn.execute(
+        "INSERT INTO dry_run_progress (kind, owner_id, target, value, updated_at) VALUES (?, ?, ?, ?, ?)
+         ON CONFLICT(kind, owner_id, target) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at",
+        params![kind, owner_id, target, value, Utc::now().to_rfc3339()],
+    )?;
+    Ok(())
+}
+
+fn target_key(target: WatchTarget) -> String {
+    format!("{}:{}", target.kind(), target.id())
+}
+
+// シミュレーション上の投稿リストの位置（無ければ本番の位置から）
+pub(crate) fn schedule_index(conn: &Connection, schedule_id: i64) -> SqliteResult<Option<usize>> {
+    Ok(load_progress(conn, SCHEDULE_INDEX, schedule_id, "")?.and_then(|v| v.parse().ok()))
+}
+
+pub(crate) fn set_schedule_index(conn: &Connection, schedule_id: i64, index: usize) -> SqliteResult<()> {
+    save_progress(conn, SCHEDULE_INDEX, schedule_id, "", &index.to_string())
+}
+
+// シミュレーション上の監視対象のチェックポイント
+pub(crate) fn reply_checkpoint(conn: &Connection, reply_setting_id: i64, target: WatchTarget) -> SqliteResult<Option<String>> {
+    load_progress(conn, REPLY_CHECKPOINT, reply_setting_id, &target_key(target))
+}
+
+pub(crate) fn set_reply_checkpoint(conn: &Connection, reply_setting_id: i64, target: WatchTarget, tweet_id: &str) -> SqliteResult<()> {
+    save_progress(conn, REPLY_CHECKPOINT, reply_setting_id, &target_key(target), tweet_id)
+}
+
+// シミュレーション上の返信候補のローテーション位置
+pub(crate) fn candidate_rotation(conn: &Connection, reply_setting_id: i64) -> SqliteResult<Option<i64>> {
+    Ok(load_progress(conn, CANDIDATE_ROTATION, reply_setting_id, "")?.and_then(|v| v.parse().ok()))
+}
+
+pub(crate) fn set_candidate_rotation(conn: &Connection, reply_setting_id: i64, rotation_index: usize) -> SqliteResult<()> {
+    save_progress(conn, CANDIDATE_ROTATION, reply_setting_id, "", &rotation_index.to_string())
+}
+
+// 別保存の進行状況を消す（account_id を指定した場合はそのアカウントのスケジュール・返信設定のみ）
+fn clear_progress(conn: &Connection, account_id: Option<i64>) -> SqliteResult<()> {
+    conn.execute(
+        "DELETE FROM dry_run_progress
+         WHERE ?1 IS NULL
+            OR (kind = ?2 AND owner_id IN (SELECT id FROM scheduled_tweets WHERE account_id = ?1))
+            OR (kind != ?2 AND owner_id IN (SELECT id FROM reply_settings WHERE reply_bot_id = ?1))",
+        params![account_id, SCHEDULE_INDEX],
+    )?;
+    Ok(())
+}
+
+// アカウントの投稿をシミュレートするか（全体設定またはアカウント設定が有効）
+pub(crate) fn is_dry_run(conn: &Connection, account_id: i64) -> bool {
+    if crate::get_app_setting(conn, SETTING_ENABLED).as_deref() == Some("true") {
+        return true;
+    }
+
+    conn.query_row(
+        "SELECT dry_run FROM bot_accounts WHERE id = ?",
+        params![account_id],
+        |row| row.get::<_, bool>(0)
+    ).unwrap_or(false)
+}
+
+#[tauri::command]
+pub fn get_dry_run_settings(state: State<AppState>) -> Result<DryRunSettings, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+
+    let mut stmt = conn.prepare("SELECT id FROM bot_accounts WHERE dry_run = 1 ORDER BY id")
+        .map_err(|e| e.to_string())?;
+    let account_ids = stmt.query_map([], |row| row.get(0))
+        .map_err(|e| e.to_string())?
+        .collect::<SqliteResult<Vec<i64>>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(DryRunSettings {
+        enabled: crate::get_app_setting(&conn, SETTING_ENABLED).as_deref() == Some("true"),
+        account_ids,
+    })
+}
+
+#[tauri::command]
+pub fn update_dry_run_settings(enabled: bool, state: State<AppState>) -> Result<(), String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    crate::set_app_setting(&conn, SETTING_ENABLED, if enabled { "true" } else { "false" })
+        .map_err(|e| e.to_string())?;
+    clear_progress(&conn, None).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn set_account_dry_run(account_id: i64, enabled: bool, state: State<AppState>) -> Result<(), String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+
+    let updated = conn.execute(
+        "UPDATE bot_accounts SET dry_run = ?, updated_at = ? WHERE id = ?",
+        params![enabled, Utc::now().to_rfc3339(), account_id],
+    ).map_err(|e| e.to_string())?;
+
+    if updated == 0 {
+        return Err(format!("アカウント ID {} が存在しません", account_id));
+    }
+    clear_progress(&conn, Some(account_id)).map_err(|e| e.to_string())
+}
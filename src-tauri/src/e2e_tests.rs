@@ -0,0 +1,1435 @@
+// モックサーバーを使ったスケジュール投稿・返信監視・メンション取得のエンドツーエンドテスト
+
+use axum::http::{Method, StatusCode};
+use chrono::{Duration, Utc};
+use rusqlite::{params, Connection};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::mock_twitter::MockTwitter;
+use crate::content_import::{self, ScheduleImportRequest};
+use crate::csv_export;
+use crate::log_query::{self, ExecutionLogFilter, ExecutionLogQuery};
+use crate::log_details::LogDetails;
+use crate::dashboard_stats::{self, DashboardStatsQuery};
+use crate::{account_health, content_approval, follower_tracking, notifications, posting_pause};
+use crate::webhooks::{self, WebhookEvent, WebhookRequest};
+use crate::local_api;
+use crate::app_events::{self, AppEvent};
+use crate::{mentions, prepare_database, reply_engine, scheduler, schedule_content, tweet_deletion, tweet_metrics, AppState};
+
+// 全時間帯を指定し、テスト実行時刻に関係なく現在の時間枠を対象にする
+const ALL_HOURS: &str = "00:00,01:00,02:00,03:00,04:00,05:00,06:00,07:00,08:00,09:00,10:00,11:00,\
+                         12:00,13:00,14:00,15:00,16:00,17:00,18:00,19:00,20:00,21:00,22:00,23:00";
+
+fn test_state(mock: &MockTwitter) -> AppState {
+    let conn = Connection::open_in_memory().unwrap();
+    prepare_database(&conn).unwrap();
+
+    AppState {
+        db: Mutex::new(conn),
+        api: mock.client(),
+        reply_monitor_running: AtomicBool::new(false),
+        mentions_polling: AtomicBool::new(false),
+        scheduler_running: AtomicBool::new(false),
+    }
+}
+
+// 稼働中のBotアカウントを登録（access_token でモックサーバーのユーザーと対応付ける）
+fn add_account(state: &AppState, name: &str, access_token: &str) -> i64 {
+    let conn = state.db.lock().unwrap();
+    let now = Utc::now().to_rfc3339();
+    conn.execute(
+        "INSERT INTO bot_accounts (account_name, api_key, api_key_secret, access_token, access_token_secret, api_type, status, created_at, updated_at)
+         VALUES (?, 'key', 'key-secret', ?, 'token-secret', 'Free', 'active', ?, ?)",
+        params![name, access_token, now, now],
+    ).unwrap();
+    conn.last_insert_rowid()
+}
+
+fn add_schedule(state: &AppState, account_id: i64, content_list: &[&str]) -> i64 {
+    let conn = state.db.lock().unwrap();
+    let now = Utc::now().to_rfc3339();
+    conn.execute(
+        "INSERT INTO scheduled_tweets (account_id, content, content_list, current_index, scheduled_times, is_active, created_at, updated_at)
+         VALUES (?, ?, ?, 0, ?, 1, ?, ?)",
+        params![account_id, content_list[0], serde_json::to_string(content_list).unwrap(), ALL_HOURS, now, now],
+    ).unwrap();
+    conn.last_insert_rowid()
+}
+
+// 1分前に作成した返信設定（作成後のツイートが返信対象になる）
+fn add_reply_setting(state: &AppState, reply_bot_id: i64, target_bot_ids: &[i64], reply_content: &str) -> i64 {
+    let conn = state.db.lock().unwrap();
+    let created_at = (Utc::now() - Duration::minutes(1)).to_rfc3339();
+    conn.execute(
+        "INSERT INTO reply_settings (target_bot_ids, reply_bot_id, reply_content, is_active, last_checked_tweet_ids, created_at, updated_at)
+         VALUES (?, ?, ?, 1, '[]', ?, ?)",
+        params![serde_json::to_string(target_bot_ids).unwrap(), reply_bot_id, reply_content, created_at, created_at],
+    ).unwrap();
+    conn.last_insert_rowid()
+}
+
+fn current_index(state: &AppState, schedule_id: i64) -> i32 {
+    let conn = state.db.lock().unwrap();
+    conn.query_row("SELECT current_index FROM scheduled_tweets WHERE id = ?", params![schedule_id], |row| row.get(0)).unwrap()
+}
+
+fn log_statuses(state: &AppState, account_id: i64) -> Vec<String> {
+    let conn = state.db.lock().unwrap();
+    let mut stmt = conn.prepare("SELECT status FROM execution_logs WHERE account_id = ? ORDER BY id").unwrap();
+    let statuses = stmt.query_map(params![account_id], |row| row.get(0)).unwrap();
+    statuses.collect::<Result<Vec<String>, _>>().unwrap()
+}
+
+fn last_seen(state: &AppState, reply_setting_id: i64) -> Option<String> {
+    let conn = state.db.lock().unwrap();
+    conn.query_row(
+        "SELECT last_seen_tweet_id FROM reply_checkpoints WHERE reply_setting_id = ?",
+        params![reply_setting_id],
+        |row| row.get(0)
+    ).ok().flatten()
+}
+
+// テスト用の一時ディレクトリ（drop すると中のファイルごと削除する）
+struct TempDir(std::path::PathBuf);
+
+impl TempDir {
+    fn new() -> Self {
+        let dir = std::env::temp_dir().join(format!("twitter-auto-manager-test-{}-{}", std::process::id(), rand::random::<u32>()));
+        std::fs::create_dir_all(&dir).unwrap();
+        TempDir(dir)
+    }
+
+    fn path(&self) -> &std::path::Path {
+        &self.0
+    }
+
+    // ファイルを作成してパスを返す
+    fn write(&self, name: &str, content: &str) -> String {
+        let path = self.0.join(name);
+        std::fs::write(&path, content).unwrap();
+        path.to_string_lossy().to_string()
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+#[tokio::test]
+async fn scheduled_post_is_published_once_per_slot() {
+    let mock = MockTwitter::start().await;
+    mock.add_user("poster", "poster-token");
+    let state = test_state(&mock);
+    let account_id = add_account(&state, "poster", "poster-token");
+    let schedule_id = add_schedule(&state, account_id, &["朝の投稿", "昼の投稿"]);
+
+    // 時間枠が変わらないよう同じ時刻で2回実行する
+    let now = Utc::now();
+    let first = scheduler::process_schedules(&state, now).await.unwrap();
+    assert_eq!(first.posted, 1);
+    assert_eq!(mock.posted_tweets().iter().map(|t| t.text.as_str()).collect::<Vec<_>>(), ["朝の投稿"]);
+    assert_eq!(current_index(&state, schedule_id), 1);
+    assert_eq!(log_statuses(&state, account_id), ["success"]);
+
+    // 同じ時間枠では再投稿しない
+    let second = scheduler::process_schedules(&state, now).await.unwrap();
+    assert_eq!(second.slot, first.slot);
+    assert_eq!(second.schedules_due, 0);
+    assert_eq!(mock.posted_tweets().len(), 1);
+    assert_eq!(current_index(&state, schedule_id), 1);
+}
+
+#[tokio::test]
+async fn scheduler_dry_run_does_not_call_api() {
+    let mock = MockTwitter::start().await;
+    mock.add_user("poster", "poster-token");
+    let state = test_state(&mock);
+    let account_id = add_account(&state, "poster", "poster-token");
+    let schedule_id = add_schedule(&state, account_id, &["朝の投稿", "昼の投稿"]);
+    state.db.lock().unwrap()
+        .execute("UPDATE bot_accounts SET dry_run = 1 WHERE id = ?", params![account_id]).unwrap();
+
+    let summary = scheduler::run_scheduler(&state).await.unwrap();
+    assert_eq!(summary.simulated, 1);
+    assert_eq!(summary.posted, 0);
+    assert_eq!(mock.request_count(Method::POST, "/2/tweets"), 0);
+    assert_eq!(log_statuses(&state, account_id), ["simulated"]);
+
+    // シミュレーションは本番のローテーション位置を進めず、次の枠ではシミュレーション上の次の内容を選ぶ
+    assert_eq!(current_index(&state, schedule_id), 0);
+    state.db.lock().unwrap()
+        .execute("UPDATE scheduled_tweets SET last_fired_slot = NULL WHERE id = ?", params![schedule_id]).unwrap();
+    scheduler::run_scheduler(&state).await.unwrap();
+    let conn = state.db.lock().unwrap();
+    let simulated: Vec<String> = conn.prepare("SELECT tweet_content FROM execution_logs WHERE account_id = ? ORDER BY id").unwrap()
+        .query_map(params![account_id], |row| row.get(0)).unwrap()
+        .collect::<Result<_, _>>().unwrap();
+    assert_eq!(simulated, ["朝の投稿", "昼の投稿"]);
+    assert_eq!(conn.query_row("SELECT current_index FROM scheduled_tweets WHERE id = ?", params![schedule_id], |row| row.get::<_, i32>(0)).unwrap(), 0);
+}
+
+#[tokio::test]
+async fn duplicate_scheduled_content_advances_index() {
+    let mock = MockTwitter::start().await;
+    let user_id = mock.add_user("poster", "poster-token");
+    mock.add_tweet(&user_id, "朝の投稿");
+    let state = test_state(&mock);
+    let account_id = add_account(&state, "poster", "poster-token");
+    let schedule_id = add_schedule(&state, account_id, &["朝の投稿", "昼の投稿"]);
+
+    let summary = scheduler::run_scheduler(&state).await.unwrap();
+    assert_eq!(summary.errors, 1);
+    assert!(mock.posted_tweets().is_empty());
+    assert_eq!(current_index(&state, schedule_id), 1);
+    assert_eq!(log_statuses(&state, account_id), ["error"]);
+}
+
+#[tokio::test]
+async fn other_forbidden_errors_keep_scheduled_content() {
+    let mock = MockTwitter::start().await;
+    mock.add_user("poster", "poster-token");
+    let state = test_state(&mock);
+    let account_id = add_account(&state, "poster", "poster-token");
+    let schedule_id = add_schedule(&state, account_id, &["朝の投稿", "昼の投稿"]);
+    mock.fail_next(Method::POST, "/2/tweets", StatusCode::FORBIDDEN, "Your account is temporarily locked.");
+
+    // 重複投稿以外の 403 では内容を飛ばさない
+    let summary = scheduler::run_scheduler(&state).await.unwrap();
+    assert_eq!(summary.errors, 1);
+    assert_eq!(current_index(&state, schedule_id), 0);
+}
+
+#[tokio::test]
+async fn logged_post_can_be_deleted_once() {
+    let mock = MockTwitter::start().await;
+    mock.add_user("poster", "poster-token");
+    let state = test_state(&mock);
+    let account_id = add_account(&state, "poster", "poster-token");
+    add_schedule(&state, account_id, &["朝の投稿"]);
+    scheduler::run_scheduler(&state).await.unwrap();
+
+    let (log_id, tweet_id): (i64, String) = state.db.lock().unwrap().query_row(
+        "SELECT id, tweet_id FROM execution_logs WHERE log_type = 'tweet'",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?))
+    ).unwrap();
+
+    // 取り消し可能時間が無効の場合は取り消せない
+    assert!(tweet_deletion::delete_logged_tweet(&state, log_id, true).await.is_err());
+
+    tweet_deletion::delete_logged_tweet(&state, log_id, false).await.unwrap();
+    assert!(mock.is_deleted(&tweet_id));
+    assert_eq!(log_statuses(&state, account_id), ["success", "success"]);
+
+    assert!(tweet_deletion::delete_logged_tweet(&state, log_id, false).await.is_err());
+    assert_eq!(mock.request_count(Method::DELETE, "/2/tweets"), 1);
+}
+
+#[tokio::test]
+async fn scheduled_post_can_be_undone_within_window() {
+    let mock = MockTwitter::start().await;
+    mock.add_user("poster", "poster-token");
+    let state = test_state(&mock);
+    let account_id = add_account(&state, "poster", "poster-token");
+    add_schedule(&state, account_id, &["朝の投稿"]);
+    crate::set_app_setting(&state.db.lock().unwrap(), "undo_window_seconds", "60").unwrap();
+    scheduler::run_scheduler(&state).await.unwrap();
+
+    let log_id: i64 = state.db.lock().unwrap()
+        .query_row("SELECT id FROM execution_logs WHERE log_type = 'tweet'", [], |row| row.get(0))
+        .unwrap();
+    tweet_deletion::delete_logged_tweet(&state, log_id, true).await.unwrap();
+    assert!(mock.is_deleted(&mock.posted_tweets()[0].id));
+}
+
+#[tokio::test]
+async fn import_rejects_file_with_invalid_rows() {
+    let mock = MockTwitter::start().await;
+    let state = test_state(&mock);
+    let bot_id = add_account(&state, "bot", "bot-token");
+    let dir = TempDir::new();
+    // 同じアカウントで異なる時刻を指定した行と、メディアパスを指定した行もエラー
+    let path = dir.write("invalid.csv", "account,time,text,media\nbot,09:00,おはよう,\nunknown,12:00,こんにちは,\nbot,25:00,,\nbot,12:00,昼,\nbot,,画像つき,image.png\n");
+
+    let request = ScheduleImportRequest {
+        file_path: path, format: None, account_id: None, scheduled_times: None, append: false,
+    };
+    let report = content_import::import_schedules(&state, &request, true).unwrap();
+
+    assert!(!report.imported);
+    let lines: Vec<usize> = report.errors.iter().map(|e| e.line).collect();
+    assert_eq!(lines, vec![3, 4, 4, 5, 6]);
+    let conn = state.db.lock().unwrap();
+    let schedules: i32 = conn.query_row("SELECT COUNT(*) FROM scheduled_tweets WHERE account_id = ?", params![bot_id], |row| row.get(0)).unwrap();
+    assert_eq!(schedules, 0);
+}
+
+#[tokio::test]
+async fn import_creates_and_appends_schedules() {
+    let mock = MockTwitter::start().await;
+    let state = test_state(&mock);
+    let first_id = add_account(&state, "first", "first-token");
+    let second_id = add_account(&state, "second", "second-token");
+    let dir = TempDir::new();
+    let csv_path = dir.write("schedules.csv", "first,09:00 21:00,\"1行目\n2行目\",,新商品\n@second,12:00,こんにちは\nfirst,,こんばんは\n");
+
+    let report = content_import::import_schedules(&state, &ScheduleImportRequest {
+        file_path: csv_path, format: None, account_id: None, scheduled_times: None, append: false,
+    }, true).unwrap();
+    assert!(report.imported, "{:?}", report.errors);
+    assert_eq!(report.schedules.len(), 2);
+
+    // 既存の投稿リストと同じ内容は追加しない
+    let text_path = dir.write("more.txt", "追加1\n続き\n\nこんばんは\n\n追加2\n");
+    let report = content_import::import_schedules(&state, &ScheduleImportRequest {
+        file_path: text_path, format: None, account_id: Some(first_id), scheduled_times: None, append: true,
+    }, true).unwrap();
+    assert!(report.imported, "{:?}", report.errors);
+    assert_eq!(report.warnings.len(), 1, "{:?}", report.warnings);
+    assert!(report.warnings[0].starts_with("4行目"));
+
+    let conn = state.db.lock().unwrap();
+    let schedule_id = |account_id: i64| -> i64 {
+        conn.query_row("SELECT id FROM scheduled_tweets WHERE account_id = ? AND is_active = 1", params![account_id], |row| row.get(0)).unwrap()
+    };
+    let first = schedule_content::load_schedule_content(&conn, schedule_id(first_id)).unwrap();
+    assert_eq!(first.content_list, vec!["1行目\n2行目\n\n#新商品", "こんばんは", "追加1\n続き", "追加2"]);
+    assert_eq!(first.scheduled_times, "09:00,21:00");
+    let second = schedule_content::load_schedule_content(&conn, schedule_id(second_id)).unwrap();
+    assert_eq!(second.content_list, vec!["こんにちは"]);
+}
+
+#[tokio::test]
+async fn exports_schedules_and_filtered_logs_as_csv() {
+    let mock = MockTwitter::start().await;
+    mock.add_user("bot", "bot-token");
+    let state = test_state(&mock);
+    let account_id = add_account(&state, "bot", "bot-token");
+    let schedule_id = add_schedule(&state, account_id, &["朝の投稿", "夜の投稿"]);
+    scheduler::run_scheduler(&state).await.unwrap();
+    assert_eq!(current_index(&state, schedule_id), 1);
+
+    let conn = state.db.lock().unwrap();
+    reply_engine::write_log(&conn, account_id, "error", "error", "失敗".to_string(), None, None, Default::default()).unwrap();
+
+    let dir = TempDir::new();
+    let schedules_path = dir.write("schedules.csv", "");
+    let rows = csv_export::write_schedules_csv(&conn, &schedules_path, Some(account_id), Utc::now()).unwrap();
+    assert_eq!(rows, 2);
+    let schedules_csv = std::fs::read_to_string(&schedules_path).unwrap();
+    assert!(schedules_csv.starts_with('\u{feff}'));
+    let next_row = schedules_csv.lines().find(|l| l.contains("夜の投稿")).unwrap();
+    assert!(next_row.contains(",2,○,"), "{}", next_row);
+
+    let logs_path = dir.write("logs.csv", "");
+    let today = crate::reply_filters::to_jst(Utc::now()).format("%Y-%m-%d").to_string();
+    let filter = ExecutionLogFilter {
+        account_id: Some(account_id),
+        date_from: Some(today.clone()),
+        date_to: Some(today),
+        status: Some("success".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(csv_export::write_execution_logs_csv(&conn, &logs_path, &filter).unwrap(), 1);
+    assert!(std::fs::read_to_string(&logs_path).unwrap().contains("朝の投稿"));
+}
+
+#[tokio::test]
+async fn github_config_exports_schedule_columns() {
+    let mock = MockTwitter::start().await;
+    let state = test_state(&mock);
+    let account_id = add_account(&state, "poster", "poster-token");
+    let schedule_id = add_schedule(&state, account_id, &["朝の投稿", "昼の投稿"]);
+    add_reply_setting(&state, account_id, &[account_id], "返信です");
+    let conn = state.db.lock().unwrap();
+    // 後から追加された列に値が入っていても、スケジュールの列を取り違えない
+    conn.execute("UPDATE bot_accounts SET twitter_user_id = '12345', dry_run = 1 WHERE id = ?", params![account_id]).unwrap();
+    conn.execute("UPDATE scheduled_tweets SET current_index = 1 WHERE id = ?", params![schedule_id]).unwrap();
+
+    let config = crate::build_github_config(&conn).unwrap();
+    let bot = &config["bots"][0];
+    assert_eq!(bot["account"]["account_name"], "poster");
+    assert_eq!(bot["scheduled_content_list"], r#"["朝の投稿","昼の投稿"]"#);
+    assert_eq!(bot["current_index"], 1);
+    assert_eq!(bot["scheduled_times"], ALL_HOURS);
+    assert!(bot.get("scheduled_content").is_none());
+
+    let reply = &config["reply_settings"][0];
+    assert_eq!(reply["reply_content"], "返信です");
+    assert_eq!(reply["reply_bot_id"], account_id);
+}
+
+#[tokio::test]
+async fn github_config_exports_only_approved_content() {
+    let mock = MockTwitter::start().await;
+    let state = test_state(&mock);
+    let account_id = add_account(&state, "client", "client-token");
+    let schedule_id = add_schedule(&state, account_id, &["投稿A", "投稿B", "投稿C", "投稿D"]);
+    let conn = state.db.lock().unwrap();
+    conn.execute("UPDATE bot_accounts SET requires_approval = 1 WHERE id = ?", params![account_id]).unwrap();
+
+    // 承認済みの内容が無ければ投稿内容を書き出さない
+    let config = crate::build_github_config(&conn).unwrap();
+    let bot = &config["bots"][0];
+    assert!(bot["scheduled_content"].is_null());
+    assert!(bot.get("scheduled_content_list").is_none());
+
+    content_approval::set_review_state(&conn, schedule_id, &[0, 3], content_approval::APPROVED, None).unwrap();
+    content_approval::set_review_state(&conn, schedule_id, &[1], content_approval::REJECTED, Some("表現を修正".to_string())).unwrap();
+    conn.execute("UPDATE scheduled_tweets SET current_index = 1 WHERE id = ?", params![schedule_id]).unwrap();
+
+    // 次に投稿するのは承認済みの投稿D（書き出したリストの2番目）
+    let config = crate::build_github_config(&conn).unwrap();
+    let bot = &config["bots"][0];
+    assert_eq!(bot["scheduled_content_list"], r#"["投稿A","投稿D"]"#);
+    assert_eq!(bot["current_index"], 1);
+    assert_eq!(bot["scheduled_times"], ALL_HOURS);
+}
+
+#[tokio::test]
+async fn execution_logs_can_be_searched_and_paged() {
+    let mock = MockTwitter::start().await;
+    let state = test_state(&mock);
+    let account_id = add_account(&state, "bot", "bot-token");
+    let conn = state.db.lock().unwrap();
+    for i in 0..5 {
+        reply_engine::write_log(&conn, account_id, "tweet", "success", format!("投稿 {}", i), None, Some(format!("定期投稿 {}", i)), Default::default()).unwrap();
+    }
+    reply_engine::write_log(&conn, account_id, "error", "error", "投稿に失敗しました: 重複したツイートです".to_string(), None, None, Default::default()).unwrap();
+    reply_engine::write_log(&conn, account_id, "error", "error", "認証エラー".to_string(), None, None, Default::default()).unwrap();
+
+    let search = |search: &str| log_query::query_logs(&conn, &ExecutionLogQuery {
+        filter: ExecutionLogFilter { search: Some(search.to_string()), ..Default::default() },
+        ..Default::default()
+    }).unwrap().total_count;
+    assert_eq!(search("重複したツイート"), 1);
+    assert_eq!(search("定期投稿"), 5);
+    assert_eq!(search("認証"), 1);
+    assert_eq!(search("失敗 重複"), 1);
+
+    let mut query = ExecutionLogQuery {
+        filter: ExecutionLogFilter { log_type: Some("tweet".to_string()), ..Default::default() },
+        limit: Some(2),
+        ..Default::default()
+    };
+    let mut messages = Vec::new();
+    loop {
+        let page = log_query::query_logs(&conn, &query).unwrap();
+        assert_eq!(page.total_count, 5);
+        messages.extend(page.logs.into_iter().map(|log| log.message));
+        match page.next_cursor {
+            Some(cursor) => query.cursor = Some(cursor),
+            None => break,
+        }
+    }
+    assert_eq!(messages, vec!["投稿 4", "投稿 3", "投稿 2", "投稿 1", "投稿 0"]);
+}
+
+#[tokio::test]
+async fn log_compaction_archives_before_deleting() {
+    use std::io::BufRead;
+
+    let mock = MockTwitter::start().await;
+    let state = test_state(&mock);
+    let account_id = add_account(&state, "bot", "bot-token");
+    let mut conn = state.db.lock().unwrap();
+    for i in 0..4 {
+        let log_id = reply_engine::write_log(&conn, account_id, "tweet", "success", format!("古い投稿 {}", i), None, None, Default::default()).unwrap();
+        let created_at = (Utc::now() - Duration::days(40 + i)).to_rfc3339();
+        conn.execute("UPDATE execution_logs SET created_at = ? WHERE id = ?", params![created_at, log_id]).unwrap();
+    }
+    reply_engine::write_log(&conn, account_id, "tweet", "success", "新しい投稿".to_string(), None, None, Default::default()).unwrap();
+    crate::set_app_setting(&conn, "log_retention_days", "30").unwrap();
+
+    let dir = TempDir::new();
+    let archive_dir = dir.path().join("archive");
+    let now = Utc::now();
+    let summary = crate::log_retention::compact_logs(&mut conn, Some(&archive_dir), now).unwrap();
+    assert_eq!((summary.archived, summary.deleted), (4, 4));
+    assert!(summary.vacuumed);
+
+    let count_lines = |path: &str| {
+        let archive = std::fs::File::open(path).unwrap();
+        std::io::BufReader::new(flate2::read::GzDecoder::new(archive)).lines().count()
+    };
+    let first_path = summary.archive_path.unwrap();
+    assert_eq!(count_lines(&first_path), 4);
+
+    // 同じ秒に再実行しても既存のアーカイブを上書きしない
+    let log_id = reply_engine::write_log(&conn, account_id, "tweet", "success", "古い投稿 4".to_string(), None, None, Default::default()).unwrap();
+    let created_at = (now - Duration::days(50)).to_rfc3339();
+    conn.execute("UPDATE execution_logs SET created_at = ? WHERE id = ?", params![created_at, log_id]).unwrap();
+    let summary = crate::log_retention::compact_logs(&mut conn, Some(&archive_dir), now).unwrap();
+    assert_eq!((summary.archived, summary.deleted), (1, 1));
+    let second_path = summary.archive_path.unwrap();
+    assert_ne!(second_path, first_path);
+    assert_eq!(count_lines(&first_path), 4);
+    assert_eq!(count_lines(&second_path), 1);
+
+    let page = log_query::query_logs(&conn, &ExecutionLogQuery::default()).unwrap();
+    assert_eq!(page.logs.len(), 1);
+    assert_eq!(page.logs[0].message, "新しい投稿");
+    let search = ExecutionLogQuery {
+        filter: ExecutionLogFilter { search: Some("古い投稿".to_string()), ..Default::default() },
+        ..Default::default()
+    };
+    assert_eq!(log_query::query_logs(&conn, &search).unwrap().total_count, 0);
+}
+
+#[tokio::test]
+async fn logs_record_schedule_and_reply_correlation() {
+    let mock = MockTwitter::start().await;
+    let target_user_id = mock.add_user("target", "target-token");
+    let replier_user_id = mock.add_user("replier", "replier-token");
+    mock.add_tweet(&replier_user_id, "朝の投稿");
+    let state = test_state(&mock);
+    let target_id = add_account(&state, "target", "target-token");
+    let replier_id = add_account(&state, "replier", "replier-token");
+    let schedule_id = add_schedule(&state, replier_id, &["朝の投稿", "昼の投稿"]);
+    let setting_id = add_reply_setting(&state, replier_id, &[target_id], "返信です");
+    let tweet_id = mock.add_tweet(&target_user_id, "新しいツイート");
+
+    // 重複エラーになった投稿内容の位置と API の結果が残る
+    scheduler::run_scheduler(&state).await.unwrap();
+    reply_engine::run_reply_cycle(&state).await.unwrap();
+
+    let conn = state.db.lock().unwrap();
+    let query = |filter: ExecutionLogFilter| log_query::query_logs(&conn, &ExecutionLogQuery { filter, ..Default::default() }).unwrap().logs;
+
+    let failed = query(ExecutionLogFilter { schedule_id: Some(schedule_id), ..Default::default() });
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0].status, "error");
+    assert_eq!(failed[0].details.content_index, Some(0));
+    assert_eq!(failed[0].details.http_status, Some(403));
+    assert!(failed[0].details.duration_ms.is_some());
+
+    let replies = query(ExecutionLogFilter { reply_setting_id: Some(setting_id), ..Default::default() });
+    assert_eq!(replies.len(), 1);
+    assert_eq!(replies[0].details.target_tweet_id.as_deref(), Some(tweet_id.as_str()));
+    assert_eq!(replies[0].details.http_status, None);
+    let by_target = query(ExecutionLogFilter { target_tweet_id: Some(tweet_id.clone()), ..Default::default() });
+    assert_eq!(by_target.len(), 1);
+}
+
+#[tokio::test]
+async fn metrics_are_collected_per_content_item() {
+    let mock = MockTwitter::start().await;
+    let user_id = mock.add_user("poster", "poster-token");
+    let state = test_state(&mock);
+    let account_id = add_account(&state, "poster", "poster-token");
+    let schedule_id = add_schedule(&state, account_id, &["朝の投稿", "昼の投稿"]);
+
+    // 投稿リストの1件目を2回、2件目を1回投稿した状態
+    let posts = [(0, "朝の投稿", 4, 1000), (1, "昼の投稿", 30, 2000), (0, "朝の投稿 2", 6, 1000)];
+    let mut tweet_ids = Vec::new();
+    {
+        let conn = state.db.lock().unwrap();
+        for (index, text, likes, impressions) in posts {
+            let tweet_id = mock.add_tweet(&user_id, text);
+            mock.set_metrics(&tweet_id, likes, 0, 0, impressions);
+            reply_engine::write_log(&conn, account_id, "tweet", "success", "投稿しました".to_string(),
+                Some(tweet_id.clone()), Some(text.to_string()), LogDetails::schedule(schedule_id, index)).unwrap();
+            tweet_ids.push(tweet_id);
+        }
+    }
+
+    let first = tweet_metrics::collect_metrics(&state, true).await.unwrap();
+    assert_eq!((first.tweets_requested, first.snapshots_saved), (3, 3));
+    assert_eq!(mock.request_count(Method::GET, "/2/tweets"), 1);
+
+    // 取得間隔内のツイートは再取得しない
+    let again = tweet_metrics::collect_metrics(&state, true).await.unwrap();
+    assert_eq!(again.tweets_requested, 0);
+
+    mock.set_metrics(&tweet_ids[1], 50, 5, 5, 3000);
+    tweet_metrics::collect_metrics(&state, false).await.unwrap();
+
+    let conn = state.db.lock().unwrap();
+    let history = tweet_metrics::metrics_history(&conn, &tweet_ids[1]).unwrap();
+    assert_eq!(history.iter().map(|s| s.metrics.like_count).collect::<Vec<_>>(), [30, 50]);
+
+    let items = tweet_metrics::content_performance(&conn, None, Some(schedule_id), None).unwrap();
+    assert_eq!(items.iter().map(|c| c.content_index).collect::<Vec<_>>(), [1, 0]);
+    assert_eq!(items[0].totals.avg_engagements, 60.0);
+    assert_eq!(items[0].totals.engagement_rate, Some(0.02));
+    assert_eq!((items[1].totals.posts, items[1].totals.like_count), (2, 10));
+    assert_eq!(items[1].content.as_deref(), Some("朝の投稿 2"));
+
+    let accounts = tweet_metrics::account_performance(&conn, Some(7)).unwrap();
+    assert_eq!(accounts.len(), 1);
+    assert_eq!(accounts[0].totals.posts, 3);
+    assert_eq!(accounts[0].totals.impression_count, Some(5000));
+}
+
+#[tokio::test]
+async fn dashboard_series_is_bucketed_in_jst() {
+    let mock = MockTwitter::start().await;
+    let state = test_state(&mock);
+    let poster_id = add_account(&state, "poster", "poster-token");
+    let replier_id = add_account(&state, "replier", "replier-token");
+    add_schedule(&state, poster_id, &["朝の投稿", "昼の投稿"]);
+    let conn = state.db.lock().unwrap();
+
+    // UTC 15:30 は JST の翌日 0:30
+    let logs = [
+        (poster_id, "tweet", "success", "2024-03-01T15:30:00+00:00"),
+        (poster_id, "tweet", "success", "2024-03-01T16:10:00+00:00"),
+        (poster_id, "error", "error", "2024-03-02T01:00:00+00:00"),
+        (replier_id, "reply", "success", "2024-03-01T03:00:00+00:00"),
+        (replier_id, "reply", "skipped", "2024-03-01T03:05:00+00:00"),
+    ];
+    for (account_id, log_type, status, created_at) in logs {
+        let log_id = reply_engine::write_log(&conn, account_id, log_type, status, "ログ".to_string(), None, None, Default::default()).unwrap();
+        conn.execute("UPDATE execution_logs SET created_at = ? WHERE id = ?", params![created_at, log_id]).unwrap();
+    }
+
+    let now = chrono::DateTime::parse_from_rfc3339("2024-03-02T03:00:00+00:00").unwrap().with_timezone(&Utc);
+    let daily = dashboard_stats::dashboard_time_series(&conn, &DashboardStatsQuery {
+        date_from: Some("2024-03-01".to_string()),
+        date_to: Some("2024-03-03".to_string()),
+        ..Default::default()
+    }, now).unwrap();
+    let series: Vec<_> = daily.series.iter().map(|b| (b.bucket.as_str(), b.counts.posts, b.counts.replies, b.counts.errors)).collect();
+    assert_eq!(series, [("2024-03-01", 0, 1, 0), ("2024-03-02", 2, 0, 1), ("2024-03-03", 0, 0, 0)]);
+    assert_eq!(daily.totals.skipped, 1);
+
+    let poster = daily.accounts.iter().find(|a| a.account_id == poster_id).unwrap();
+    let rate = poster.success_rate.unwrap();
+    assert!((rate - 2.0 / 3.0).abs() < 1e-9);
+    let next = poster.next_scheduled_post.as_ref().unwrap();
+    assert_eq!(next.content, "朝の投稿");
+    assert!(next.scheduled_at.starts_with("2024-03-02T13:00:00"));
+    assert!(daily.accounts.iter().find(|a| a.account_id == replier_id).unwrap().next_scheduled_post.is_none());
+
+    let hourly = dashboard_stats::dashboard_time_series(&conn, &DashboardStatsQuery {
+        date_from: Some("2024-03-02".to_string()),
+        date_to: Some("2024-03-02".to_string()),
+        granularity: Some("hour".to_string()),
+        account_ids: Some(vec![poster_id]),
+    }, now).unwrap();
+    assert_eq!(hourly.series.len(), 24);
+    assert_eq!(hourly.series[0].bucket, "2024-03-02 00:00");
+    assert_eq!((hourly.series[0].counts.posts, hourly.series[1].counts.posts, hourly.series[10].counts.errors), (1, 1, 1));
+    assert_eq!(hourly.accounts.len(), 1);
+}
+
+#[tokio::test]
+async fn follower_snapshots_record_daily_deltas() {
+    let mock = MockTwitter::start().await;
+    let user_id = mock.add_user("bot", "bot-token");
+    let state = test_state(&mock);
+    let account_id = add_account(&state, "bot", "bot-token");
+    let day = |date: &str| chrono::DateTime::parse_from_rfc3339(&format!("{}T03:00:00+00:00", date)).unwrap().with_timezone(&Utc);
+
+    mock.set_user_metrics(&user_id, 100, 50, 10, 1);
+    follower_tracking::take_snapshots(&state, day("2024-03-01")).await.unwrap();
+    mock.set_user_metrics(&user_id, 120, 52, 12, 1);
+    follower_tracking::take_snapshots(&state, day("2024-03-02")).await.unwrap();
+    // 同じ日の再取得はその日の記録を上書きする
+    mock.set_user_metrics(&user_id, 125, 52, 13, 2);
+    let summary = follower_tracking::take_snapshots(&state, day("2024-03-02")).await.unwrap();
+    assert_eq!((summary.saved, summary.errors), (1, 0));
+
+    let conn = state.db.lock().unwrap();
+    let history = follower_tracking::follower_history(&conn, Some(account_id), 30, day("2024-03-02")).unwrap();
+    assert_eq!(history.len(), 1);
+    let snapshots: Vec<_> = history[0].snapshots.iter()
+        .map(|s| (s.snapshot_date.as_str(), s.metrics.followers_count, s.followers_delta))
+        .collect();
+    assert_eq!(snapshots, [("2024-03-01", 100, None), ("2024-03-02", 125, Some(25))]);
+    assert_eq!(history[0].followers_change, Some(25));
+
+    // 期間外の前回の記録からの増減も返す
+    let latest = follower_tracking::follower_history(&conn, Some(account_id), 1, day("2024-03-02")).unwrap();
+    assert_eq!(latest[0].snapshots.len(), 1);
+    assert_eq!(latest[0].snapshots[0].followers_delta, Some(25));
+    assert_eq!(latest[0].followers_change, None);
+
+    let cached: Option<String> = conn.query_row("SELECT twitter_user_id FROM bot_accounts WHERE id = ?", params![account_id], |row| row.get(0)).unwrap();
+    assert_eq!(cached, Some(user_id));
+}
+
+#[tokio::test]
+async fn repeated_auth_errors_disable_account_until_verified() {
+    let mock = MockTwitter::start().await;
+    let state = test_state(&mock);
+    let account_id = add_account(&state, "revoked", "revoked-token");
+    let target_id = add_account(&state, "target", "target-token");
+    let schedule_id = add_schedule(&state, account_id, &["朝の投稿"]);
+    let setting_id = add_reply_setting(&state, account_id, &[target_id], "返信です");
+    let is_active = |table: &str, id: i64| -> bool {
+        state.db.lock().unwrap()
+            .query_row(&format!("SELECT is_active FROM {} WHERE id = ?", table), params![id], |row| row.get(0)).unwrap()
+    };
+
+    // 存在しないトークンは 401 になる
+    let first = scheduler::run_scheduler(&state).await.unwrap();
+    assert_eq!(first.errors, 1);
+    assert!(is_active("scheduled_tweets", schedule_id));
+
+    let verification = account_health::verify_account(&state, account_id).await.unwrap();
+    assert!(!verification.verified);
+    {
+        let conn = state.db.lock().unwrap();
+        let health = account_health::load_account_health(&conn).unwrap();
+        let revoked = health.iter().find(|h| h.account_id == account_id).unwrap();
+        assert_eq!(revoked.status, "error");
+        assert_eq!(revoked.failure_kind.as_deref(), Some("auth"));
+        assert!(revoked.disabled_reason.is_some());
+        assert_eq!((revoked.paused_schedules, revoked.paused_reply_settings), (1, 1));
+    }
+    assert!(!is_active("scheduled_tweets", schedule_id));
+    assert!(!is_active("reply_settings", setting_id));
+
+    // 停止中は投稿しない
+    let stopped = scheduler::run_scheduler(&state).await.unwrap();
+    assert_eq!(stopped.schedules_due, 0);
+
+    mock.add_user("revoked", "revoked-token");
+    let verification = account_health::verify_account(&state, account_id).await.unwrap();
+    assert!(verification.verified && verification.reactivated);
+    assert!(is_active("scheduled_tweets", schedule_id));
+    assert!(is_active("reply_settings", setting_id));
+    let status: String = state.db.lock().unwrap()
+        .query_row("SELECT status FROM bot_accounts WHERE id = ?", params![account_id], |row| row.get(0)).unwrap();
+    assert_eq!(status, "active");
+}
+
+#[tokio::test]
+async fn only_posting_rate_limits_count_toward_auto_disable() {
+    let mock = MockTwitter::start().await;
+    let state = test_state(&mock);
+    let account_id = add_account(&state, "bot", "bot-token");
+    let conn = state.db.lock().unwrap();
+    let error_log = |details: LogDetails, tweet_content: Option<&str>| crate::ExecutionLog {
+        id: None,
+        account_id,
+        log_type: "error".to_string(),
+        message: "失敗しました".to_string(),
+        tweet_id: None,
+        tweet_content: tweet_content.map(str::to_string),
+        status: "error".to_string(),
+        created_at: Utc::now().to_rfc3339(),
+        details,
+    };
+    let health = |conn: &rusqlite::Connection| {
+        account_health::load_account_health(conn).unwrap()
+            .into_iter().find(|h| h.account_id == account_id).unwrap()
+    };
+
+    // タイムライン・メンション・フォロワー数の取得で受けたレート制限は数えない
+    for _ in 0..6 {
+        let read = LogDetails { http_status: Some(429), ..Default::default() };
+        account_health::observe_log(&conn, &error_log(read, None)).unwrap();
+    }
+    // client-forbidden はアプリ側の設定の問題で、凍結ではない
+    let forbidden = LogDetails { http_status: Some(403), api_error_code: Some("client-forbidden".to_string()), ..Default::default() };
+    for _ in 0..2 {
+        account_health::observe_log(&conn, &error_log(forbidden.clone(), Some("投稿"))).unwrap();
+    }
+    assert_eq!(health(&conn).status, "active");
+    assert_eq!(health(&conn).consecutive_failures, 0);
+
+    for _ in 0..5 {
+        let post = LogDetails { schedule_id: Some(1), http_status: Some(429), ..Default::default() };
+        account_health::observe_log(&conn, &error_log(post, Some("投稿"))).unwrap();
+    }
+    assert_eq!(health(&conn).status, "error");
+    assert_eq!(health(&conn).failure_kind.as_deref(), Some("rate_limit"));
+}
+
+#[tokio::test]
+async fn failures_and_auto_disable_are_notified_once() {
+    let mock = MockTwitter::start().await;
+    let state = test_state(&mock);
+    let account_id = add_account(&state, "revoked", "revoked-token");
+    add_schedule(&state, account_id, &["朝の投稿"]);
+
+    scheduler::run_scheduler(&state).await.unwrap();
+    account_health::verify_account(&state, account_id).await.unwrap();
+
+    let conn = state.db.lock().unwrap();
+    let history = notifications::load_notifications(&conn, true, 10).unwrap();
+    let kinds: Vec<&str> = history.iter().map(|n| n.kind.as_str()).collect();
+    assert_eq!(kinds, vec!["account_disabled", "post_failed"]);
+    assert!(history.iter().all(|n| n.account_name.as_deref() == Some("revoked")));
+
+    // 同じアカウントの投稿失敗は一定時間内には再通知しない
+    let log = crate::ExecutionLog {
+        id: None,
+        account_id,
+        log_type: "error".to_string(),
+        message: "再度失敗".to_string(),
+        tweet_id: None,
+        tweet_content: None,
+        status: "error".to_string(),
+        created_at: Utc::now().to_rfc3339(),
+        details: LogDetails { schedule_id: Some(1), ..Default::default() },
+    };
+    notifications::observe_log(&conn, &log).unwrap();
+    assert_eq!(notifications::load_notifications(&conn, false, 10).unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn notifications_are_sent_after_commit() {
+    let mock = MockTwitter::start().await;
+    let state = test_state(&mock);
+    let account_id = add_account(&state, "notify-after-commit", "notify-token");
+    let mut conn = state.db.lock().unwrap();
+    let mut receiver = notifications::subscribe();
+    // 並行するテストの通知も流れるため、このアカウントのものだけを数える
+    let received = |receiver: &mut tokio::sync::broadcast::Receiver<notifications::Notification>| {
+        std::iter::from_fn(|| receiver.try_recv().ok())
+            .filter(|n| n.account_name.as_deref() == Some("notify-after-commit"))
+            .count()
+    };
+    let post_failed = |tx: &rusqlite::Connection| {
+        reply_engine::write_log(tx, account_id, "error", "error", "投稿に失敗しました".to_string(), None, Some("投稿".to_string()),
+            LogDetails { schedule_id: Some(1), ..Default::default() }).unwrap();
+    };
+
+    {
+        let tx = crate::after_commit::begin(&mut conn).unwrap();
+        post_failed(&tx);
+    }
+    assert_eq!(received(&mut receiver), 0);
+    assert!(notifications::load_notifications(&conn, false, 10).unwrap().is_empty());
+
+    let tx = crate::after_commit::begin(&mut conn).unwrap();
+    post_failed(&tx);
+    assert_eq!(received(&mut receiver), 0);
+    tx.commit().unwrap();
+    assert_eq!(received(&mut receiver), 1);
+}
+
+// 受け取った Webhook（ヘッダーと本文）を記録するローカルの受信サーバー
+// 最初の fail_first 件には 500 を返す
+#[derive(Clone)]
+struct WebhookReceiver {
+    received: Arc<Mutex<Vec<(axum::http::HeaderMap, String)>>>,
+    fail_remaining: Arc<AtomicUsize>,
+}
+
+async fn start_webhook_receiver(fail_first: usize) -> (String, WebhookReceiver) {
+    let receiver = WebhookReceiver {
+        received: Arc::new(Mutex::new(Vec::new())),
+        fail_remaining: Arc::new(AtomicUsize::new(fail_first)),
+    };
+    let handle = |axum::extract::State(receiver): axum::extract::State<WebhookReceiver>, headers: axum::http::HeaderMap, body: String| async move {
+        if receiver.fail_remaining.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1)).is_ok() {
+            return StatusCode::INTERNAL_SERVER_ERROR;
+        }
+        receiver.received.lock().unwrap().push((headers, body));
+        StatusCode::OK
+    };
+    let app = axum::Router::new().fallback(handle).with_state(receiver.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    (url, receiver)
+}
+
+#[tokio::test]
+async fn webhooks_are_signed_and_retried() {
+    let mock = MockTwitter::start().await;
+    mock.add_user("poster", "poster-token");
+    let state = test_state(&mock);
+    let account_id = add_account(&state, "poster", "poster-token");
+    add_schedule(&state, account_id, &["朝の投稿"]);
+    let (url, receiver) = start_webhook_receiver(1).await;
+
+    let save = |name: &str, format: &str, events: &[&str], secret: Option<&str>| {
+        let request = WebhookRequest {
+            id: None,
+            name: name.to_string(),
+            url: url.clone(),
+            format: format.to_string(),
+            events: events.iter().map(|e| e.to_string()).collect(),
+            secret: secret.map(str::to_string),
+            is_active: true,
+        };
+        webhooks::save_webhook_row(&state.db.lock().unwrap(), &request).unwrap()
+    };
+    let slack_id = save("team", "slack", &["post_succeeded"], None);
+    save("signed", "json", &["post_succeeded", "post_failed"], Some("s3cret"));
+    save("failures", "discord", &["post_failed"], None);
+
+    scheduler::run_scheduler(&state).await.unwrap();
+
+    // Slack 宛ての最初の送信は 500 で失敗し、再送時刻まで待つ
+    let now = Utc::now();
+    let first = webhooks::deliver_pending(&state, now).await.unwrap();
+    assert_eq!((first.attempted, first.delivered, first.retrying), (2, 1, 1));
+    assert_eq!(webhooks::deliver_pending(&state, now).await.unwrap().attempted, 0);
+    let retried = webhooks::deliver_pending(&state, now + Duration::minutes(2)).await.unwrap();
+    assert_eq!(retried.delivered, 1);
+
+    let received = receiver.received.lock().unwrap().clone();
+    assert_eq!(received.len(), 2);
+    let (headers, body) = &received[0];
+    let payload: serde_json::Value = serde_json::from_str(body).unwrap();
+    assert_eq!(payload["event"], "post_succeeded");
+    assert_eq!(payload["account_name"], "poster");
+    assert_eq!(payload["tweet_content"], "朝の投稿");
+    let timestamp: i64 = headers["x-webhook-timestamp"].to_str().unwrap().parse().unwrap();
+    assert_eq!(
+        headers["x-webhook-signature"].to_str().unwrap(),
+        format!("sha256={}", webhooks::sign("s3cret", timestamp, body))
+    );
+
+    let (headers, body) = &received[1];
+    assert!(!headers.contains_key("x-webhook-signature"));
+    let slack: serde_json::Value = serde_json::from_str(body).unwrap();
+    assert!(slack["text"].as_str().unwrap().starts_with("【投稿成功】poster: "));
+
+    let deliveries = webhooks::load_deliveries(&state.db.lock().unwrap(), Some(slack_id), 10).unwrap();
+    assert_eq!(deliveries.len(), 1);
+    assert_eq!((deliveries[0].status.as_str(), deliveries[0].attempts), ("success", 2));
+}
+
+#[tokio::test]
+async fn webhook_deliveries_are_claimed_once() {
+    let mock = MockTwitter::start().await;
+    let state = test_state(&mock);
+    let account_id = add_account(&state, "poster", "poster-token");
+    let (url, receiver) = start_webhook_receiver(0).await;
+    let request = WebhookRequest {
+        id: None,
+        name: "team".to_string(),
+        url,
+        format: "json".to_string(),
+        events: vec!["account_error".to_string()],
+        secret: None,
+        is_active: true,
+    };
+    let webhook_id = webhooks::save_webhook_row(&state.db.lock().unwrap(), &request).unwrap();
+    {
+        let conn = state.db.lock().unwrap();
+        for i in 0..3 {
+            let payload = webhooks::payload(&conn, WebhookEvent::AccountError, Some(account_id), &format!("エラー {}", i));
+            webhooks::enqueue(&conn, payload, None).unwrap();
+        }
+    }
+
+    // テスト送信は自分の送信だけを送り、他の送信待ちには触れない
+    let test = webhooks::send_test(&state, webhook_id).await.unwrap();
+    assert_eq!((test.event.as_str(), test.status.as_str()), ("test", "success"));
+    assert_eq!(receiver.received.lock().unwrap().len(), 1);
+
+    // 同時に送っても同じ送信を二重に送らない
+    let now = Utc::now();
+    let (first, second) = tokio::join!(webhooks::deliver_pending(&state, now), webhooks::deliver_pending(&state, now));
+    assert_eq!(first.unwrap().delivered + second.unwrap().delivered, 3);
+    assert_eq!(receiver.received.lock().unwrap().len(), 4);
+    let deliveries = webhooks::load_deliveries(&state.db.lock().unwrap(), Some(webhook_id), 10).unwrap();
+    assert!(deliveries.iter().all(|d| d.status == "success" && d.attempts == 1));
+}
+
+#[tokio::test]
+async fn local_api_requires_token_and_drives_schedules() {
+    let mock = MockTwitter::start().await;
+    mock.add_user("cms", "cms-token");
+    let state = Arc::new(test_state(&mock));
+    let account_id = add_account(&state, "cms", "cms-token");
+    let token = local_api::regenerate_token(&state.db.lock().unwrap()).unwrap();
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}/api", listener.local_addr().unwrap());
+    let router = local_api::router(state.clone());
+    tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+    let client = reqwest::Client::new();
+
+    let unauthorized = client.get(format!("{}/accounts", base)).bearer_auth("wrong").send().await.unwrap();
+    assert_eq!(unauthorized.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    let accounts: serde_json::Value = client.get(format!("{}/accounts", base)).bearer_auth(&token)
+        .send().await.unwrap().json().await.unwrap();
+    assert_eq!(accounts[0]["account_name"], "cms");
+    assert!(accounts[0].get("access_token").is_none());
+
+    let saved: serde_json::Value = client.put(format!("{}/accounts/{}/schedule", base, account_id)).bearer_auth(&token)
+        .json(&serde_json::json!({ "scheduled_times": "09:00,18:00", "content_list": ["記事A", "記事B"] }))
+        .send().await.unwrap().json().await.unwrap();
+    let schedule_id = saved[0]["id"].as_i64().unwrap();
+
+    let appended: serde_json::Value = client.post(format!("{}/schedules/{}/content", base, schedule_id)).bearer_auth(&token)
+        .json(&serde_json::json!({ "content": "記事C" }))
+        .send().await.unwrap().json().await.unwrap();
+    let content_list: Vec<String> = serde_json::from_str(appended["content_list"].as_str().unwrap()).unwrap();
+    assert_eq!(content_list, vec!["記事A", "記事B", "記事C"]);
+
+    let missing = client.put(format!("{}/accounts/9999/schedule", base)).bearer_auth(&token)
+        .json(&serde_json::json!({ "scheduled_times": "09:00", "content_list": ["x"] }))
+        .send().await.unwrap();
+    assert_eq!(missing.status(), reqwest::StatusCode::NOT_FOUND);
+
+    let posted: serde_json::Value = client.post(format!("{}/tweets", base)).bearer_auth(&token)
+        .json(&serde_json::json!({ "account_id": account_id, "content": "今すぐ投稿" }))
+        .send().await.unwrap().json().await.unwrap();
+    assert_eq!(posted["success"], true);
+    assert_eq!(mock.posted_tweets()[0].text, "今すぐ投稿");
+
+    let logs: serde_json::Value = client.get(format!("{}/logs?account_id={}&limit=5", base, account_id)).bearer_auth(&token)
+        .send().await.unwrap().json().await.unwrap();
+    assert_eq!(logs[0]["tweet_content"], "今すぐ投稿");
+}
+
+#[tokio::test]
+async fn scheduled_post_emits_events_in_order() {
+    let mock = MockTwitter::start().await;
+    mock.add_user("live", "live-token");
+    let state = test_state(&mock);
+    let account_id = add_account(&state, "live", "live-token");
+    let schedule_id = add_schedule(&state, account_id, &["イベント確認の投稿"]);
+    let mut events = app_events::subscribe();
+
+    scheduler::run_scheduler(&state).await.unwrap();
+
+    // 並行するテストのイベントも流れるため、この投稿内容のものだけを見る
+    let mut names = Vec::new();
+    while let Ok(event) = events.try_recv() {
+        let matched = match &event {
+            AppEvent::ScheduleFired(fired) => fired.schedule_id == schedule_id && fired.content == "イベント確認の投稿",
+            AppEvent::LogAdded(log) => log.tweet_content.as_deref() == Some("イベント確認の投稿") && log.id.is_some(),
+            AppEvent::PostSent(sent) => sent.content.as_deref() == Some("イベント確認の投稿") && sent.tweet_id.is_some(),
+            _ => false,
+        };
+        if matched {
+            names.push(event.name());
+        }
+    }
+    assert_eq!(names, vec!["schedule_fired", "log_added", "post_sent"]);
+}
+
+#[tokio::test]
+async fn log_events_are_published_after_commit() {
+    let mock = MockTwitter::start().await;
+    let state = test_state(&mock);
+    let account_id = add_account(&state, "bot", "bot-token");
+    let mut conn = state.db.lock().unwrap();
+    let mut events = app_events::subscribe();
+    // 並行するテストのイベントも流れるため、このテストのログだけを見る
+    let received = |events: &mut tokio::sync::broadcast::Receiver<AppEvent>| {
+        let mut messages = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            if let AppEvent::LogAdded(log) = event {
+                if log.message == "取り消される投稿" || log.message == "確定する投稿" {
+                    messages.push(log.message);
+                }
+            }
+        }
+        messages
+    };
+
+    // ロールバックされたログのイベントは流さない
+    {
+        let tx = crate::after_commit::begin(&mut conn).unwrap();
+        reply_engine::write_log(&tx, account_id, "tweet", "success", "取り消される投稿".to_string(), None, None, Default::default()).unwrap();
+    }
+    assert!(received(&mut events).is_empty());
+
+    let tx = crate::after_commit::begin(&mut conn).unwrap();
+    reply_engine::write_log(&tx, account_id, "tweet", "success", "確定する投稿".to_string(), None, None, Default::default()).unwrap();
+    assert!(received(&mut events).is_empty());
+    tx.commit().unwrap();
+    assert_eq!(received(&mut events), vec!["確定する投稿"]);
+}
+
+#[tokio::test]
+async fn scheduler_posts_only_approved_content() {
+    let mock = MockTwitter::start().await;
+    mock.add_user("client", "client-token");
+    let state = test_state(&mock);
+    let account_id = add_account(&state, "client", "client-token");
+    let schedule_id = add_schedule(&state, account_id, &["投稿A", "投稿B", "投稿C"]);
+    let reset_slot = || {
+        state.db.lock().unwrap()
+            .execute("UPDATE scheduled_tweets SET last_fired_slot = NULL WHERE id = ?", params![schedule_id]).unwrap();
+    };
+    state.db.lock().unwrap()
+        .execute("UPDATE bot_accounts SET requires_approval = 1 WHERE id = ?", params![account_id]).unwrap();
+
+    // 承認済みの内容が無い間は投稿しない
+    let skipped = scheduler::run_scheduler(&state).await.unwrap();
+    assert_eq!(skipped.schedules_due, 0);
+    assert!(mock.posted_tweets().is_empty());
+
+    let pending = vec![content_approval::PENDING.to_string()];
+    {
+        let conn = state.db.lock().unwrap();
+        assert_eq!(content_approval::review_queue(&conn, None, &pending).unwrap().len(), 3);
+        assert!(content_approval::set_review_state(&conn, schedule_id, &[0], content_approval::REJECTED, None).is_err());
+        content_approval::set_review_state(&conn, schedule_id, &[0], content_approval::REJECTED, Some("表現を修正".to_string())).unwrap();
+        content_approval::set_review_state(&conn, schedule_id, &[1], content_approval::APPROVED, None).unwrap();
+    }
+
+    // 先頭の却下された内容を飛ばし、承認済みの内容を投稿する
+    reset_slot();
+    let posted = scheduler::run_scheduler(&state).await.unwrap();
+    assert_eq!(posted.posted, 1);
+    assert_eq!(mock.posted_tweets()[0].text, "投稿B");
+    assert_eq!(current_index(&state, schedule_id), 2);
+
+    let conn = state.db.lock().unwrap();
+    let queue = content_approval::review_queue(&conn, Some(account_id), &pending).unwrap();
+    assert_eq!(queue.iter().map(|r| r.content.as_str()).collect::<Vec<_>>(), vec!["投稿C"]);
+    let reviews = content_approval::schedule_reviews(&conn, schedule_id).unwrap();
+    assert_eq!(reviews[0].reviewer_note.as_deref(), Some("表現を修正"));
+}
+
+#[tokio::test]
+async fn pauses_and_blackouts_skip_automated_posts() {
+    let mock = MockTwitter::start().await;
+    let target_user_id = mock.add_user("target", "target-token");
+    mock.add_user("replier", "replier-token");
+    let state = test_state(&mock);
+    let target_id = add_account(&state, "target", "target-token");
+    let replier_id = add_account(&state, "replier", "replier-token");
+    let schedule_id = add_schedule(&state, replier_id, &["定期投稿"]);
+    add_reply_setting(&state, replier_id, &[target_id], "返信です");
+    mock.add_tweet(&target_user_id, "新しいツイート");
+    let now = Utc::now();
+
+    // 全体の一時停止中はスケジュール投稿も返信もスキップとして記録する
+    posting_pause::set_global_pause(&state.db.lock().unwrap(), Some((Some("障害対応".to_string()), None)), now).unwrap();
+    assert_eq!(scheduler::run_scheduler(&state).await.unwrap().schedules_due, 0);
+    assert_eq!(reply_engine::run_reply_cycle(&state).await.unwrap().replies_sent, 0);
+    assert!(mock.posted_tweets().is_empty());
+    assert_eq!(log_statuses(&state, replier_id), ["skipped", "skipped"]);
+
+    {
+        let conn = state.db.lock().unwrap();
+        posting_pause::set_global_pause(&conn, None, now).unwrap();
+
+        // 再開日時を過ぎたアカウントの一時停止は自動で解除される
+        let resume_at = (now + Duration::hours(1)).to_rfc3339();
+        posting_pause::set_account_pause(&conn, replier_id, None, Some(resume_at), now).unwrap();
+        assert!(posting_pause::pause_reason(&conn, replier_id, now).unwrap().is_some());
+        assert_eq!(posting_pause::pause_reason(&conn, replier_id, now + Duration::hours(2)).unwrap(), None);
+        assert!(posting_pause::load_pause_status(&conn, now).unwrap().accounts.is_empty());
+
+        // 停止期間は指定したアカウントだけに効く
+        let starts_at = (now - Duration::minutes(5)).to_rfc3339();
+        let ends_at = (now + Duration::days(1)).to_rfc3339();
+        assert!(posting_pause::save_blackout(&conn, None, "年末年始", Some(target_id), &ends_at, &starts_at).is_err());
+        posting_pause::save_blackout(&conn, None, "年末年始", Some(target_id), &starts_at, &ends_at).unwrap();
+        assert_eq!(posting_pause::pause_reason(&conn, target_id, now).unwrap().as_deref(), Some("停止期間「年末年始」中"));
+        assert_eq!(posting_pause::pause_reason(&conn, replier_id, now).unwrap(), None);
+        conn.execute("UPDATE scheduled_tweets SET last_fired_slot = NULL WHERE id = ?", params![schedule_id]).unwrap();
+    }
+
+    let resumed = scheduler::run_scheduler(&state).await.unwrap();
+    assert_eq!(resumed.posted, 1);
+    assert_eq!(mock.posted_tweets()[0].text, "定期投稿");
+}
+
+#[tokio::test]
+async fn replies_held_during_pause_are_sent_after_resume() {
+    let mock = MockTwitter::start().await;
+    let target_user_id = mock.add_user("target", "target-token");
+    mock.add_user("replier", "replier-token");
+    let state = test_state(&mock);
+    let target_id = add_account(&state, "target", "target-token");
+    let replier_id = add_account(&state, "replier", "replier-token");
+    let setting_id = add_reply_setting(&state, replier_id, &[target_id], "返信です");
+    let first_tweet_id = mock.add_tweet(&target_user_id, "停止中のツイート1");
+    let last_tweet_id = mock.add_tweet(&target_user_id, "停止中のツイート2");
+    let now = Utc::now();
+    posting_pause::set_account_pause(&state.db.lock().unwrap(), replier_id, None, None, now).unwrap();
+
+    // 停止中は返信せず、チェックポイントも進めない（記録は1回だけ）
+    let paused = reply_engine::run_reply_cycle(&state).await.unwrap();
+    assert_eq!((paused.replies_sent, paused.skipped), (0, 0));
+    assert!(mock.posted_tweets().is_empty());
+    assert_eq!(last_seen(&state, setting_id), None);
+    assert_eq!(log_statuses(&state, replier_id), ["skipped"]);
+
+    // 再開後の実行では保留したツイートに返信する
+    state.db.lock().unwrap()
+        .execute("DELETE FROM account_pauses WHERE account_id = ?", params![replier_id]).unwrap();
+    // （同じ返信内容の2件目はモックが重複投稿として拒否する）
+    let resumed = reply_engine::run_reply_cycle(&state).await.unwrap();
+    assert_eq!(resumed.replies_sent, 1);
+    assert_eq!(mock.posted_tweets()[0].in_reply_to_tweet_id.as_deref(), Some(first_tweet_id.as_str()));
+    assert_eq!(last_seen(&state, setting_id).as_deref(), Some(last_tweet_id.as_str()));
+}
+
+#[tokio::test]
+async fn mention_auto_replies_held_during_pause_are_sent_after_resume() {
+    let mock = MockTwitter::start().await;
+    let fan_user_id = mock.add_user("fan", "fan-token");
+    mock.add_user("bot", "bot-token");
+    let state = test_state(&mock);
+    let bot_id = add_account(&state, "bot", "bot-token");
+    let now = Utc::now();
+    {
+        let conn = state.db.lock().unwrap();
+        let created_at = now.to_rfc3339();
+        conn.execute(
+            "INSERT INTO mention_settings (account_id, is_enabled, auto_reply_enabled, auto_reply_since, created_at, updated_at) VALUES (?, 1, 1, ?, ?, ?)",
+            params![bot_id, (now - Duration::hours(1)).to_rfc3339(), created_at, created_at],
+        ).unwrap();
+        conn.execute(
+            "INSERT INTO mention_reply_rules (account_id, reply_content, keywords, created_at, updated_at) VALUES (?, 'お答えします', '[\"質問\"]', ?, ?)",
+            params![bot_id, created_at, created_at],
+        ).unwrap();
+        posting_pause::set_account_pause(&conn, bot_id, None, None, now).unwrap();
+    }
+    let mention_id = mock.add_tweet(&fan_user_id, "@bot 質問があります");
+
+    let paused = mentions::run_mention_poll(&state, false).await.unwrap();
+    assert_eq!((paused.new_mentions, paused.auto_replies), (1, 0));
+    assert!(mock.posted_tweets().is_empty());
+
+    // 再開後のポーリングでは新しいメンションが無くても保留した返信を送る
+    state.db.lock().unwrap()
+        .execute("DELETE FROM account_pauses WHERE account_id = ?", params![bot_id]).unwrap();
+    let resumed = mentions::run_mention_poll(&state, false).await.unwrap();
+    assert_eq!((resumed.new_mentions, resumed.auto_replies), (0, 1));
+    let posted = mock.posted_tweets();
+    assert_eq!(posted.len(), 1);
+    assert_eq!(posted[0].text, "お答えします");
+    assert_eq!(posted[0].in_reply_to_tweet_id.as_deref(), Some(mention_id.as_str()));
+
+    let again = mentions::run_mention_poll(&state, false).await.unwrap();
+    assert_eq!(again.auto_replies, 0);
+}
+
+#[tokio::test]
+async fn reply_cycle_replies_to_new_tweets_once() {
+    let mock = MockTwitter::start().await;
+    let target_user_id = mock.add_user("target", "target-token");
+    mock.add_user("replier", "replier-token");
+    let state = test_state(&mock);
+    let target_id = add_account(&state, "target", "target-token");
+    let replier_id = add_account(&state, "replier", "replier-token");
+    let setting_id = add_reply_setting(&state, replier_id, &[target_id], "返信です");
+
+    let tweet_id = mock.add_tweet(&target_user_id, "新しいツイート");
+    // 返信・リツイートは既定のフィルタで除外される
+    mock.add_tweet_with_references(&target_user_id, "RT 他のツイート", &[("retweeted", "1")]);
+
+    let first = reply_engine::run_reply_cycle(&state).await.unwrap();
+    assert_eq!(first.replies_sent, 1);
+    let posted = mock.posted_tweets();
+    assert_eq!(posted.len(), 1);
+    assert_eq!(posted[0].text, "返信です");
+    assert_eq!(posted[0].in_reply_to_tweet_id.as_deref(), Some(tweet_id.as_str()));
+    assert_eq!(last_seen(&state, setting_id).as_deref(), Some(tweet_id.as_str()));
+
+    let second = reply_engine::run_reply_cycle(&state).await.unwrap();
+    assert_eq!(second.replies_sent, 0);
+    assert_eq!(mock.posted_tweets().len(), 1);
+}
+
+#[tokio::test]
+async fn rate_limited_reply_is_retried_next_cycle() {
+    let mock = MockTwitter::start().await;
+    let target_user_id = mock.add_user("target", "target-token");
+    mock.add_user("replier", "replier-token");
+    mock.add_user("other", "other-token");
+    let state = test_state(&mock);
+    let target_id = add_account(&state, "target", "target-token");
+    let replier_id = add_account(&state, "replier", "replier-token");
+    let other_id = add_account(&state, "other", "other-token");
+    let setting_id = add_reply_setting(&state, replier_id, &[target_id], "返信です");
+    let other_setting_id = add_reply_setting(&state, other_id, &[target_id], "別の返信です");
+    let tweet_id = mock.add_tweet(&target_user_id, "新しいツイート");
+
+    mock.fail_next(Method::POST, "/2/tweets", StatusCode::TOO_MANY_REQUESTS, "Too Many Requests");
+    let limited = reply_engine::run_reply_cycle(&state).await.unwrap();
+    assert_eq!((limited.replies_sent, limited.errors), (1, 1));
+    assert_eq!(last_seen(&state, setting_id), None);
+    // レート制限を受けたアカウントだけを解除日時まで見送り、他のアカウントは返信する
+    assert_eq!(last_seen(&state, other_setting_id).as_deref(), Some(tweet_id.as_str()));
+    let retry_key = format!("reply_monitor_retry_after:{}", replier_id);
+    assert!(crate::get_app_setting(&state.db.lock().unwrap(), &retry_key).is_some());
+    assert!(crate::get_app_setting(&state.db.lock().unwrap(), &format!("reply_monitor_retry_after:{}", other_id)).is_none());
+
+    let waiting = reply_engine::run_reply_cycle(&state).await.unwrap();
+    assert_eq!((waiting.replies_sent, waiting.errors, waiting.settings_processed), (0, 0, 1));
+    assert_eq!(last_seen(&state, setting_id), None);
+
+    // 解除日時を過ぎると再試行する
+    crate::set_app_setting(&state.db.lock().unwrap(), &retry_key, &(Utc::now() - Duration::minutes(1)).to_rfc3339()).unwrap();
+    let retried = reply_engine::run_reply_cycle(&state).await.unwrap();
+    assert_eq!(retried.replies_sent, 1);
+    let posted = mock.posted_tweets();
+    assert_eq!(posted.len(), 2);
+    assert!(posted.iter().all(|t| t.in_reply_to_tweet_id.as_deref() == Some(tweet_id.as_str())));
+    assert_eq!(last_seen(&state, setting_id).as_deref(), Some(tweet_id.as_str()));
+}
+
+#[tokio::test]
+async fn transient_reply_error_is_retried_and_bursts_are_paged() {
+    let mock = MockTwitter::start().await;
+    let target_user_id = mock.add_user("target", "target-token");
+    mock.add_user("replier", "replier-token");
+    let state = test_state(&mock);
+    let target_id = add_account(&state, "target", "target-token");
+    let replier_id = add_account(&state, "replier", "replier-token");
+    let setting_id = add_reply_setting(&state, replier_id, &[target_id], "返信です");
+    let filters = crate::reply_filters::ReplyFilters { include_keywords: vec!["対象".to_string()], ..Default::default() };
+    state.db.lock().unwrap()
+        .execute("UPDATE reply_settings SET reply_filters = ? WHERE id = ?", params![serde_json::to_string(&filters).unwrap(), setting_id]).unwrap();
+
+    // 1回目でチェックポイントを作る
+    let first_id = mock.add_tweet(&target_user_id, "雑談");
+    reply_engine::run_reply_cycle(&state).await.unwrap();
+    assert_eq!(last_seen(&state, setting_id).as_deref(), Some(first_id.as_str()));
+
+    // 1ページに収まらない新着の最も古いツイートだけが返信対象
+    let target_tweet_id = mock.add_tweet(&target_user_id, "対象のツイート");
+    for i in 0..104 {
+        mock.add_tweet(&target_user_id, &format!("雑談 {}", i));
+    }
+
+    // 一時的なエラー（503）ではチェックポイントを進めない
+    mock.fail_next(Method::POST, "/2/tweets", StatusCode::SERVICE_UNAVAILABLE, "Service Unavailable");
+    let failed = reply_engine::run_reply_cycle(&state).await.unwrap();
+    assert_eq!((failed.replies_sent, failed.errors), (0, 1));
+    assert_eq!(last_seen(&state, setting_id).as_deref(), Some(first_id.as_str()));
+
+    let retried = reply_engine::run_reply_cycle(&state).await.unwrap();
+    assert_eq!(retried.replies_sent, 1);
+    assert_eq!(retried.skipped, 104);
+    let posted = mock.posted_tweets();
+    assert_eq!(posted.len(), 1);
+    assert_eq!(posted[0].in_reply_to_tweet_id.as_deref(), Some(target_tweet_id.as_str()));
+}
+
+#[tokio::test]
+async fn truncated_timeline_keeps_checkpoint_and_backfills_oldest_first() {
+    let mock = MockTwitter::start().await;
+    let target_user_id = mock.add_user("target", "target-token");
+    mock.add_user("replier", "replier-token");
+    let state = test_state(&mock);
+    let target_id = add_account(&state, "target", "target-token");
+    let replier_id = add_account(&state, "replier", "replier-token");
+    let setting_id = add_reply_setting(&state, replier_id, &[target_id], "返信です");
+    let filters = crate::reply_filters::ReplyFilters { include_keywords: vec!["対象".to_string()], ..Default::default() };
+    state.db.lock().unwrap()
+        .execute("UPDATE reply_settings SET reply_filters = ? WHERE id = ?", params![serde_json::to_string(&filters).unwrap(), setting_id]).unwrap();
+
+    let first_id = mock.add_tweet(&target_user_id, "雑談");
+    reply_engine::run_reply_cycle(&state).await.unwrap();
+
+    // 取得上限（5ページ）を超える新着の最も古いツイートだけが返信対象
+    let target_tweet_id = mock.add_tweet(&target_user_id, "対象のツイート");
+    for i in 0..501 {
+        mock.add_tweet(&target_user_id, &format!("雑談 {}", i));
+    }
+
+    // 取得しきれない場合はエラーとして数え、チェックポイントを進めない
+    let truncated = reply_engine::run_reply_cycle(&state).await.unwrap();
+    assert_eq!((truncated.replies_sent, truncated.skipped, truncated.errors), (0, 0, 1));
+    assert_eq!(last_seen(&state, setting_id).as_deref(), Some(first_id.as_str()));
+
+    // 次回は取得できなかった古い範囲から返信し、その次で残りを確認する
+    let backfilled = reply_engine::run_reply_cycle(&state).await.unwrap();
+    assert_eq!((backfilled.replies_sent, backfilled.skipped, backfilled.errors), (1, 1, 0));
+    let caught_up = reply_engine::run_reply_cycle(&state).await.unwrap();
+    assert_eq!((caught_up.replies_sent, caught_up.skipped, caught_up.errors), (0, 500, 0));
+
+    let posted = mock.posted_tweets();
+    assert_eq!(posted.len(), 1);
+    assert_eq!(posted[0].in_reply_to_tweet_id.as_deref(), Some(target_tweet_id.as_str()));
+}
+
+#[tokio::test]
+async fn reply_cycle_dry_run_does_not_call_api() {
+    let mock = MockTwitter::start().await;
+    let target_user_id = mock.add_user("target", "target-token");
+    mock.add_user("replier", "replier-token");
+    let state = test_state(&mock);
+    let target_id = add_account(&state, "target", "target-token");
+    let replier_id = add_account(&state, "replier", "replier-token");
+    let setting_id = add_reply_setting(&state, replier_id, &[target_id], "返信です");
+    let tweet_id = mock.add_tweet(&target_user_id, "新しいツイート");
+    crate::set_app_setting(&state.db.lock().unwrap(), "dry_run_enabled", "true").unwrap();
+
+    let summary = reply_engine::run_reply_cycle(&state).await.unwrap();
+    assert_eq!(summary.simulated, 1);
+    assert_eq!(summary.replies_sent, 0);
+    assert_eq!(mock.request_count(Method::POST, "/2/tweets"), 0);
+    assert_eq!(log_statuses(&state, replier_id), ["simulated"]);
+
+    // 本番のチェックポイントは進めず、同じツイートを再びシミュレートすることもない
+    assert_eq!(last_seen(&state, setting_id), None);
+    let second = reply_engine::run_reply_cycle(&state).await.unwrap();
+    assert_eq!(second.simulated, 0);
+
+    // ドライランを解除すると本番のチェックポイントから返信する
+    crate::set_app_setting(&state.db.lock().unwrap(), "dry_run_enabled", "false").unwrap();
+    let live = reply_engine::run_reply_cycle(&state).await.unwrap();
+    assert_eq!(live.replies_sent, 1);
+    assert_eq!(last_seen(&state, setting_id).as_deref(), Some(tweet_id.as_str()));
+}
+
+#[tokio::test]
+async fn timeline_error_is_recorded_on_checkpoint() {
+    let mock = MockTwitter::start().await;
+    mock.add_user("target", "target-token");
+    mock.add_user("replier", "replier-token");
+    let state = test_state(&mock);
+    let target_id = add_account(&state, "target", "target-token");
+    let replier_id = add_account(&state, "replier", "replier-token");
+    let setting_id = add_reply_setting(&state, replier_id, &[target_id], "返信です");
+
+    mock.fail_next(Method::GET, "/2/users/1000/tweets", StatusCode::SERVICE_UNAVAILABLE, "Service Unavailable");
+    let summary = reply_engine::run_reply_cycle(&state).await.unwrap();
+    assert_eq!(summary.errors, 1);
+
+    let last_error: Option<String> = state.db.lock().unwrap().query_row(
+        "SELECT last_error FROM reply_checkpoints WHERE reply_setting_id = ?",
+        params![setting_id],
+        |row| row.get(0)
+    ).unwrap();
+    assert!(last_error.unwrap().contains("503"));
+}
+
+#[tokio::test]
+async fn mention_poll_stores_new_mentions() {
+    let mock = MockTwitter::start().await;
+    let fan_user_id = mock.add_user("fan", "fan-token");
+    mock.add_user("bot", "bot-token");
+    let state = test_state(&mock);
+    let bot_id = add_account(&state, "bot", "bot-token");
+    {
+        let conn = state.db.lock().unwrap();
+        let now = Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO mention_settings (account_id, is_enabled, auto_reply_enabled, created_at, updated_at) VALUES (?, 1, 0, ?, ?)",
+            params![bot_id, now, now],
+        ).unwrap();
+    }
+    mock.add_tweet(&fan_user_id, "@bot こんにちは");
+    mock.add_tweet(&fan_user_id, "関係ないツイート");
+
+    let first = mentions::run_mention_poll(&state, false).await.unwrap();
+    assert_eq!(first.new_mentions, 1);
+
+    let second = mentions::run_mention_poll(&state, false).await.unwrap();
+    assert_eq!(second.new_mentions, 0);
+}
+
+#[tokio::test]
+async fn api_errors_keep_http_status() {
+    let mock = MockTwitter::start().await;
+    mock.add_user("bot", "bot-token");
+    let state = test_state(&mock);
+    let account_id = add_account(&state, "unknown", "unknown-token");
+    let account = crate::get_bot_account(&state.db.lock().unwrap(), account_id).unwrap();
+
+    let unauthorized = state.api.get_me(&account).await.unwrap_err();
+    assert_eq!(unauthorized.status, Some(401));
+
+    mock.set_rate_limit_remaining("GET /2/users/me", 0);
+    let limited = state.api.get_me(&account).await.unwrap_err();
+    assert!(limited.is_rate_limited());
+    assert!(limited.rate_limit_reset.is_some_and(|reset| reset > Utc::now()));
+}
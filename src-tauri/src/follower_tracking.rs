@@ -0,0 +1,290 @@
+// Botアカウントのフォロワー数の推移
+//
+// 1日1回、稼働中のアカウントごとに GET /2/users/me（user.fields=public_metrics）で
+// フォロワー数・フォロー数・ツイート数・リスト数を取得し、JST の日付ごとに1行の follower_snapshots に保存する。
+// 同じ日に再取得した場合はその日の行を上書きする。/2/users/me で認証を確認したユーザーIDも bot_accounts にキャッシュする。
+// 履歴は前日（前回のスナップショット）からの増減と、期間全体の増減つきで返す。
+
+use rusqlite::{Connection, params, Result as SqliteResult};
+use serde::{Serialize, Deserialize};
+use chrono::{DateTime, Duration, Utc};
+use tauri::{AppHandle, Manager, State};
+
+use crate::{AppState, BotAccount};
+use crate::log_details::LogDetails;
+use crate::reply_engine::{self, write_log};
+use crate::reply_filters::to_jst;
+use crate::twitter_api::UserMetrics;
+
+const SETTING_ENABLED: &str = "follower_tracking_enabled";
+const SETTING_LAST_SNAPSHOT_DATE: &str = "follower_snapshot_last_date";
+
+const DEFAULT_HISTORY_DAYS: i64 = 30;
+const LOOP_TICK: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+// 記録の設定（UI用）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FollowerTrackingSettings {
+    pub enabled: bool,
+    #[serde(default)]
+    pub last_snapshot_date: Option<String>,
+}
+
+// 1回の取得結果
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct FollowerSnapshotSummary {
+    pub snapshot_date: String,
+    pub accounts_checked: i32,
+    pub saved: i32,
+    pub errors: i32,
+}
+
+// 1日分の記録と、前回の記録からの増減
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FollowerSnapshot {
+    pub snapshot_date: String,   // JST の "YYYY-MM-DD"
+    #[serde(flatten)]
+    pub metrics: UserMetrics,
+    pub followers_delta: Option<i64>,
+    pub following_delta: Option<i64>,
+    pub tweet_delta: Option<i64>,
+    pub listed_delta: Option<i64>,
+    pub collected_at: String,
+}
+
+// アカウントごとの推移
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FollowerHistory {
+    pub account_id: i64,
+    pub account_name: String,
+    pub snapshots: Vec<FollowerSnapshot>,   // 古い順
+    pub followers_change: Option<i64>,      // 期間の最初と最後の記録の差
+    pub following_change: Option<i64>,
+}
+
+// スナップショットのテーブル（マイグレーションから呼ばれる）
+pub fn create_follower_snapshot_table(conn: &Connection) -> SqliteResult<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS follower_snapshots (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            account_id INTEGER NOT NULL,
+            twitter_user_id TEXT NOT NULL,
+            snapshot_date TEXT NOT NULL,
+            followers_count INTEGER NOT NULL,
+            following_count INTEGER NOT NULL,
+            tweet_count INTEGER NOT NULL,
+            listed_count INTEGER NOT NULL,
+            collected_at TEXT NOT NULL,
+            UNIQUE (account_id, snapshot_date),
+            FOREIGN KEY (account_id) REFERENCES bot_accounts(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+fn load_settings(conn: &Connection) -> FollowerTrackingSettings {
+    FollowerTrackingSettings {
+        enabled: crate::get_app_setting(conn, SETTING_ENABLED).map(|v| v == "true").unwrap_or(true),
+        last_snapshot_date: crate::get_app_setting(conn, SETTING_LAST_SNAPSHOT_DATE),
+    }
+}
+
+fn jst_date(time: DateTime<Utc>) -> String {
+    to_jst(time).format("%Y-%m-%d").to_string()
+}
+
+fn save_snapshot(conn: &Connection, account_id: i64, twitter_user_id: &str, snapshot_date: &str, metrics: &UserMetrics) -> SqliteResult<()> {
+    let now = Utc::now().to_rfc3339();
+    conn.execute(
+        "INSERT INTO follower_snapshots
+            (account_id, twitter_user_id, snapshot_date, followers_count, following_count, tweet_count, listed_count, collected_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+         ON CONFLICT(account_id, snapshot_date) DO UPDATE SET
+            twitter_user_id = excluded.twitter_user_id,
+            followers_count = excluded.followers_count,
+            following_count = excluded.following_count,
+            tweet_count = excluded.tweet_count,
+            listed_count = excluded.listed_count,
+            collected_at = excluded.collected_at",
+        params![
+            account_id, twitter_user_id, snapshot_date, metrics.followers_count,
+            metrics.following_count, metrics.tweet_count, metrics.listed_count, now
+        ],
+    )?;
+    conn.execute(
+        "UPDATE bot_accounts SET twitter_user_id = ? WHERE id = ?",
+        params![twitter_user_id, account_id],
+    )?;
+    Ok(())
+}
+
+// 稼働中の全アカウントのスナップショットを取得して保存
+pub(crate) async fn take_snapshots(state: &AppState, now: DateTime<Utc>) -> Result<FollowerSnapshotSummary, String> {
+    let snapshot_date = jst_date(now);
+    let mut summary = FollowerSnapshotSummary {
+        snapshot_date: snapshot_date.clone(),
+        ..Default::default()
+    };
+
+    let accounts: Vec<(i64, BotAccount)> = {
+        let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+        let account_ids: Vec<i64> = conn.prepare("SELECT id FROM bot_accounts WHERE status = 'active' ORDER BY id")
+            .and_then(|mut stmt| stmt.query_map([], |row| row.get(0))?.collect::<SqliteResult<Vec<_>>>())
+            .map_err(|e| e.to_string())?;
+        account_ids.into_iter()
+            .filter_map(|id| reply_engine::load_active_account(&conn, id).map(|(account, _)| (id, account)))
+            .collect()
+    };
+
+    for (account_id, account) in accounts {
+        summary.accounts_checked += 1;
+        let result = state.api.get_me_with_metrics(&account).await;
+
+        let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+        match result {
+            Ok(user) => {
+                let metrics = user.public_metrics.unwrap_or_default();
+                save_snapshot(&conn, account_id, &user.id, &snapshot_date, &metrics).map_err(|e| e.to_string())?;
+                summary.saved += 1;
+            }
+            Err(e) => {
+                summary.errors += 1;
+                let _ = write_log(&conn, account_id, "error", "error",
+                    format!("{} のフォロワー数の取得に失敗しました: {}", account.account_name, e), None, None,
+                    LogDetails::default().with_api_error(&e));
+            }
+        }
+    }
+
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    crate::set_app_setting(&conn, SETTING_LAST_SNAPSHOT_DATE, &snapshot_date).map_err(|e| e.to_string())?;
+
+    if summary.accounts_checked > 0 {
+        println!(
+            "Follower snapshot finished: {} saved, {} errors ({})",
+            summary.saved, summary.errors, snapshot_date
+        );
+    }
+    Ok(summary)
+}
+
+// バックグラウンドの記録ループを起動（有効な場合、JST の日付が変わったら1回取得）
+pub fn spawn_follower_tracker(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(LOOP_TICK).await;
+
+            let state = app.state::<AppState>();
+            let due = match state.db.lock() {
+                Ok(conn) => {
+                    let settings = load_settings(&conn);
+                    settings.enabled && settings.last_snapshot_date.as_deref() != Some(jst_date(Utc::now()).as_str())
+                }
+                Err(_) => continue,
+            };
+            if !due {
+                continue;
+            }
+
+            if let Err(e) = take_snapshots(&state, Utc::now()).await {
+                eprintln!("Follower tracker error: {}", e);
+            }
+        }
+    });
+}
+
+// 直近 days 日間のフォロワー数の推移（account_id を省略すると全アカウント）
+pub(crate) fn follower_history(conn: &Connection, account_id: Option<i64>, days: i64, now: DateTime<Utc>) -> Result<Vec<FollowerHistory>, String> {
+    let since = jst_date(now - Duration::days(days.max(1) - 1));
+
+    let accounts: Vec<(i64, String)> = conn.prepare(
+        "SELECT id, account_name FROM bot_accounts WHERE (?1 IS NULL OR id = ?1) ORDER BY account_name"
+    )
+    .and_then(|mut stmt| stmt.query_map(params![account_id], |row| Ok((row.get(0)?, row.get(1)?)))?.collect::<SqliteResult<Vec<_>>>())
+    .map_err(|e| e.to_string())?;
+
+    // 期間の最初の記録の増減を出すため、その前の記録も1件読む
+    let mut stmt = conn.prepare(
+        "SELECT snapshot_date, followers_count, following_count, tweet_count, listed_count, collected_at
+         FROM follower_snapshots
+         WHERE account_id = ?1
+           AND snapshot_date >= (
+               SELECT COALESCE(MAX(snapshot_date), ?2) FROM follower_snapshots WHERE account_id = ?1 AND snapshot_date < ?2
+           )
+         ORDER BY snapshot_date"
+    ).map_err(|e| e.to_string())?;
+
+    let mut histories = Vec::new();
+    for (account_id, account_name) in accounts {
+        let rows = stmt.query_map(params![account_id, since], |row| {
+            Ok((row.get::<_, String>(0)?, UserMetrics {
+                followers_count: row.get(1)?,
+                following_count: row.get(2)?,
+                tweet_count: row.get(3)?,
+                listed_count: row.get(4)?,
+            }, row.get::<_, String>(5)?))
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<SqliteResult<Vec<_>>>()
+        .map_err(|e| e.to_string())?;
+
+        let mut snapshots = Vec::new();
+        let mut previous: Option<UserMetrics> = None;
+        for (snapshot_date, metrics, collected_at) in rows {
+            let delta = |f: fn(&UserMetrics) -> i64| previous.as_ref().map(|p| f(&metrics) - f(p));
+            let snapshot = FollowerSnapshot {
+                followers_delta: delta(|m| m.followers_count),
+                following_delta: delta(|m| m.following_count),
+                tweet_delta: delta(|m| m.tweet_count),
+                listed_delta: delta(|m| m.listed_count),
+                snapshot_date,
+                metrics: metrics.clone(),
+                collected_at,
+            };
+            previous = Some(metrics);
+            if snapshot.snapshot_date >= since {
+                snapshots.push(snapshot);
+            }
+        }
+
+        let change = |f: fn(&UserMetrics) -> i64| match (snapshots.first(), snapshots.last()) {
+            (Some(first), Some(last)) if snapshots.len() > 1 => Some(f(&last.metrics) - f(&first.metrics)),
+            _ => None,
+        };
+        histories.push(FollowerHistory {
+            account_id,
+            account_name,
+            followers_change: change(|m| m.followers_count),
+            following_change: change(|m| m.following_count),
+            snapshots,
+        });
+    }
+
+    Ok(histories)
+}
+
+#[tauri::command]
+pub fn get_follower_tracking_settings(state: State<AppState>) -> Result<FollowerTrackingSettings, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    Ok(load_settings(&conn))
+}
+
+#[tauri::command]
+pub fn update_follower_tracking_enabled(enabled: bool, state: State<AppState>) -> Result<(), String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    crate::set_app_setting(&conn, SETTING_ENABLED, if enabled { "true" } else { "false" })
+        .map_err(|e| e.to_string())
+}
+
+// 全アカウントのフォロワー数を今すぐ取得（今日の記録を上書き）
+#[tauri::command]
+pub async fn take_follower_snapshots_now(state: State<'_, AppState>) -> Result<FollowerSnapshotSummary, String> {
+    take_snapshots(&state, Utc::now()).await
+}
+
+#[tauri::command]
+pub fn get_follower_history(account_id: Option<i64>, days: Option<i64>, state: State<AppState>) -> Result<Vec<FollowerHistory>, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    follower_history(&conn, account_id, days.unwrap_or(DEFAULT_HISTORY_DAYS), Utc::now())
+}
@@ -0,0 +1,326 @@
+// ローカル HTTP API
+//
+// 他のツール（社内 CMS など）からアプリを操作できるよう、127.0.0.1 だけで待ち受ける REST サーバーを提供する。
+// アカウント・スケジュール・返信設定・実行ログの取得、スケジュールの投稿内容の登録、今すぐ投稿ができ、
+// 処理はそれぞれ対応する Tauri コマンドと同じ関数を使う。
+// 既定では無効で、有効にするとアクセストークンを発行する（リクエストには Authorization: Bearer が必要）。
+// アカウントの API キーなどの認証情報は返さない。
+
+use axum::extract::{Path, Query, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post, put};
+use axum::{Json, Router};
+use rusqlite::Connection;
+use serde::{Serialize, Deserialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use crate::{AppState, ExecutionLog, ReplySettings, ScheduledTweet, TestTweetRequest, TwitterApiResponse};
+use crate::after_commit;
+use crate::log_query::{self, ExecutionLogPage, ExecutionLogQuery};
+use crate::schedule_content;
+
+const SETTING_ENABLED: &str = "local_api_enabled";
+const SETTING_PORT: &str = "local_api_port";
+const SETTING_TOKEN: &str = "local_api_token";
+
+const DEFAULT_PORT: u16 = 17321;
+const TOKEN_LENGTH: usize = 40;
+
+// 起動中のサーバー（設定を変えたら止めて起動し直す）
+static SERVER: Mutex<Option<tauri::async_runtime::JoinHandle<()>>> = Mutex::new(None);
+
+// ルーターから AppState を参照する（アプリでは AppHandle、テストでは AppState を直接使う）
+pub(crate) trait ApiContext: Clone + Send + Sync + 'static {
+    fn app_state(&self) -> &AppState;
+}
+
+impl ApiContext for AppHandle {
+    fn app_state(&self) -> &AppState {
+        self.state::<AppState>().inner()
+    }
+}
+
+impl ApiContext for std::sync::Arc<AppState> {
+    fn app_state(&self) -> &AppState {
+        self
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LocalApiSettings {
+    pub enabled: bool,
+    pub port: u16,
+    pub token: Option<String>,
+    pub base_url: String,
+}
+
+// API で返すアカウント情報（認証情報を除く）
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApiAccount {
+    pub id: i64,
+    pub account_name: String,
+    pub api_type: String,
+    pub status: String,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ScheduleBody {
+    scheduled_times: String,
+    content_list: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContentBody {
+    content: String,
+    index: Option<usize>,   // 省略時は末尾に追加
+}
+
+#[derive(Debug, Deserialize)]
+struct AccountParams {
+    account_id: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LogParams {
+    account_id: Option<i64>,
+    limit: Option<i32>,
+}
+
+// エラーは {"error": "..."} で返す
+struct HttpError(StatusCode, String);
+
+impl From<String> for HttpError {
+    fn from(message: String) -> Self {
+        HttpError(StatusCode::BAD_REQUEST, message)
+    }
+}
+
+impl IntoResponse for HttpError {
+    fn into_response(self) -> Response {
+        (self.0, Json(serde_json::json!({ "error": self.1 }))).into_response()
+    }
+}
+
+type ApiResult<T> = Result<Json<T>, HttpError>;
+
+fn with_conn<C: ApiContext, T>(ctx: &C, f: impl FnOnce(&Connection) -> Result<T, String>) -> Result<T, HttpError> {
+    let conn = ctx.app_state().db.lock()
+        .map_err(|_| HttpError(StatusCode::INTERNAL_SERVER_ERROR, "Failed to lock database".to_string()))?;
+    f(&conn).map_err(HttpError::from)
+}
+
+pub(crate) fn load_settings(conn: &Connection) -> LocalApiSettings {
+    let port = crate::get_app_setting(conn, SETTING_PORT)
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_PORT);
+    LocalApiSettings {
+        enabled: crate::get_app_setting(conn, SETTING_ENABLED).as_deref() == Some("true"),
+        port,
+        token: crate::get_app_setting(conn, SETTING_TOKEN).filter(|t| !t.is_empty()),
+        base_url: format!("http://127.0.0.1:{}/api", port),
+    }
+}
+
+// アクセストークンを発行し直す（以前のトークンは使えなくなる）
+pub(crate) fn regenerate_token(conn: &Connection) -> Result<String, String> {
+    let chars = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+    let token: String = (0..TOKEN_LENGTH)
+        .map(|_| chars[rand::random::<usize>() % chars.len()] as char)
+        .collect();
+    crate::set_app_setting(conn, SETTING_TOKEN, &token).map_err(|e| e.to_string())?;
+    Ok(token)
+}
+
+// 長さが同じなら内容に関係なく同じ時間で比較する
+fn token_matches(expected: &str, given: &str) -> bool {
+    expected.len() == given.len()
+        && expected.bytes().zip(given.bytes()).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+async fn require_token<C: ApiContext>(State(ctx): State<C>, request: Request, next: Next) -> Response {
+    let given = request.headers().get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim)
+        .unwrap_or_default()
+        .to_string();
+    let expected = match ctx.app_state().db.lock() {
+        Ok(conn) => load_settings(&conn).token,
+        Err(_) => None,
+    };
+
+    match expected {
+        Some(expected) if token_matches(&expected, &given) => next.run(request).await,
+        _ => HttpError(StatusCode::UNAUTHORIZED, "アクセストークンが正しくありません".to_string()).into_response(),
+    }
+}
+
+async fn list_accounts<C: ApiContext>(State(ctx): State<C>) -> ApiResult<Vec<ApiAccount>> {
+    let accounts = with_conn(&ctx, crate::load_bot_accounts)?;
+    Ok(Json(accounts.into_iter()
+        .map(|a| ApiAccount {
+            id: a.id.unwrap_or_default(),
+            account_name: a.account_name,
+            api_type: a.api_type,
+            status: a.status,
+            created_at: a.created_at,
+            updated_at: a.updated_at,
+        })
+        .collect()))
+}
+
+async fn list_schedules<C: ApiContext>(State(ctx): State<C>, Query(params): Query<AccountParams>) -> ApiResult<Vec<ScheduledTweet>> {
+    with_conn(&ctx, |conn| crate::load_scheduled_tweets(conn, params.account_id)).map(Json)
+}
+
+// アカウントの投稿リストと投稿時刻を保存（save_scheduled_tweet_list と同じ）
+async fn save_schedule<C: ApiContext>(
+    State(ctx): State<C>,
+    Path(account_id): Path<i64>,
+    Json(body): Json<ScheduleBody>,
+) -> ApiResult<Vec<ScheduledTweet>> {
+    let mut conn = ctx.app_state().db.lock()
+        .map_err(|_| HttpError(StatusCode::INTERNAL_SERVER_ERROR, "Failed to lock database".to_string()))?;
+    crate::get_bot_account(&conn, account_id)
+        .map_err(|_| HttpError(StatusCode::NOT_FOUND, format!("アカウント ID {} が存在しません", account_id)))?;
+
+    let tx = after_commit::begin(&mut conn).map_err(|e| e.to_string())?;
+    schedule_content::save_content_list(&tx, account_id, body.scheduled_times, body.content_list)?;
+    tx.commit().map_err(|e| e.to_string())?;
+
+    crate::load_scheduled_tweets(&conn, Some(account_id)).map(Json).map_err(HttpError::from)
+}
+
+// スケジュールに投稿内容を1件追加（insert_schedule_content と同じ）
+async fn add_schedule_content<C: ApiContext>(
+    State(ctx): State<C>,
+    Path(schedule_id): Path<i64>,
+    Json(body): Json<ContentBody>,
+) -> ApiResult<ScheduledTweet> {
+    let index = match body.index {
+        Some(index) => index,
+        None => with_conn(&ctx, |conn| schedule_content::load_schedule_content(conn, schedule_id))
+            .map_err(|e| HttpError(StatusCode::NOT_FOUND, e.1))?
+            .content_list.len(),
+    };
+    schedule_content::insert_content(ctx.app_state(), schedule_id, index, body.content)
+        .map(Json)
+        .map_err(HttpError::from)
+}
+
+async fn list_reply_settings<C: ApiContext>(State(ctx): State<C>) -> ApiResult<Vec<ReplySettings>> {
+    with_conn(&ctx, crate::load_reply_settings).map(Json)
+}
+
+async fn list_logs<C: ApiContext>(State(ctx): State<C>, Query(params): Query<LogParams>) -> ApiResult<Vec<ExecutionLog>> {
+    with_conn(&ctx, |conn| crate::load_execution_logs(conn, params.account_id, params.limit)).map(Json)
+}
+
+// 絞り込み条件つきの検索（query_execution_logs と同じ）
+async fn search_logs<C: ApiContext>(State(ctx): State<C>, Json(query): Json<ExecutionLogQuery>) -> ApiResult<ExecutionLogPage> {
+    with_conn(&ctx, |conn| log_query::query_logs(conn, &query)).map(Json)
+}
+
+// 今すぐ投稿（test_tweet と同じ）
+async fn post_tweet<C: ApiContext>(State(ctx): State<C>, Json(request): Json<TestTweetRequest>) -> ApiResult<TwitterApiResponse> {
+    crate::post_tweet_now(ctx.app_state(), request).await
+        .map(Json)
+        .map_err(HttpError::from)
+}
+
+pub(crate) fn router<C: ApiContext>(ctx: C) -> Router {
+    Router::new()
+        .route("/api/accounts", get(list_accounts::<C>))
+        .route("/api/accounts/:account_id/schedule", put(save_schedule::<C>))
+        .route("/api/schedules", get(list_schedules::<C>))
+        .route("/api/schedules/:schedule_id/content", post(add_schedule_content::<C>))
+        .route("/api/reply-settings", get(list_reply_settings::<C>))
+        .route("/api/logs", get(list_logs::<C>))
+        .route("/api/logs/search", post(search_logs::<C>))
+        .route("/api/tweets", post(post_tweet::<C>))
+        .route_layer(middleware::from_fn_with_state(ctx.clone(), require_token::<C>))
+        .with_state(ctx)
+}
+
+// 設定に合わせてサーバーを止めて起動し直す
+pub(crate) async fn restart_server(app: AppHandle) -> Result<(), String> {
+    let running = SERVER.lock().map_err(|_| "Failed to lock server")?.take();
+    if let Some(handle) = running {
+        handle.abort();
+        // 待ち受けていたポートが解放されるまで待つ
+        let _ = handle.await;
+    }
+
+    let settings = {
+        let state = app.state::<AppState>();
+        let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+        load_settings(&conn)
+    };
+    if !settings.enabled {
+        return Ok(());
+    }
+
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", settings.port)).await
+        .map_err(|e| format!("ポート {} で待ち受けできません: {}", settings.port, e))?;
+    let router = router(app.clone());
+    let handle = tauri::async_runtime::spawn(async move {
+        if let Err(e) = axum::serve(listener, router).await {
+            eprintln!("Local API server error: {}", e);
+        }
+    });
+    *SERVER.lock().map_err(|_| "Failed to lock server")? = Some(handle);
+
+    println!("Local API listening on {}", settings.base_url);
+    Ok(())
+}
+
+// 起動時に設定で有効ならサーバーを起動
+pub fn spawn_local_api(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = restart_server(app).await {
+            eprintln!("Local API error: {}", e);
+        }
+    });
+}
+
+#[tauri::command]
+pub fn get_local_api_settings(state: tauri::State<AppState>) -> Result<LocalApiSettings, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    Ok(load_settings(&conn))
+}
+
+// 有効・無効とポートを保存してサーバーを起動し直す（初めて有効にしたときはトークンを発行）
+#[tauri::command]
+pub async fn update_local_api_settings(enabled: bool, port: u16, app: AppHandle) -> Result<LocalApiSettings, String> {
+    if port < 1024 {
+        return Err("ポートは1024以上で指定してください".to_string());
+    }
+
+    {
+        let state = app.state::<AppState>();
+        let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+        crate::set_app_setting(&conn, SETTING_ENABLED, if enabled { "true" } else { "false" })
+            .map_err(|e| e.to_string())?;
+        crate::set_app_setting(&conn, SETTING_PORT, &port.to_string()).map_err(|e| e.to_string())?;
+        if enabled && load_settings(&conn).token.is_none() {
+            regenerate_token(&conn)?;
+        }
+    }
+
+    restart_server(app.clone()).await?;
+
+    let state = app.state::<AppState>();
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    Ok(load_settings(&conn))
+}
+
+#[tauri::command]
+pub fn regenerate_local_api_token(state: tauri::State<AppState>) -> Result<String, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    regenerate_token(&conn)
+}
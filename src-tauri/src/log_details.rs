@@ -0,0 +1,137 @@
+// 実行ログの構造化情報
+//
+// 実行ログがどのスケジュール（投稿リストの位置）・返信設定（返信候補の位置）・メンション自動返信ルール・
+// 対象ツイートから生じたかと、Twitter API 呼び出しの HTTP ステータス・エラーコード・所要時間を列として記録する。
+// メッセージの文言に頼らず「どのルールの返信か」「どの投稿内容が失敗したか」を検索できるようにする。
+
+use rusqlite::{Connection, Row, Result as SqliteResult};
+use serde::{Serialize, Deserialize};
+use std::time::Duration;
+
+use crate::twitter_api::ApiError;
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct LogDetails {
+    pub schedule_id: Option<i64>,
+    pub content_index: Option<i64>,      // 投稿リストまたは返信候補の位置（0始まり）
+    pub reply_setting_id: Option<i64>,
+    pub mention_rule_id: Option<i64>,
+    pub target_tweet_id: Option<String>, // 返信・削除の対象ツイート
+    pub http_status: Option<i64>,        // API エラー時の HTTP ステータス
+    pub api_error_code: Option<String>,  // API エラーのコード・種別
+    pub duration_ms: Option<i64>,        // API 呼び出しの所要時間
+}
+
+// 構造化情報の列を追加（マイグレーションから呼ばれる）
+pub fn create_log_detail_columns(conn: &Connection) -> SqliteResult<()> {
+    let columns = [
+        ("schedule_id", "INTEGER"),
+        ("content_index", "INTEGER"),
+        ("reply_setting_id", "INTEGER"),
+        ("mention_rule_id", "INTEGER"),
+        ("target_tweet_id", "TEXT"),
+        ("http_status", "INTEGER"),
+        ("api_error_code", "TEXT"),
+        ("duration_ms", "INTEGER"),
+    ];
+
+    for (name, definition) in columns {
+        let exists: i32 = conn.query_row(
+            "SELECT COUNT(*) FROM pragma_table_info('execution_logs') WHERE name = ?",
+            [name],
+            |row| row.get(0)
+        ).unwrap_or(0);
+
+        if exists == 0 {
+            conn.execute(&format!("ALTER TABLE execution_logs ADD COLUMN {} {}", name, definition), [])?;
+            println!("Added {} column to execution_logs table", name);
+        }
+    }
+
+    conn.execute_batch(
+        "CREATE INDEX IF NOT EXISTS idx_execution_logs_schedule ON execution_logs (schedule_id, content_index);
+         CREATE INDEX IF NOT EXISTS idx_execution_logs_reply_setting ON execution_logs (reply_setting_id);
+         CREATE INDEX IF NOT EXISTS idx_execution_logs_target_tweet ON execution_logs (target_tweet_id);"
+    )?;
+
+    Ok(())
+}
+
+impl LogDetails {
+    // スケジュール投稿の投稿リストの位置
+    pub(crate) fn schedule(schedule_id: i64, content_index: usize) -> Self {
+        LogDetails {
+            schedule_id: Some(schedule_id),
+            content_index: Some(content_index as i64),
+            ..Default::default()
+        }
+    }
+
+    // 返信設定による返信（返信候補の位置は with_content_index で指定）
+    pub(crate) fn reply(reply_setting_id: i64, target_tweet_id: &str) -> Self {
+        LogDetails {
+            reply_setting_id: Some(reply_setting_id),
+            target_tweet_id: Some(target_tweet_id.to_string()),
+            ..Default::default()
+        }
+    }
+
+    // メンションへの返信（手動返信ではルール無し）
+    pub(crate) fn mention(target_tweet_id: &str, mention_rule_id: Option<i64>) -> Self {
+        LogDetails {
+            mention_rule_id,
+            target_tweet_id: Some(target_tweet_id.to_string()),
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn target(target_tweet_id: &str) -> Self {
+        LogDetails {
+            target_tweet_id: Some(target_tweet_id.to_string()),
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn with_content_index(mut self, content_index: Option<usize>) -> Self {
+        self.content_index = content_index.map(|i| i as i64);
+        self
+    }
+
+    pub(crate) fn with_duration(mut self, elapsed: Duration) -> Self {
+        self.duration_ms = Some(elapsed.as_millis() as i64);
+        self
+    }
+
+    // API 呼び出しの結果（エラーなら HTTP ステータスとエラーコード）
+    pub(crate) fn with_api_result<T>(mut self, result: &Result<T, ApiError>) -> Self {
+        if let Err(e) = result {
+            self = self.with_api_error(e);
+        }
+        self
+    }
+
+    pub(crate) fn with_api_error(mut self, error: &ApiError) -> Self {
+        self.http_status = error.status.map(i64::from);
+        self.api_error_code = error.code.clone();
+        self
+    }
+
+    // スケジュール投稿・返信設定・メンション自動返信による投稿のログか
+    pub(crate) fn is_automated_post(&self) -> bool {
+        self.schedule_id.is_some() || self.reply_setting_id.is_some() || self.mention_rule_id.is_some()
+    }
+
+    // execution_logs の行から読み込む（列名で参照）
+    pub(crate) fn from_row(row: &Row) -> SqliteResult<Self> {
+        Ok(LogDetails {
+            schedule_id: row.get("schedule_id")?,
+            content_index: row.get("content_index")?,
+            reply_setting_id: row.get("reply_setting_id")?,
+            mention_rule_id: row.get("mention_rule_id")?,
+            target_tweet_id: row.get("target_tweet_id")?,
+            http_status: row.get("http_status")?,
+            api_error_code: row.get("api_error_code")?,
+            duration_ms: row.get("duration_ms")?,
+        })
+    }
+}
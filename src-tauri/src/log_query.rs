@@ -0,0 +1,247 @@
+// 実行ログの検索・絞り込み・ページング
+//
+// message / tweet_content の全文検索には FTS5（trigram トークナイザ）の外部コンテンツテーブルを使い、
+// execution_logs への追加・更新・削除はトリガーで検索インデックスに反映する。
+// trigram は3文字未満の語を検索できないため、短い検索語は LIKE で部分一致検索する。
+// ページングは (created_at, id) の降順によるカーソル方式で、件数の多いログでも途中から取得できる。
+
+use rusqlite::{Connection, Result as SqliteResult, ToSql};
+use serde::{Serialize, Deserialize};
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+use tauri::State;
+
+use crate::{AppState, ExecutionLog};
+use crate::reply_filters::to_jst;
+
+const DEFAULT_PAGE_SIZE: i64 = 100;
+const MAX_PAGE_SIZE: i64 = 500;
+const MIN_FTS_QUERY_CHARS: usize = 3;
+
+// WHERE 句の条件式と、その順に対応するパラメータ
+pub(crate) type SqlConditions = (Vec<String>, Vec<Box<dyn ToSql>>);
+
+// 実行ログの絞り込み条件（日付は JST の "YYYY-MM-DD"、両端を含む）
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ExecutionLogFilter {
+    pub account_id: Option<i64>,
+    pub date_from: Option<String>,
+    pub date_to: Option<String>,
+    pub log_type: Option<String>,
+    pub status: Option<String>,
+    pub search: Option<String>,  // メッセージ・投稿内容の全文検索
+    pub schedule_id: Option<i64>,
+    pub content_index: Option<i64>,
+    pub reply_setting_id: Option<i64>,
+    pub mention_rule_id: Option<i64>,
+    pub target_tweet_id: Option<String>,
+    pub http_status: Option<i64>,
+}
+
+// 実行ログの検索リクエスト
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ExecutionLogQuery {
+    #[serde(flatten)]
+    pub filter: ExecutionLogFilter,
+    pub cursor: Option<String>,  // 前のページの next_cursor
+    pub limit: Option<i64>,
+}
+
+// 実行ログの検索結果（1ページ分）
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExecutionLogPage {
+    pub logs: Vec<ExecutionLog>,
+    pub total_count: i64,             // 絞り込み条件に一致する全件数
+    pub next_cursor: Option<String>,  // 次のページが無ければ None
+}
+
+// 全文検索用の仮想テーブルと同期トリガー（マイグレーションから呼ばれる）
+pub fn create_log_search_index(conn: &Connection) -> SqliteResult<()> {
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_execution_logs_created_at ON execution_logs (created_at, id)",
+        [],
+    )?;
+
+    let fts_exists: i32 = conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='execution_logs_fts'",
+        [],
+        |row| row.get(0)
+    ).unwrap_or(0);
+
+    if fts_exists == 0 {
+        conn.execute_batch(
+            "CREATE VIRTUAL TABLE execution_logs_fts USING fts5(
+                message, tweet_content,
+                content='execution_logs', content_rowid='id', tokenize='trigram'
+            );
+            CREATE TRIGGER IF NOT EXISTS execution_logs_fts_insert AFTER INSERT ON execution_logs BEGIN
+                INSERT INTO execution_logs_fts (rowid, message, tweet_content)
+                VALUES (new.id, new.message, new.tweet_content);
+            END;
+            CREATE TRIGGER IF NOT EXISTS execution_logs_fts_delete AFTER DELETE ON execution_logs BEGIN
+                INSERT INTO execution_logs_fts (execution_logs_fts, rowid, message, tweet_content)
+                VALUES ('delete', old.id, old.message, old.tweet_content);
+            END;
+            CREATE TRIGGER IF NOT EXISTS execution_logs_fts_update AFTER UPDATE ON execution_logs BEGIN
+                INSERT INTO execution_logs_fts (execution_logs_fts, rowid, message, tweet_content)
+                VALUES ('delete', old.id, old.message, old.tweet_content);
+                INSERT INTO execution_logs_fts (rowid, message, tweet_content)
+                VALUES (new.id, new.message, new.tweet_content);
+            END;
+            INSERT INTO execution_logs_fts (execution_logs_fts) VALUES ('rebuild');"
+        )?;
+        println!("Created execution_logs_fts search index");
+    }
+
+    Ok(())
+}
+
+// JST の日付の 0:00 を UTC で返す
+pub(crate) fn jst_day_start(date: &str) -> Result<DateTime<Utc>, String> {
+    let day = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| format!("日付「{}」の形式が正しくありません（YYYY-MM-DD で指定してください）", date))?;
+    let jst = to_jst(Utc::now()).timezone();
+    jst.from_local_datetime(&day.and_hms_opt(0, 0, 0).unwrap())
+        .single()
+        .map(|t| t.with_timezone(&Utc))
+        .ok_or_else(|| format!("日付「{}」を変換できません", date))
+}
+
+// LIKE のワイルドカードをエスケープ
+fn like_pattern(value: &str) -> String {
+    let escaped = value.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+impl ExecutionLogFilter {
+    // 条件式とパラメータ（execution_logs は el として参照する）
+    pub(crate) fn conditions(&self) -> Result<SqlConditions, String> {
+        let mut conditions: Vec<String> = Vec::new();
+        let mut values: Vec<Box<dyn ToSql>> = Vec::new();
+
+        if let Some(account_id) = self.account_id {
+            conditions.push("el.account_id = ?".to_string());
+            values.push(Box::new(account_id));
+        }
+        if let Some(date_from) = self.date_from.as_deref().filter(|d| !d.is_empty()) {
+            conditions.push("el.created_at >= ?".to_string());
+            values.push(Box::new(jst_day_start(date_from)?.to_rfc3339()));
+        }
+        if let Some(date_to) = self.date_to.as_deref().filter(|d| !d.is_empty()) {
+            conditions.push("el.created_at < ?".to_string());
+            values.push(Box::new((jst_day_start(date_to)? + Duration::days(1)).to_rfc3339()));
+        }
+        if let Some(log_type) = self.log_type.as_deref().filter(|t| !t.is_empty()) {
+            conditions.push("el.log_type = ?".to_string());
+            values.push(Box::new(log_type.to_string()));
+        }
+        if let Some(status) = self.status.as_deref().filter(|s| !s.is_empty()) {
+            conditions.push("el.status = ?".to_string());
+            values.push(Box::new(status.to_string()));
+        }
+
+        // 構造化情報の列（どのスケジュール・返信設定・ルール・対象ツイートのログか）
+        let detail_ids = [
+            ("el.schedule_id", self.schedule_id),
+            ("el.content_index", self.content_index),
+            ("el.reply_setting_id", self.reply_setting_id),
+            ("el.mention_rule_id", self.mention_rule_id),
+            ("el.http_status", self.http_status),
+        ];
+        for (column, value) in detail_ids {
+            if let Some(value) = value {
+                conditions.push(format!("{} = ?", column));
+                values.push(Box::new(value));
+            }
+        }
+        if let Some(target_tweet_id) = self.target_tweet_id.as_deref().filter(|t| !t.is_empty()) {
+            conditions.push("el.target_tweet_id = ?".to_string());
+            values.push(Box::new(target_tweet_id.to_string()));
+        }
+
+        // 空白区切りの各語をすべて含むログ（AND 検索）
+        let terms = self.search.as_deref().unwrap_or_default().split_whitespace();
+        for term in terms {
+            if term.chars().count() >= MIN_FTS_QUERY_CHARS {
+                conditions.push(
+                    "el.id IN (SELECT rowid FROM execution_logs_fts WHERE execution_logs_fts MATCH ?)".to_string()
+                );
+                values.push(Box::new(format!("\"{}\"", term.replace('"', "\"\""))));
+            } else {
+                conditions.push(
+                    "(el.message LIKE ? ESCAPE '\\' OR el.tweet_content LIKE ? ESCAPE '\\')".to_string()
+                );
+                values.push(Box::new(like_pattern(term)));
+                values.push(Box::new(like_pattern(term)));
+            }
+        }
+
+        Ok((conditions, values))
+    }
+}
+
+pub(crate) fn where_clause(conditions: &[String]) -> String {
+    if conditions.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", conditions.join(" AND "))
+    }
+}
+
+// カーソルは最後に返したログの "created_at|id"
+fn parse_cursor(cursor: &str) -> Result<(String, i64), String> {
+    cursor.rsplit_once('|')
+        .and_then(|(created_at, id)| Some((created_at.to_string(), id.parse().ok()?)))
+        .ok_or_else(|| "カーソルの形式が正しくありません".to_string())
+}
+
+// 条件に一致する実行ログを新しい順に1ページ分取得
+pub(crate) fn query_logs(conn: &Connection, query: &ExecutionLogQuery) -> Result<ExecutionLogPage, String> {
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+    let (mut conditions, mut values) = query.filter.conditions()?;
+
+    let total_count: i64 = conn.query_row(
+        &format!("SELECT COUNT(*) FROM execution_logs el {}", where_clause(&conditions)),
+        rusqlite::params_from_iter(values.iter()),
+        |row| row.get(0)
+    ).map_err(|e| e.to_string())?;
+
+    if let Some(cursor) = query.cursor.as_deref().filter(|c| !c.is_empty()) {
+        let (created_at, id) = parse_cursor(cursor)?;
+        conditions.push("(el.created_at < ? OR (el.created_at = ? AND el.id < ?))".to_string());
+        values.push(Box::new(created_at.clone()));
+        values.push(Box::new(created_at));
+        values.push(Box::new(id));
+    }
+    // 次のページがあるか判定するため1件多く取得する
+    values.push(Box::new(limit + 1));
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT el.*
+         FROM execution_logs el
+         {}
+         ORDER BY el.created_at DESC, el.id DESC
+         LIMIT ?",
+        where_clause(&conditions)
+    )).map_err(|e| e.to_string())?;
+
+    let mut logs = stmt.query_map(rusqlite::params_from_iter(values.iter()), crate::execution_log_from_row)
+        .map_err(|e| e.to_string())?
+        .collect::<SqliteResult<Vec<_>>>()
+        .map_err(|e| e.to_string())?;
+
+    let next_cursor = if logs.len() as i64 > limit {
+        logs.truncate(limit as usize);
+        logs.last().map(|log| format!("{}|{}", log.created_at, log.id.unwrap_or_default()))
+    } else {
+        None
+    };
+
+    Ok(ExecutionLogPage { logs, total_count, next_cursor })
+}
+
+// 実行ログを検索（絞り込み・全文検索・カーソルによるページング）
+#[tauri::command]
+pub fn query_execution_logs(query: ExecutionLogQuery, state: State<AppState>) -> Result<ExecutionLogPage, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    query_logs(&conn, &query)
+}
@@ -0,0 +1,328 @@
+// 実行ログの保持期間・アーカイブ・VACUUM
+//
+// 保持期間（日数）またはアカウントごとの保持件数を超えた実行ログを、gzip 圧縮した JSONL ファイルに
+// 書き出してから削除する。アーカイブの書き出しに失敗した場合は何も削除しない。
+// 自動整理を有効にすると1日1回実行し、行を削除した後は設定した間隔で VACUUM してファイルサイズを縮める。
+// 保持期間・件数は既定で無制限（0）のため、設定するまでログは削除されない。
+
+use rusqlite::{Connection, ToSql};
+use serde::{Serialize, Deserialize};
+use chrono::{DateTime, Duration, Utc};
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, State};
+
+use crate::{AppState, ExecutionLog};
+use crate::after_commit;
+use crate::log_query::SqlConditions;
+
+const SETTING_RETENTION_DAYS: &str = "log_retention_days";
+const SETTING_MAX_ROWS_PER_ACCOUNT: &str = "log_retention_max_rows_per_account";
+const SETTING_ARCHIVE_ENABLED: &str = "log_archive_enabled";
+const SETTING_ARCHIVE_DIR: &str = "log_archive_dir";
+const SETTING_AUTO_COMPACTION: &str = "log_auto_compaction_enabled";
+const SETTING_VACUUM_INTERVAL_DAYS: &str = "log_vacuum_interval_days";
+const SETTING_LAST_COMPACTION_AT: &str = "log_compaction_last_run_at";
+const SETTING_LAST_COMPACTION_RESULT: &str = "log_compaction_last_result";
+const SETTING_LAST_VACUUM_AT: &str = "log_vacuum_last_run_at";
+
+const DEFAULT_VACUUM_INTERVAL_DAYS: i64 = 7;
+const ARCHIVE_DIR_NAME: &str = "log-archive";
+
+// 自動整理の確認間隔と実行間隔
+const LOOP_TICK: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+const COMPACTION_INTERVAL_HOURS: i64 = 24;
+
+// 保持ポリシー（0 は無制限）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogRetentionPolicy {
+    pub retention_days: i64,
+    pub max_rows_per_account: i64,
+    pub archive_enabled: bool,          // 削除前に圧縮 JSONL へ書き出す
+    pub archive_dir: Option<String>,    // 省略時はデータディレクトリの log-archive
+    pub auto_compaction_enabled: bool,  // 1日1回自動で整理する
+    pub vacuum_interval_days: i64,      // 行を削除した後に VACUUM する間隔（0 で自動 VACUUM しない）
+}
+
+// 保持設定と前回の実行結果（UI用）
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LogRetentionSettings {
+    #[serde(flatten)]
+    pub policy: LogRetentionPolicy,
+    pub default_archive_dir: Option<String>,
+    pub last_compaction_at: Option<String>,
+    pub last_compaction_result: Option<LogCompactionSummary>,
+    pub last_vacuum_at: Option<String>,
+}
+
+// 1回の整理の結果
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct LogCompactionSummary {
+    pub deleted: usize,
+    pub archived: usize,
+    pub archive_path: Option<String>,
+    pub vacuumed: bool,
+    pub started_at: String,
+    pub finished_at: String,
+}
+
+// アーカイブに書き出す1行
+#[derive(Serialize)]
+struct ArchivedLog {
+    #[serde(flatten)]
+    log: ExecutionLog,
+    account_name: Option<String>,
+}
+
+fn int_setting(conn: &Connection, key: &str, default: i64) -> i64 {
+    crate::get_app_setting(conn, key)
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+fn bool_setting(conn: &Connection, key: &str, default: bool) -> bool {
+    crate::get_app_setting(conn, key)
+        .map(|v| v == "true")
+        .unwrap_or(default)
+}
+
+pub(crate) fn load_policy(conn: &Connection) -> LogRetentionPolicy {
+    LogRetentionPolicy {
+        retention_days: int_setting(conn, SETTING_RETENTION_DAYS, 0),
+        max_rows_per_account: int_setting(conn, SETTING_MAX_ROWS_PER_ACCOUNT, 0),
+        archive_enabled: bool_setting(conn, SETTING_ARCHIVE_ENABLED, true),
+        archive_dir: crate::get_app_setting(conn, SETTING_ARCHIVE_DIR).filter(|d| !d.is_empty()),
+        auto_compaction_enabled: bool_setting(conn, SETTING_AUTO_COMPACTION, true),
+        vacuum_interval_days: int_setting(conn, SETTING_VACUUM_INTERVAL_DAYS, DEFAULT_VACUUM_INTERVAL_DAYS),
+    }
+}
+
+fn default_archive_dir() -> Option<PathBuf> {
+    crate::app_data_dir().ok().map(|dir| dir.join(ARCHIVE_DIR_NAME))
+}
+
+// 削除対象の条件（保持期間より古い、またはアカウントごとの新しい順で保持件数を超えた行）
+fn expired_conditions(policy: &LogRetentionPolicy, now: DateTime<Utc>) -> SqlConditions {
+    let mut conditions = Vec::new();
+    let mut values: Vec<Box<dyn ToSql>> = Vec::new();
+
+    if policy.retention_days > 0 {
+        conditions.push("el.created_at < ?".to_string());
+        values.push(Box::new((now - Duration::days(policy.retention_days)).to_rfc3339()));
+    }
+    if policy.max_rows_per_account > 0 {
+        conditions.push(
+            "el.id IN (SELECT id FROM (
+                 SELECT id, ROW_NUMBER() OVER (PARTITION BY account_id ORDER BY created_at DESC, id DESC) AS rn
+                 FROM execution_logs
+             ) WHERE rn > ?)".to_string()
+        );
+        values.push(Box::new(policy.max_rows_per_account));
+    }
+
+    (conditions, values)
+}
+
+// 削除対象のログを gzip 圧縮した JSONL ファイルに書き出す
+// 既存のアーカイブを上書きしないよう、同名のファイルがあれば連番を付けて新規作成する
+fn create_archive_file(dir: &Path, stem: &str) -> Result<(std::fs::File, PathBuf), String> {
+    std::fs::create_dir_all(dir).map_err(|e| format!("ディレクトリ作成エラー: {}", e))?;
+    for n in 0u32.. {
+        let name = if n == 0 { format!("{}.jsonl.gz", stem) } else { format!("{}-{}.jsonl.gz", stem, n) };
+        let path = dir.join(name);
+        match std::fs::OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => return Ok((file, path)),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(format!("アーカイブ作成エラー: {}", e)),
+        }
+    }
+    Err("アーカイブのファイル名を決定できません".to_string())
+}
+
+fn write_archive(conn: &Connection, condition: &str, values: &[Box<dyn ToSql>], dir: &Path, stem: &str) -> Result<(usize, Option<PathBuf>), String> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT el.*, ba.account_name
+         FROM execution_logs el
+         LEFT JOIN bot_accounts ba ON el.account_id = ba.id
+         WHERE {}
+         ORDER BY el.created_at, el.id",
+        condition
+    )).map_err(|e| e.to_string())?;
+
+    let mut rows = stmt.query(rusqlite::params_from_iter(values.iter())).map_err(|e| e.to_string())?;
+    let mut archived = 0;
+    let mut writer: Option<BufWriter<GzEncoder<std::fs::File>>> = None;
+    let mut path = None;
+
+    while let Some(row) = rows.next().map_err(|e| e.to_string())? {
+        let log = ArchivedLog {
+            log: crate::execution_log_from_row(row).map_err(|e| e.to_string())?,
+            account_name: row.get("account_name").map_err(|e| e.to_string())?,
+        };
+
+        // 対象の行がある場合のみファイルを作成する
+        let out = match writer.as_mut() {
+            Some(out) => out,
+            None => {
+                let (file, created) = create_archive_file(dir, stem)?;
+                path = Some(created);
+                writer.insert(BufWriter::new(GzEncoder::new(file, Compression::default())))
+            }
+        };
+        serde_json::to_writer(&mut *out, &log).map_err(|e| e.to_string())?;
+        out.write_all(b"\n").map_err(|e| format!("アーカイブ書き込みエラー: {}", e))?;
+        archived += 1;
+    }
+
+    if let Some(out) = writer {
+        let encoder = out.into_inner().map_err(|e| format!("アーカイブ書き込みエラー: {}", e))?;
+        encoder.finish()
+            .and_then(|file| file.sync_all())
+            .map_err(|e| format!("アーカイブ書き込みエラー: {}", e))?;
+    }
+
+    Ok((archived, path))
+}
+
+// VACUUM でファイルサイズを縮める（検索インデックスも最適化する）
+fn vacuum(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "INSERT INTO execution_logs_fts (execution_logs_fts) VALUES ('optimize');
+         VACUUM;"
+    ).map_err(|e| e.to_string())?;
+    crate::set_app_setting(conn, SETTING_LAST_VACUUM_AT, &Utc::now().to_rfc3339()).map_err(|e| e.to_string())
+}
+
+// 保持ポリシーに従ってログをアーカイブ・削除し、必要なら VACUUM する
+pub(crate) fn compact_logs(conn: &mut Connection, archive_dir: Option<&Path>, now: DateTime<Utc>) -> Result<LogCompactionSummary, String> {
+    let policy = load_policy(conn);
+    let mut summary = LogCompactionSummary {
+        started_at: Utc::now().to_rfc3339(),
+        ..Default::default()
+    };
+
+    let (conditions, values) = expired_conditions(&policy, now);
+    if !conditions.is_empty() {
+        let condition = conditions.join(" OR ");
+        if policy.archive_enabled {
+            let dir = policy.archive_dir.as_deref().map(Path::new)
+                .or(archive_dir)
+                .ok_or("アーカイブの保存先を決定できません")?;
+            let stem = format!("execution_logs-{}", now.format("%Y%m%d-%H%M%S"));
+
+            let (archived, path) = write_archive(conn, &condition, &values, dir, &stem)?;
+            summary.archived = archived;
+            summary.archive_path = path.map(|p| p.to_string_lossy().to_string());
+        }
+
+        let tx = after_commit::begin(conn).map_err(|e| e.to_string())?;
+        summary.deleted = tx.execute(
+            &format!("DELETE FROM execution_logs WHERE id IN (SELECT el.id FROM execution_logs el WHERE {})", condition),
+            rusqlite::params_from_iter(values.iter()),
+        ).map_err(|e| e.to_string())?;
+        tx.commit().map_err(|e| e.to_string())?;
+    }
+
+    if summary.deleted > 0 && policy.vacuum_interval_days > 0 {
+        let last_vacuum = crate::get_app_setting(conn, SETTING_LAST_VACUUM_AT)
+            .and_then(|v| DateTime::parse_from_rfc3339(&v).ok());
+        let due = last_vacuum
+            .map(|t| now.signed_duration_since(t) >= Duration::days(policy.vacuum_interval_days))
+            .unwrap_or(true);
+        if due {
+            vacuum(conn)?;
+            summary.vacuumed = true;
+        }
+    }
+
+    summary.finished_at = Utc::now().to_rfc3339();
+    let summary_json = serde_json::to_string(&summary).map_err(|e| e.to_string())?;
+    crate::set_app_setting(conn, SETTING_LAST_COMPACTION_AT, &summary.finished_at).map_err(|e| e.to_string())?;
+    crate::set_app_setting(conn, SETTING_LAST_COMPACTION_RESULT, &summary_json).map_err(|e| e.to_string())?;
+
+    if summary.deleted > 0 {
+        println!(
+            "Log compaction finished: {} deleted, {} archived, vacuumed: {}",
+            summary.deleted, summary.archived, summary.vacuumed
+        );
+    }
+    Ok(summary)
+}
+
+// バックグラウンドのログ整理ループを起動（前回の実行から24時間経過していれば実行）
+pub fn spawn_log_compaction(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(LOOP_TICK).await;
+
+            let state = app.state::<AppState>();
+            let Ok(mut conn) = state.db.lock() else { continue };
+            if !load_policy(&conn).auto_compaction_enabled {
+                continue;
+            }
+
+            let last_run = crate::get_app_setting(&conn, SETTING_LAST_COMPACTION_AT)
+                .and_then(|v| DateTime::parse_from_rfc3339(&v).ok());
+            let due = last_run
+                .map(|t| Utc::now().signed_duration_since(t) >= Duration::hours(COMPACTION_INTERVAL_HOURS))
+                .unwrap_or(true);
+
+            if due {
+                if let Err(e) = compact_logs(&mut conn, default_archive_dir().as_deref(), Utc::now()) {
+                    eprintln!("Log compaction error: {}", e);
+                }
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub fn get_log_retention_settings(state: State<AppState>) -> Result<LogRetentionSettings, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+
+    Ok(LogRetentionSettings {
+        policy: load_policy(&conn),
+        default_archive_dir: default_archive_dir().map(|d| d.to_string_lossy().to_string()),
+        last_compaction_at: crate::get_app_setting(&conn, SETTING_LAST_COMPACTION_AT),
+        last_compaction_result: crate::get_app_setting(&conn, SETTING_LAST_COMPACTION_RESULT)
+            .and_then(|v| serde_json::from_str(&v).ok()),
+        last_vacuum_at: crate::get_app_setting(&conn, SETTING_LAST_VACUUM_AT),
+    })
+}
+
+#[tauri::command]
+pub fn update_log_retention_settings(policy: LogRetentionPolicy, state: State<AppState>) -> Result<(), String> {
+    if policy.retention_days < 0 || policy.max_rows_per_account < 0 || policy.vacuum_interval_days < 0 {
+        return Err("保持期間・保持件数・VACUUM間隔は0以上で指定してください".to_string());
+    }
+
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    let bool_value = |v: bool| if v { "true" } else { "false" };
+    let settings = [
+        (SETTING_RETENTION_DAYS, policy.retention_days.to_string()),
+        (SETTING_MAX_ROWS_PER_ACCOUNT, policy.max_rows_per_account.to_string()),
+        (SETTING_ARCHIVE_ENABLED, bool_value(policy.archive_enabled).to_string()),
+        (SETTING_ARCHIVE_DIR, policy.archive_dir.unwrap_or_default()),
+        (SETTING_AUTO_COMPACTION, bool_value(policy.auto_compaction_enabled).to_string()),
+        (SETTING_VACUUM_INTERVAL_DAYS, policy.vacuum_interval_days.to_string()),
+    ];
+    for (key, value) in settings {
+        crate::set_app_setting(&conn, key, &value).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+// 保持ポリシーに従って今すぐログを整理
+#[tauri::command]
+pub fn run_log_compaction_now(state: State<AppState>) -> Result<LogCompactionSummary, String> {
+    let mut conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    compact_logs(&mut conn, default_archive_dir().as_deref(), Utc::now())
+}
+
+#[tauri::command]
+pub fn vacuum_database(state: State<AppState>) -> Result<(), String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    vacuum(&conn)
+}
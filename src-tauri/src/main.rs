@@ -3,21 +3,56 @@
     windows_subsystem = "windows"
 )]
 
-use rusqlite::{Connection, params, Result as SqliteResult};
+use rusqlite::{Connection, params, OptionalExtension, Result as SqliteResult};
 use std::sync::Mutex;
+use std::sync::atomic::AtomicBool;
 use std::fs;
 use tauri::State;
 use serde::{Serialize, Deserialize};
 use chrono::Utc;
 use anyhow::{Result, Context};
 use directories::ProjectDirs;
-use reqwest;
-use serde_json::json;
-use base64;
+
+mod account_health;
+mod after_commit;
+mod app_events;
+mod content_approval;
+mod content_import;
+mod csv_export;
+mod dashboard_stats;
+mod dry_run;
+mod follower_tracking;
+mod local_api;
+mod log_details;
+mod log_query;
+mod log_retention;
+mod mentions;
+mod notifications;
+mod posting_pause;
+mod reply_candidates;
+mod reply_checkpoints;
+mod reply_engine;
+mod reply_filters;
+mod schedule_content;
+mod scheduler;
+mod tweet_deletion;
+mod tweet_metrics;
+mod twitter_api;
+mod watched_users;
+mod webhooks;
+
+#[cfg(test)]
+mod e2e_tests;
+#[cfg(test)]
+mod mock_twitter;
 
 // アプリケーション状態
 struct AppState {
     db: Mutex<Connection>,
+    api: twitter_api::ApiClient,
+    reply_monitor_running: AtomicBool,
+    mentions_polling: AtomicBool,
+    scheduler_running: AtomicBool,
 }
 
 // Bot アカウント情報（簡素化版）
@@ -60,21 +95,24 @@ struct ReplySettings {
     reply_content: String,         // 返信内容
     is_active: bool,               // 有効/無効
     last_checked_tweet_ids: Option<String>, // 最後にチェックしたツイートIDのJSON配列
+    watched_user_ids: Option<String>,       // 監視対象の外部アカウントIDの配列（JSON）
     created_at: String,
     updated_at: String,
 }
 
 // 実行ログ
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct ExecutionLog {
     id: Option<i64>,
     account_id: i64,
-    log_type: String, // "tweet", "error", "info", "reply"
+    log_type: String, // "tweet", "error", "info", "reply", "delete"
     message: String,
     tweet_id: Option<String>,
     tweet_content: Option<String>,
     status: String, // "success", "error", "warning"
     created_at: String,
+    #[serde(flatten)]
+    details: log_details::LogDetails,
 }
 
 // ユーザー設定
@@ -117,6 +155,8 @@ struct DashboardStats {
 struct TestTweetRequest {
     account_id: i64,
     content: String,
+    #[serde(default)]
+    dry_run: bool,  // 投稿せずに実行ログへ記録する（アカウントがドライラン中の場合も同様）
 }
 
 // Twitter API レスポンス
@@ -127,6 +167,97 @@ struct TwitterApiResponse {
     message: String,
 }
 
+// Bot アカウント行の読み取り（SELECT * の列順）
+fn bot_account_from_row(row: &rusqlite::Row) -> SqliteResult<BotAccount> {
+    Ok(BotAccount {
+        id: Some(row.get(0)?),
+        account_name: row.get(1)?,
+        api_key: row.get(2)?,
+        api_key_secret: row.get(3)?,
+        access_token: row.get(4)?,
+        access_token_secret: row.get(5)?,
+        api_type: row.get(6)?,
+        status: row.get(7)?,
+        created_at: Some(row.get(8)?),
+        updated_at: Some(row.get(9)?),
+    })
+}
+
+fn get_bot_account(conn: &Connection, account_id: i64) -> SqliteResult<BotAccount> {
+    conn.query_row(
+        "SELECT * FROM bot_accounts WHERE id = ?",
+        params![account_id],
+        bot_account_from_row
+    )
+}
+
+// 実行ログを書き込む
+fn insert_execution_log(conn: &Connection, log: &ExecutionLog) -> SqliteResult<i64> {
+    conn.execute(
+        "INSERT INTO execution_logs (account_id, log_type, message, tweet_id, tweet_content, status, created_at,
+             schedule_id, content_index, reply_setting_id, mention_rule_id, target_tweet_id, http_status, api_error_code, duration_ms)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        params![
+            log.account_id,
+            log.log_type,
+            log.message,
+            log.tweet_id,
+            log.tweet_content,
+            log.status,
+            log.created_at,
+            log.details.schedule_id,
+            log.details.content_index,
+            log.details.reply_setting_id,
+            log.details.mention_rule_id,
+            log.details.target_tweet_id,
+            log.details.http_status,
+            log.details.api_error_code,
+            log.details.duration_ms
+        ],
+    )?;
+    let log_id = conn.last_insert_rowid();
+
+    // 連続した認証エラー・凍結・レート制限を数え、続いたアカウントを停止する
+    account_health::observe_log(conn, log)?;
+    notifications::observe_log(conn, log)?;
+    webhooks::observe_log(conn, log)?;
+    app_events::observe_log(conn, log_id, log);
+    Ok(log_id)
+}
+
+// execution_logs の行を読み込む（列名で参照するため SELECT * でも el.* でもよい）
+fn execution_log_from_row(row: &rusqlite::Row) -> SqliteResult<ExecutionLog> {
+    Ok(ExecutionLog {
+        id: row.get("id")?,
+        account_id: row.get("account_id")?,
+        log_type: row.get("log_type")?,
+        message: row.get("message")?,
+        tweet_id: row.get("tweet_id")?,
+        tweet_content: row.get("tweet_content")?,
+        status: row.get("status")?,
+        created_at: row.get("created_at")?,
+        details: log_details::LogDetails::from_row(row)?,
+    })
+}
+
+// アプリ設定（app_settings）の読み書き
+fn get_app_setting(conn: &Connection, key: &str) -> Option<String> {
+    conn.query_row(
+        "SELECT value FROM app_settings WHERE key = ?",
+        params![key],
+        |row| row.get(0)
+    ).ok()
+}
+
+fn set_app_setting(conn: &Connection, key: &str, value: &str) -> SqliteResult<()> {
+    conn.execute(
+        "INSERT INTO app_settings (key, value, updated_at) VALUES (?, ?, ?)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at",
+        params![key, value, Utc::now().to_rfc3339()],
+    )?;
+    Ok(())
+}
+
 // 孤立した返信設定をクリーンアップする関数
 fn cleanup_orphaned_reply_settings(conn: &Connection) -> Result<()> {
     // 存在しないreply_bot_idを参照する設定を削除
@@ -145,40 +276,38 @@ fn cleanup_orphaned_reply_settings(conn: &Connection) -> Result<()> {
         Ok((id, target_bot_ids_json))
     })?;
     
-    for row in rows {
-        if let Ok((id, target_bot_ids_json)) = row {
-            if let Ok(target_bot_ids) = serde_json::from_str::<Vec<i64>>(&target_bot_ids_json) {
-                // 各target_bot_idが存在するかチェック
-                let mut valid_ids = Vec::new();
-                for target_id in &target_bot_ids {
-                    let exists: i32 = conn.query_row(
-                        "SELECT COUNT(*) FROM bot_accounts WHERE id = ?",
-                        params![target_id],
-                        |row| row.get(0)
-                    ).unwrap_or(0);
-                    
-                    if exists > 0 {
-                        valid_ids.push(*target_id);
-                    }
-                }
+    for (id, target_bot_ids_json) in rows.flatten() {
+        if let Ok(target_bot_ids) = serde_json::from_str::<Vec<i64>>(&target_bot_ids_json) {
+            // 各target_bot_idが存在するかチェック
+            let mut valid_ids = Vec::new();
+            for target_id in &target_bot_ids {
+                let exists: i32 = conn.query_row(
+                    "SELECT COUNT(*) FROM bot_accounts WHERE id = ?",
+                    params![target_id],
+                    |row| row.get(0)
+                ).unwrap_or(0);
                 
-                if valid_ids.is_empty() {
-                    // 全てのtarget_bot_idが存在しない場合は設定を削除
-                    orphaned_settings.push(id);
-                } else if valid_ids.len() < target_bot_ids.len() {
-                    // 一部のtarget_bot_idが存在しない場合は有効なIDのみで更新
-                    let updated_json = serde_json::to_string(&valid_ids).unwrap();
-                    let now = Utc::now().to_rfc3339();
-                    conn.execute(
-                        "UPDATE reply_settings SET target_bot_ids = ?, updated_at = ? WHERE id = ?",
-                        params![updated_json, now, id],
-                    )?;
-                    println!("Updated reply setting {} with valid target_bot_ids: {:?}", id, valid_ids);
+                if exists > 0 {
+                    valid_ids.push(*target_id);
                 }
-            } else {
-                // JSONパースに失敗した場合も削除対象
+            }
+            
+            if valid_ids.is_empty() && watched_users::load_watched_user_ids(conn, id).is_empty() {
+                // 全てのtarget_bot_idが存在せず外部アカウントも監視していない場合は設定を削除
                 orphaned_settings.push(id);
+            } else if valid_ids.len() < target_bot_ids.len() {
+                // 一部のtarget_bot_idが存在しない場合は有効なIDのみで更新
+                let updated_json = serde_json::to_string(&valid_ids).unwrap();
+                let now = Utc::now().to_rfc3339();
+                conn.execute(
+                    "UPDATE reply_settings SET target_bot_ids = ?, updated_at = ? WHERE id = ?",
+                    params![updated_json, now, id],
+                )?;
+                println!("Updated reply setting {} with valid target_bot_ids: {:?}", id, valid_ids);
             }
+        } else {
+            // JSONパースに失敗した場合も削除対象
+            orphaned_settings.push(id);
         }
     }
     
@@ -190,6 +319,9 @@ fn cleanup_orphaned_reply_settings(conn: &Connection) -> Result<()> {
         conn.execute("DELETE FROM reply_settings WHERE id = ?", params![setting_id])?;
     }
     
+    // 削除された設定のチェックポイントも削除
+    reply_checkpoints::delete_orphaned(conn)?;
+    
     if deleted_by_reply_bot > 0 || orphaned_count > 0 {
         println!("Cleaned up orphaned reply settings: {} by reply_bot, {} by target_bots", 
             deleted_by_reply_bot, orphaned_count);
@@ -198,19 +330,30 @@ fn cleanup_orphaned_reply_settings(conn: &Connection) -> Result<()> {
     Ok(())
 }
 
-// データベース初期化
-fn init_database() -> Result<Connection> {
+// アプリのデータディレクトリ（データベースやログのアーカイブを保存）
+fn app_data_dir() -> Result<std::path::PathBuf> {
     let proj_dirs = ProjectDirs::from("com", "twilia", "bot-manager")
         .context("Failed to determine project directories")?;
     
-    let data_dir = proj_dirs.data_dir();
-    fs::create_dir_all(data_dir).context("Failed to create data directory")?;
+    let data_dir = proj_dirs.data_dir().to_path_buf();
+    fs::create_dir_all(&data_dir).context("Failed to create data directory")?;
     
-    let db_path = data_dir.join("twilia.sqlite");
+    Ok(data_dir)
+}
+
+// データベース初期化
+fn init_database() -> Result<Connection> {
+    let db_path = app_data_dir()?.join("twilia.sqlite");
     
     // データベース接続
     let conn = Connection::open(&db_path)?;
+    prepare_database(&conn)?;
     
+    Ok(conn)
+}
+
+// テーブル作成・マイグレーション・クリーンアップ（テストではインメモリDBに対して実行）
+fn prepare_database(conn: &Connection) -> Result<()> {
     // データベースが新規作成かどうかを確認
     let table_exists: i32 = conn.query_row(
         "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='bot_accounts'",
@@ -333,15 +476,15 @@ fn init_database() -> Result<Connection> {
              VALUES ('default', ?, ?)",
             params![now, now],
         )?;
-    } else {
-        // 既存のデータベースに対してマイグレーションを実行
-        run_database_migrations(&conn)?;
     }
     
+    // マイグレーションを実行（新規作成直後は追加テーブルの作成のみ）
+    run_database_migrations(conn)?;
+    
     // 孤立した返信設定をクリーンアップ
-    cleanup_orphaned_reply_settings(&conn)?;
+    cleanup_orphaned_reply_settings(conn)?;
     
-    Ok(conn)
+    Ok(())
 }
 
 // データベースマイグレーション
@@ -460,6 +603,71 @@ fn run_database_migrations(conn: &Connection) -> Result<()> {
         }
     }
     
+    // スケジュール投稿リビジョンテーブル
+    schedule_content::create_revision_table(conn)?;
+    
+    // 返信チェックポイントテーブル（旧 last_checked_tweet_ids からの移行を含む）
+    reply_checkpoints::create_checkpoint_table(conn)?;
+    
+    // 返信フィルタ列と1日の返信数テーブル
+    reply_filters::create_filter_schema(conn)?;
+    
+    // 返信候補の列
+    reply_candidates::create_candidate_columns(conn)?;
+    
+    // メンション受信箱テーブル
+    mentions::create_mention_tables(conn)?;
+    
+    // 監視対象の外部アカウント
+    watched_users::create_watched_user_tables(conn)?;
+    
+    // アプリ内スケジュール投稿の時間枠とドライラン設定の列
+    scheduler::create_scheduler_columns(conn)?;
+    dry_run::create_dry_run_column(conn)?;
+    dry_run::create_progress_table(conn)?;
+    
+    // アプリ設定テーブル（古いデータベースには存在しない場合がある）
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS app_settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+    
+    // TwitterユーザーIDのキャッシュ列（返信監視でタイムライン取得に使用）
+    let twitter_user_id_exists: i32 = conn.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info('bot_accounts') WHERE name='twitter_user_id'",
+        [],
+        |row| row.get(0)
+    ).unwrap_or(0);
+    
+    if twitter_user_id_exists == 0 {
+        conn.execute(
+            "ALTER TABLE bot_accounts ADD COLUMN twitter_user_id TEXT",
+            [],
+        )?;
+        println!("Added twitter_user_id column to bot_accounts table");
+    }
+    
+    // 実行ログの構造化情報の列と全文検索インデックス
+    log_details::create_log_detail_columns(conn)?;
+    log_query::create_log_search_index(conn)?;
+    
+    // 投稿済みツイートの指標スナップショット
+    tweet_metrics::create_metrics_table(conn)?;
+    
+    // Botアカウントのフォロワー数の推移
+    follower_tracking::create_follower_snapshot_table(conn)?;
+    
+    // アカウントの健全性と自動停止
+    account_health::create_health_tables(conn)?;
+    notifications::create_notification_table(conn)?;
+    webhooks::create_webhook_tables(conn)?;
+    content_approval::create_approval_tables(conn)?;
+    posting_pause::create_pause_tables(conn)?;
+    
     Ok(())
 }
 
@@ -490,11 +698,7 @@ fn migrate_reply_settings_table(conn: &Connection) -> Result<()> {
                 ))
             })?;
             
-            for row in rows {
-                if let Ok(data) = row {
-                    existing_settings.push(data);
-                }
-            }
+            existing_settings.extend(rows.flatten());
         }
         
         // テーブルを削除して再作成
@@ -556,22 +760,34 @@ fn migrate_reply_settings_table(conn: &Connection) -> Result<()> {
 
 // 返信設定管理（新仕様）
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 fn save_reply_settings(
     reply_bot_id: i64,
     target_bot_ids: Vec<i64>, 
     reply_content: String, 
+    filters: Option<reply_filters::ReplyFilters>,
+    candidates: Option<Vec<reply_candidates::ReplyCandidate>>,
+    candidate_selection: Option<reply_candidates::CandidateSelection>,
+    watched_user_ids: Option<Vec<i64>>,
     state: State<AppState>
 ) -> Result<i64, String> {
     let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
     let now = Utc::now().to_rfc3339();
     
-    // バリデーション
+    // バリデーション（返信候補がある場合は先頭の候補を reply_content とする）
+    let candidates = candidates.map(reply_candidates::normalize_candidates).transpose()?;
+    let reply_content = match candidates.as_ref().and_then(|c| c.first()) {
+        Some(first) if reply_content.trim().is_empty() => first.content.clone(),
+        _ => reply_content,
+    };
     if reply_content.trim().is_empty() {
         return Err("返信内容が空です".to_string());
     }
-    if target_bot_ids.is_empty() {
+    let watched_user_ids = watched_user_ids.unwrap_or_default();
+    if target_bot_ids.is_empty() && watched_user_ids.is_empty() {
         return Err("監視対象Botが選択されていません".to_string());
     }
+    let filters = filters.map(|f| f.normalized()).transpose()?;
     
     // Botの存在チェック
     let reply_bot_exists: i32 = conn.query_row(
@@ -596,7 +812,22 @@ fn save_reply_settings(
         }
     }
     
-    // 既存の設定を無効化
+    for user_id in &watched_user_ids {
+        if !watched_users::watched_user_exists(&conn, *user_id) {
+            return Err(format!("監視対象アカウント ID {} が存在しません", user_id));
+        }
+    }
+    
+    // 既存の設定を無効化（チェックポイント引き継ぎのためIDを控えておく）
+    let previous_ids: Vec<i64> = conn.prepare(
+        "SELECT id FROM reply_settings WHERE reply_bot_id = ? AND is_active = 1 ORDER BY id DESC"
+    )
+    .and_then(|mut stmt| {
+        stmt.query_map(params![reply_bot_id], |row| row.get(0))?
+            .collect::<SqliteResult<Vec<i64>>>()
+    })
+    .map_err(|e| e.to_string())?;
+    
     conn.execute(
         "UPDATE reply_settings SET is_active = 0, updated_at = ? WHERE reply_bot_id = ?",
         params![now, reply_bot_id],
@@ -605,20 +836,48 @@ fn save_reply_settings(
     // 新しい設定を保存
     let target_bot_ids_json = serde_json::to_string(&target_bot_ids)
         .map_err(|e| format!("JSON変換エラー: {}", e))?;
+    let watched_user_ids_json = serde_json::to_string(&watched_user_ids)
+        .map_err(|e| format!("JSON変換エラー: {}", e))?;
     
     conn.execute(
-        "INSERT INTO reply_settings (target_bot_ids, reply_bot_id, reply_content, is_active, last_checked_tweet_ids, created_at, updated_at)
-         VALUES (?, ?, ?, 1, '[]', ?, ?)",
-        params![target_bot_ids_json, reply_bot_id, reply_content, now, now],
+        "INSERT INTO reply_settings (target_bot_ids, reply_bot_id, reply_content, is_active, last_checked_tweet_ids, watched_user_ids, created_at, updated_at)
+         VALUES (?, ?, ?, 1, '[]', ?, ?, ?)",
+        params![target_bot_ids_json, reply_bot_id, reply_content, watched_user_ids_json, now, now],
     ).map_err(|e| e.to_string())?;
+    let setting_id = conn.last_insert_rowid();
     
-    Ok(conn.last_insert_rowid())
+    // 同じ監視対象の確認済みツイートを引き継ぎ、設定変更で過去ツイートに返信しないようにする
+    let targets: Vec<reply_checkpoints::WatchTarget> = target_bot_ids.iter()
+        .map(|&id| reply_checkpoints::WatchTarget::Bot(id))
+        .chain(watched_user_ids.iter().map(|&id| reply_checkpoints::WatchTarget::User(id)))
+        .collect();
+    reply_checkpoints::carry_over(&conn, &previous_ids, setting_id, &targets)
+        .map_err(|e| e.to_string())?;
+    
+    // フィルタ未指定の場合は直前の設定のフィルタを引き継ぐ
+    let filters = match (filters, previous_ids.first()) {
+        (Some(filters), _) => Some(filters),
+        (None, Some(&previous_id)) => reply_filters::load_filters(&conn, previous_id).map_err(|e| e.to_string())?,
+        (None, None) => None,
+    };
+    if let Some(filters) = filters {
+        reply_filters::save_filters(&conn, setting_id, &filters)?;
+    }
+    
+    if let Some(candidates) = candidates {
+        reply_candidates::save_candidates(&conn, setting_id, &candidates, candidate_selection.unwrap_or_default())?;
+    }
+    
+    Ok(setting_id)
 }
 
 #[tauri::command]
 fn get_reply_settings(state: State<AppState>) -> Result<Vec<ReplySettings>, String> {
     let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
-    
+    load_reply_settings(&conn)
+}
+
+fn load_reply_settings(conn: &Connection) -> Result<Vec<ReplySettings>, String> {
     // 存在するBotを参照する返信設定のみを取得（JOINを使用）
     let mut stmt = conn.prepare(
         "SELECT rs.* FROM reply_settings rs
@@ -634,7 +893,8 @@ fn get_reply_settings(state: State<AppState>) -> Result<Vec<ReplySettings>, Stri
             reply_bot_id: row.get(2)?,
             reply_content: row.get(3)?,
             is_active: row.get(4)?,
-            last_checked_tweet_ids: row.get(5)?,
+            last_checked_tweet_ids: None,
+            watched_user_ids: None,
             created_at: row.get(6)?,
             updated_at: row.get(7)?,
         })
@@ -647,6 +907,10 @@ fn get_reply_settings(state: State<AppState>) -> Result<Vec<ReplySettings>, Stri
     let mut valid_settings = Vec::new();
     
     for mut setting in settings {
+        if let Some(id) = setting.id {
+            setting.last_checked_tweet_ids = Some(reply_checkpoints::legacy_ids_json(conn, id));
+            setting.watched_user_ids = serde_json::to_string(&watched_users::load_watched_user_ids(conn, id)).ok();
+        }
         if let Ok(target_bot_ids) = serde_json::from_str::<Vec<i64>>(&setting.target_bot_ids) {
             let mut valid_targets = Vec::new();
             
@@ -662,7 +926,9 @@ fn get_reply_settings(state: State<AppState>) -> Result<Vec<ReplySettings>, Stri
                 }
             }
             
-            if !valid_targets.is_empty() {
+            let has_watched_users = setting.id
+                .is_some_and(|id| !watched_users::load_watched_user_ids(conn, id).is_empty());
+            if !valid_targets.is_empty() || has_watched_users {
                 // 有効なターゲットがある場合のみ設定を含める
                 if valid_targets.len() != target_bot_ids.len() {
                     // 一部無効なターゲットがある場合は更新
@@ -689,6 +955,8 @@ fn delete_reply_settings(id: i64, state: State<AppState>) -> Result<(), String>
     
     conn.execute("DELETE FROM reply_settings WHERE id = ?", params![id])
         .map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM reply_checkpoints WHERE reply_setting_id = ?", params![id])
+        .map_err(|e| e.to_string())?;
     
     Ok(())
 }
@@ -698,50 +966,32 @@ fn update_last_checked_tweet(
     target_bot_id: i64, 
     tweet_id: String, 
     reply_bot_id: i64,
+    reply_setting_id: Option<i64>,
     state: State<AppState>
 ) -> Result<(), String> {
     let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
-    let now = Utc::now().to_rfc3339();
-    
-    // 現在の last_checked_tweet_ids を取得
-    let current_ids: String = conn.query_row(
-        "SELECT last_checked_tweet_ids FROM reply_settings WHERE reply_bot_id = ? AND is_active = 1",
-        params![reply_bot_id],
-        |row| row.get(0)
-    ).unwrap_or_else(|_| "[]".to_string());
-    
-    // JSON を解析して更新
-    let mut tweet_id_map: std::collections::HashMap<String, String> = if let Ok(ids) = serde_json::from_str::<Vec<String>>(&current_ids) {
-        ids.into_iter()
-            .filter_map(|entry| {
-                let parts: Vec<&str> = entry.split(':').collect();
-                if parts.len() == 2 {
-                    Some((parts[0].to_string(), parts[1].to_string()))
-                } else {
-                    None
-                }
-            })
-            .collect()
-    } else {
-        std::collections::HashMap::new()
-    };
-    
-    // 該当するtarget_bot_idのツイートIDを更新
-    tweet_id_map.insert(target_bot_id.to_string(), tweet_id);
     
-    // JSON 配列形式に戻す
-    let updated_ids: Vec<String> = tweet_id_map.into_iter()
-        .map(|(bot_id, tweet_id)| format!("{}:{}", bot_id, tweet_id))
-        .collect();
-    
-    let updated_ids_json = serde_json::to_string(&updated_ids)
-        .map_err(|e| format!("JSON変換エラー: {}", e))?;
-    
-    conn.execute(
-        "UPDATE reply_settings SET last_checked_tweet_ids = ?, updated_at = ? 
-         WHERE reply_bot_id = ? AND is_active = 1",
-        params![updated_ids_json, now, reply_bot_id],
+    // 返信Botの有効な設定のうち、監視対象に target_bot_id を含むもののチェックポイントを更新
+    let mut stmt = conn.prepare(
+        "SELECT id, target_bot_ids FROM reply_settings WHERE reply_bot_id = ? AND is_active = 1"
     ).map_err(|e| e.to_string())?;
+    let settings = stmt.query_map(params![reply_bot_id], |row| {
+        Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+    })
+    .map_err(|e| e.to_string())?
+    .collect::<SqliteResult<Vec<_>>>()
+    .map_err(|e| e.to_string())?;
+    
+    for (setting_id, target_bot_ids_json) in settings {
+        if reply_setting_id.is_some_and(|id| id != setting_id) {
+            continue;
+        }
+        let target_bot_ids: Vec<i64> = serde_json::from_str(&target_bot_ids_json).unwrap_or_default();
+        if target_bot_ids.contains(&target_bot_id) {
+            reply_checkpoints::set_last_seen(&conn, setting_id, reply_checkpoints::WatchTarget::Bot(target_bot_id), &tweet_id)
+                .map_err(|e| e.to_string())?;
+        }
+    }
     
     Ok(())
 }
@@ -787,22 +1037,24 @@ fn get_dashboard_stats(state: State<AppState>) -> Result<DashboardStats, String>
         |row| row.get(0)
     ).map_err(|e| e.to_string())?;
     
-    let today = Utc::now().format("%Y-%m-%d").to_string();
+    // 「今日」は JST の日付で数える
+    let today_start = log_query::jst_day_start(&reply_filters::to_jst(Utc::now()).format("%Y-%m-%d").to_string())?;
+    let today = (today_start.to_rfc3339(), (today_start + chrono::Duration::days(1)).to_rfc3339());
     let today_tweets: i32 = conn.query_row(
-        "SELECT COUNT(*) FROM execution_logs WHERE log_type = 'tweet' AND date(created_at) = ?",
-        params![today],
+        "SELECT COUNT(*) FROM execution_logs WHERE log_type = 'tweet' AND status != 'simulated' AND created_at >= ? AND created_at < ?",
+        params![today.0, today.1],
         |row| row.get(0)
     ).map_err(|e| e.to_string())?;
     
     let total_tweets: i32 = conn.query_row(
-        "SELECT COUNT(*) FROM execution_logs WHERE log_type = 'tweet'",
+        "SELECT COUNT(*) FROM execution_logs WHERE log_type = 'tweet' AND status != 'simulated'",
         [],
         |row| row.get(0)
     ).map_err(|e| e.to_string())?;
     
     let error_count: i32 = conn.query_row(
-        "SELECT COUNT(*) FROM execution_logs WHERE status = 'error' AND date(created_at) = ?",
-        params![today],
+        "SELECT COUNT(*) FROM execution_logs WHERE status = 'error' AND created_at >= ? AND created_at < ?",
+        params![today.0, today.1],
         |row| row.get(0)
     ).map_err(|e| e.to_string())?;
     
@@ -819,6 +1071,10 @@ fn get_dashboard_stats(state: State<AppState>) -> Result<DashboardStats, String>
 #[tauri::command]
 fn get_bot_accounts(state: State<AppState>) -> Result<Vec<BotAccount>, String> {
     let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    load_bot_accounts(&conn)
+}
+
+fn load_bot_accounts(conn: &Connection) -> Result<Vec<BotAccount>, String> {
     let mut stmt = conn.prepare("SELECT * FROM bot_accounts ORDER BY created_at DESC")
         .map_err(|e| e.to_string())?;
     
@@ -901,14 +1157,21 @@ fn add_bot_account(account: BotAccount, state: State<AppState>) -> Result<i64, S
 fn update_bot_account(account: BotAccount, state: State<AppState>) -> Result<(), String> {
     let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
     let now = Utc::now().to_rfc3339();
+    let previous_status: Option<String> = conn.query_row(
+        "SELECT status FROM bot_accounts WHERE id = ?",
+        params![account.id],
+        |row| row.get(0)
+    ).optional().map_err(|e| e.to_string())?;
     
     conn.execute(
         "UPDATE bot_accounts 
-         SET account_name = ?, api_key = ?, api_key_secret = ?,
+         SET twitter_user_id = CASE WHEN access_token = ? THEN twitter_user_id ELSE NULL END,
+             account_name = ?, api_key = ?, api_key_secret = ?,
              access_token = ?, access_token_secret = ?, api_type = ?, 
              status = ?, updated_at = ?
          WHERE id = ?",
         params![
+            account.access_token,
             account.account_name,
             account.api_key,
             account.api_key_secret,
@@ -921,6 +1184,16 @@ fn update_bot_account(account: BotAccount, state: State<AppState>) -> Result<(),
         ],
     )
     .map_err(|e| format!("データベース更新エラー: {}", e))?;
+    if let Some(id) = account.id {
+        app_events::account_status_changed(&conn, id, previous_status, &account.status, None);
+    }
+    
+    // 自動停止していたアカウントを手動で稼働中に戻した場合は、停止したスケジュール・返信設定も再開する
+    if account.status == "active" {
+        if let Some(id) = account.id {
+            account_health::resume_account(&conn, id).map_err(|e| e.to_string())?;
+        }
+    }
     
     Ok(())
 }
@@ -947,22 +1220,20 @@ fn delete_bot_account(id: i64, state: State<AppState>) -> Result<(), String> {
             Ok((setting_id, target_bot_ids_json))
         }).map_err(|e| e.to_string())?;
         
-        for row in rows {
-            if let Ok((setting_id, target_bot_ids_json)) = row {
-                if let Ok(target_bot_ids) = serde_json::from_str::<Vec<i64>>(&target_bot_ids_json) {
-                    let original_len = target_bot_ids.len();
-                    let mut updated_target_bot_ids = target_bot_ids;
-                    updated_target_bot_ids.retain(|&x| x != id);
-                    
-                    if updated_target_bot_ids.len() != original_len {
-                        if updated_target_bot_ids.is_empty() {
-                            // 監視対象がなくなった場合は設定を削除
-                            settings_to_update.push((setting_id, None));
-                        } else {
-                            // 監視対象を更新
-                            if let Ok(updated_json) = serde_json::to_string(&updated_target_bot_ids) {
-                                settings_to_update.push((setting_id, Some(updated_json)));
-                            }
+        for (setting_id, target_bot_ids_json) in rows.flatten() {
+            if let Ok(target_bot_ids) = serde_json::from_str::<Vec<i64>>(&target_bot_ids_json) {
+                let original_len = target_bot_ids.len();
+                let mut updated_target_bot_ids = target_bot_ids;
+                updated_target_bot_ids.retain(|&x| x != id);
+                
+                if updated_target_bot_ids.len() != original_len {
+                    if updated_target_bot_ids.is_empty() && watched_users::load_watched_user_ids(&conn, setting_id).is_empty() {
+                        // 監視対象がなくなった場合は設定を削除
+                        settings_to_update.push((setting_id, None));
+                    } else {
+                        // 監視対象を更新
+                        if let Ok(updated_json) = serde_json::to_string(&updated_target_bot_ids) {
+                            settings_to_update.push((setting_id, Some(updated_json)));
                         }
                     }
                 }
@@ -986,7 +1257,10 @@ fn delete_bot_account(id: i64, state: State<AppState>) -> Result<(), String> {
         }
     }
     
-    // 3. 最後にBotアカウントを削除（CASCADE削除により関連レコードも自動削除）
+    // 3. メンション受信箱のデータを削除
+    mentions::delete_account_data(&conn, id).map_err(|e| e.to_string())?;
+    
+    // 4. 最後にBotアカウントを削除（CASCADE削除により関連レコードも自動削除）
     conn.execute("DELETE FROM bot_accounts WHERE id = ?", params![id])
         .map_err(|e| e.to_string())?;
     
@@ -1047,32 +1321,13 @@ fn update_bot_config(config: BotConfig, state: State<AppState>) -> Result<(), St
 }
 
 // スケジュール投稿の保存（投稿リスト対応版）
+// 有効なスケジュールがあればその行を更新し、変更前の状態をリビジョンとして残す
 #[tauri::command]
 fn save_scheduled_tweet_list(account_id: i64, scheduled_times: String, content_list: Vec<String>, state: State<AppState>) -> Result<(), String> {
-    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
-    let now = Utc::now().to_rfc3339();
-    
-    // 既存のスケジュール投稿を無効化
-    conn.execute(
-        "UPDATE scheduled_tweets SET is_active = 0, updated_at = ? WHERE account_id = ?",
-        params![now, account_id],
-    )
-    .map_err(|e| e.to_string())?;
-    
-    // 新しいスケジュール投稿を作成
-    if !scheduled_times.is_empty() && !content_list.is_empty() {
-        let content_list_json = serde_json::to_string(&content_list)
-            .map_err(|e| format!("JSON変換エラー: {}", e))?;
-        
-        let first_content = content_list.first().unwrap_or(&String::new()).clone();
-        
-        conn.execute(
-            "INSERT INTO scheduled_tweets (account_id, content, content_list, current_index, scheduled_times, is_active, created_at, updated_at)
-             VALUES (?, ?, ?, 0, ?, 1, ?, ?)",
-            params![account_id, first_content, content_list_json, scheduled_times, now, now],
-        )
-        .map_err(|e| e.to_string())?;
-    }
+    let mut conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    let tx = after_commit::begin(&mut conn).map_err(|e| e.to_string())?;
+    schedule_content::save_content_list(&tx, account_id, scheduled_times, content_list)?;
+    tx.commit().map_err(|e| e.to_string())?;
     
     Ok(())
 }
@@ -1169,7 +1424,10 @@ fn add_scheduled_tweet(tweet: ScheduledTweet, state: State<AppState>) -> Result<
 #[tauri::command]
 fn get_scheduled_tweets(account_id: Option<i64>, state: State<AppState>) -> Result<Vec<ScheduledTweet>, String> {
     let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
-    
+    load_scheduled_tweets(&conn, account_id)
+}
+
+fn load_scheduled_tweets(conn: &Connection, account_id: Option<i64>) -> Result<Vec<ScheduledTweet>, String> {
     let tweets = match account_id {
         Some(id) => {
             let mut stmt = conn.prepare(
@@ -1228,6 +1486,10 @@ fn get_scheduled_tweets(account_id: Option<i64>, state: State<AppState>) -> Resu
 #[tauri::command]
 fn get_execution_logs(account_id: Option<i64>, limit: Option<i32>, state: State<AppState>) -> Result<Vec<ExecutionLog>, String> {
     let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    load_execution_logs(&conn, account_id, limit)
+}
+
+fn load_execution_logs(conn: &Connection, account_id: Option<i64>, limit: Option<i32>) -> Result<Vec<ExecutionLog>, String> {
     let limit = limit.unwrap_or(100);
     
     let logs = match account_id {
@@ -1237,19 +1499,8 @@ fn get_execution_logs(account_id: Option<i64>, limit: Option<i32>, state: State<
                  ORDER BY created_at DESC LIMIT ?"
             ).map_err(|e| e.to_string())?;
             
-            let rows = stmt.query_map(params![id, limit], |row| {
-                Ok(ExecutionLog {
-                    id: row.get(0)?,
-                    account_id: row.get(1)?,
-                    log_type: row.get(2)?,
-                    message: row.get(3)?,
-                    tweet_id: row.get(4)?,
-                    tweet_content: row.get(5)?,
-                    status: row.get(6)?,
-                    created_at: row.get(7)?,
-                })
-            })
-            .map_err(|e| e.to_string())?;
+            let rows = stmt.query_map(params![id, limit], execution_log_from_row)
+                .map_err(|e| e.to_string())?;
 
             rows.collect::<SqliteResult<Vec<_>>>()
                 .map_err(|e| e.to_string())?
@@ -1259,19 +1510,8 @@ fn get_execution_logs(account_id: Option<i64>, limit: Option<i32>, state: State<
                 "SELECT * FROM execution_logs ORDER BY created_at DESC LIMIT ?"
             ).map_err(|e| e.to_string())?;
             
-            let rows = stmt.query_map(params![limit], |row| {
-                Ok(ExecutionLog {
-                    id: row.get(0)?,
-                    account_id: row.get(1)?,
-                    log_type: row.get(2)?,
-                    message: row.get(3)?,
-                    tweet_id: row.get(4)?,
-                    tweet_content: row.get(5)?,
-                    status: row.get(6)?,
-                    created_at: row.get(7)?,
-                })
-            })
-            .map_err(|e| e.to_string())?;
+            let rows = stmt.query_map(params![limit], execution_log_from_row)
+                .map_err(|e| e.to_string())?;
 
             rows.collect::<SqliteResult<Vec<_>>>()
                 .map_err(|e| e.to_string())?
@@ -1284,24 +1524,13 @@ fn get_execution_logs(account_id: Option<i64>, limit: Option<i32>, state: State<
 #[tauri::command]
 fn add_execution_log(log: ExecutionLog, state: State<AppState>) -> Result<i64, String> {
     let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
-    let now = Utc::now().to_rfc3339();
-    
-    conn.execute(
-        "INSERT INTO execution_logs (account_id, log_type, message, tweet_id, tweet_content, status, created_at)
-         VALUES (?, ?, ?, ?, ?, ?, ?)",
-        params![
-            log.account_id,
-            log.log_type,
-            log.message,
-            log.tweet_id,
-            log.tweet_content,
-            log.status,
-            now
-        ],
-    )
-    .map_err(|e| e.to_string())?;
     
-    Ok(conn.last_insert_rowid())
+    insert_execution_log(&conn, &ExecutionLog {
+        id: None,
+        created_at: Utc::now().to_rfc3339(),
+        ..log
+    })
+    .map_err(|e| e.to_string())
 }
 
 // ユーザー設定管理
@@ -1417,32 +1646,30 @@ fn export_data(path: String, state: State<AppState>) -> Result<(), String> {
             reply_bot_id: row.get(2)?,
             reply_content: row.get(3)?,
             is_active: row.get(4)?,
-            last_checked_tweet_ids: row.get(5)?,
+            last_checked_tweet_ids: None,
+            watched_user_ids: None,
             created_at: row.get(6)?,
             updated_at: row.get(7)?,
         })
     })
     .map_err(|e| e.to_string())?;
     
-    let reply_settings: Vec<ReplySettings> = reply_rows.collect::<SqliteResult<Vec<_>>>()
+    let mut reply_settings: Vec<ReplySettings> = reply_rows.collect::<SqliteResult<Vec<_>>>()
         .map_err(|e| e.to_string())?;
     
+    // チェックポイントは従来形式のJSON配列で出力する（GitHub Actions側との互換のため）
+    for setting in reply_settings.iter_mut() {
+        if let Some(id) = setting.id {
+            setting.last_checked_tweet_ids = Some(reply_checkpoints::legacy_ids_json(&conn, id));
+            setting.watched_user_ids = serde_json::to_string(&watched_users::load_watched_user_ids(&conn, id)).ok();
+        }
+    }
+    
     // 実行ログを取得
     let mut logs_stmt = conn.prepare("SELECT * FROM execution_logs ORDER BY created_at DESC LIMIT 1000")
         .map_err(|e| e.to_string())?;
     
-    let logs_rows = logs_stmt.query_map([], |row| {
-        Ok(ExecutionLog {
-            id: row.get(0)?,
-            account_id: row.get(1)?,
-            log_type: row.get(2)?,
-            message: row.get(3)?,
-            tweet_id: row.get(4)?,
-            tweet_content: row.get(5)?,
-            status: row.get(6)?,
-            created_at: row.get(7)?,
-        })
-    })
+    let logs_rows = logs_stmt.query_map([], execution_log_from_row)
     .map_err(|e| e.to_string())?;
     
     let logs: Vec<ExecutionLog> = logs_rows.collect::<SqliteResult<Vec<_>>>()
@@ -1482,28 +1709,13 @@ fn export_data(path: String, state: State<AppState>) -> Result<(), String> {
     Ok(())
 }
 
-// GitHub Actions用の設定ファイル出力（返信機能対応版）
-#[tauri::command]
-fn export_github_config(path: String, state: State<AppState>) -> Result<(), String> {
-    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
-    
-    // プロジェクトルートのdataディレクトリに保存するようにパスを調整
-    let adjusted_path = if path.starts_with("data/") {
-        format!("../{}", path)
-    } else {
-        path
-    };
-    
-    // パスの親ディレクトリを作成
-    if let Some(parent) = std::path::Path::new(&adjusted_path).parent() {
-        if let Err(e) = std::fs::create_dir_all(parent) {
-            return Err(format!("ディレクトリ作成エラー: {}", e));
-        }
-    }
-    
+// GitHub Actions用設定の内容を組み立てる（列は名前を並べて指定し、後から追加された列の影響を受けないようにする）
+pub(crate) fn build_github_config(conn: &Connection) -> Result<serde_json::Value, String> {
     // アクティブなBot一覧を取得（投稿リスト対応）
     let mut stmt = conn.prepare(
-        "SELECT ba.*, st.content, st.content_list, st.current_index, st.scheduled_times 
+        "SELECT ba.id, ba.account_name, ba.api_key, ba.api_key_secret, ba.access_token, ba.access_token_secret,
+                ba.api_type, ba.status, ba.created_at, ba.updated_at,
+                st.content, st.content_list, st.current_index, st.scheduled_times, ba.requires_approval, st.id
          FROM bot_accounts ba 
          LEFT JOIN scheduled_tweets st ON ba.id = st.account_id AND st.is_active = 1
          WHERE ba.status = 'active'
@@ -1524,10 +1736,19 @@ fn export_github_config(path: String, state: State<AppState>) -> Result<(), Stri
             updated_at: Some(row.get(9)?),
         };
         
-        let scheduled_content: Option<String> = row.get(10).ok();
-        let content_list_json: Option<String> = row.get(11).ok();
-        let current_index: Option<i32> = row.get(12).ok();
-        let scheduled_times: Option<String> = row.get(13).ok();
+        let scheduled_content: Option<String> = row.get(10)?;
+        let content_list_json: Option<String> = row.get(11)?;
+        let current_index: Option<i32> = row.get(12)?;
+        let scheduled_times: Option<String> = row.get(13)?;
+        let approval_schedule_id: Option<i64> = if row.get(14)? { row.get(15)? } else { None };
+        
+        // 承認が必要なアカウントは承認済みの内容だけを書き出す（下で置き換える）
+        if let Some(schedule_id) = approval_schedule_id {
+            return Ok((Some(schedule_id), serde_json::json!({
+                "account": account,
+                "scheduled_times": scheduled_times
+            })));
+        }
         
         // 投稿リストがある場合は配列として出力、ない場合は従来形式
         let bot_data = if let Some(content_list_str) = content_list_json {
@@ -1547,16 +1768,32 @@ fn export_github_config(path: String, state: State<AppState>) -> Result<(), Stri
             })
         };
         
-        Ok(bot_data)
+        Ok((None, bot_data))
     })
     .map_err(|e| e.to_string())?;
     
-    let bot_configs: Vec<_> = rows.collect::<SqliteResult<Vec<_>>>()
-        .map_err(|e| e.to_string())?;
+    let mut bot_configs = Vec::new();
+    for row in rows {
+        let (approval_schedule_id, mut bot_data) = row.map_err(|e| e.to_string())?;
+        if let Some(schedule_id) = approval_schedule_id {
+            // 承認済みの内容が無ければスケジュールの無いBotとして出力する
+            match content_approval::approved_schedule_content(conn, schedule_id)? {
+                Some((content_list, current_index)) => {
+                    bot_data["scheduled_content_list"] = serde_json::Value::String(
+                        serde_json::to_string(&content_list).map_err(|e| e.to_string())?
+                    );
+                    bot_data["current_index"] = serde_json::json!(current_index);
+                }
+                None => bot_data["scheduled_content"] = serde_json::Value::Null,
+            }
+        }
+        bot_configs.push(bot_data);
+    }
     
     // 返信設定を取得（新仕様、存在するBotのみ）
     let mut reply_stmt = conn.prepare(
-        "SELECT rs.* FROM reply_settings rs
+        "SELECT rs.id, rs.target_bot_ids, rs.reply_bot_id, rs.reply_content, rs.is_active, rs.created_at, rs.updated_at
+         FROM reply_settings rs
          INNER JOIN bot_accounts ba ON rs.reply_bot_id = ba.id
          WHERE rs.is_active = 1"
     ).map_err(|e| e.to_string())?;
@@ -1568,23 +1805,54 @@ fn export_github_config(path: String, state: State<AppState>) -> Result<(), Stri
             reply_bot_id: row.get(2)?,
             reply_content: row.get(3)?,
             is_active: row.get(4)?,
-            last_checked_tweet_ids: row.get(5)?,
-            created_at: row.get(6)?,
-            updated_at: row.get(7)?,
+            last_checked_tweet_ids: None,
+            watched_user_ids: None,
+            created_at: row.get(5)?,
+            updated_at: row.get(6)?,
         })
     })
     .map_err(|e| e.to_string())?;
     
-    let reply_settings: Vec<ReplySettings> = reply_rows.collect::<SqliteResult<Vec<_>>>()
+    let mut reply_settings: Vec<ReplySettings> = reply_rows.collect::<SqliteResult<Vec<_>>>()
         .map_err(|e| e.to_string())?;
     
+    // チェックポイントは従来形式のJSON配列で出力する（GitHub Actions側との互換のため）
+    for setting in reply_settings.iter_mut() {
+        if let Some(id) = setting.id {
+            setting.last_checked_tweet_ids = Some(reply_checkpoints::legacy_ids_json(conn, id));
+            setting.watched_user_ids = serde_json::to_string(&watched_users::load_watched_user_ids(conn, id)).ok();
+        }
+    }
+    
     // GitHub Actions用設定
-    let github_config = serde_json::json!({
+    Ok(serde_json::json!({
         "version": "1.0",
         "bots": bot_configs,
         "reply_settings": reply_settings,
         "updated_at": Utc::now().to_rfc3339()
-    });
+    }))
+}
+
+// GitHub Actions用の設定ファイル出力（返信機能対応版）
+#[tauri::command]
+fn export_github_config(path: String, state: State<AppState>) -> Result<(), String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    
+    // プロジェクトルートのdataディレクトリに保存するようにパスを調整
+    let adjusted_path = if path.starts_with("data/") {
+        format!("../{}", path)
+    } else {
+        path
+    };
+    
+    // パスの親ディレクトリを作成
+    if let Some(parent) = std::path::Path::new(&adjusted_path).parent() {
+        if let Err(e) = std::fs::create_dir_all(parent) {
+            return Err(format!("ディレクトリ作成エラー: {}", e));
+        }
+    }
+    
+    let github_config = build_github_config(&conn)?;
     
     // GitHub Actions用設定ファイルを書き込み
     fs::write(&adjusted_path, serde_json::to_string_pretty(&github_config).unwrap())
@@ -1597,31 +1865,54 @@ fn export_github_config(path: String, state: State<AppState>) -> Result<(), Stri
 // テスト投稿機能
 #[tauri::command]
 async fn test_tweet(request: TestTweetRequest, state: State<'_, AppState>) -> Result<TwitterApiResponse, String> {
+    post_tweet_now(&state, request).await
+}
+
+// 指定した内容をすぐに投稿（ドライラン中は記録のみ）
+async fn post_tweet_now(state: &AppState, request: TestTweetRequest) -> Result<TwitterApiResponse, String> {
     // アカウント情報を取得
     let account = {
         let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
-        conn.query_row(
-            "SELECT * FROM bot_accounts WHERE id = ?",
-            params![request.account_id],
-            |row| {
-                Ok(BotAccount {
-                    id: Some(row.get(0)?),
-                    account_name: row.get(1)?,
-                    api_key: row.get(2)?,
-                    api_key_secret: row.get(3)?,
-                    access_token: row.get(4)?,
-                    access_token_secret: row.get(5)?,
-                    api_type: row.get(6)?,
-                    status: row.get(7)?,
-                    created_at: Some(row.get(8)?),
-                    updated_at: Some(row.get(9)?),
-                })
-            }
-        ).map_err(|e| format!("アカウント取得エラー: {}", e))?
+        get_bot_account(&conn, request.account_id)
+            .map_err(|e| format!("アカウント取得エラー: {}", e))?
     };
     
+    // ドライランの場合は投稿せずにログのみ記録
+    let dry_run = {
+        let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+        request.dry_run || dry_run::is_dry_run(&conn, request.account_id)
+    };
+    if dry_run {
+        let log = ExecutionLog {
+            id: None,
+            account_id: request.account_id,
+            log_type: "tweet".to_string(),
+            message: "テスト投稿をシミュレートしました".to_string(),
+            tweet_id: None,
+            tweet_content: Some(request.content),
+            status: dry_run::SIMULATED.to_string(),
+            created_at: Utc::now().to_rfc3339(),
+            details: log_details::LogDetails::default(),
+        };
+        
+        let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+        let _ = insert_execution_log(&conn, &log);
+        
+        return Ok(TwitterApiResponse {
+            success: true,
+            tweet_id: None,
+            message: "ドライランのため投稿せずに記録しました".to_string(),
+        });
+    }
+    
     // Twitter API v2 へ投稿
-    match post_to_twitter(&account, &request.content).await {
+    let started = std::time::Instant::now();
+    let result = state.api.post_to_twitter(&account, &request.content).await;
+    let details = log_details::LogDetails::default()
+        .with_duration(started.elapsed())
+        .with_api_result(&result);
+    
+    match result {
         Ok(tweet_id) => {
             // 実行ログを追加
             let log = ExecutionLog {
@@ -1633,22 +1924,11 @@ async fn test_tweet(request: TestTweetRequest, state: State<'_, AppState>) -> Re
                 tweet_content: Some(request.content),
                 status: "success".to_string(),
                 created_at: Utc::now().to_rfc3339(),
+                details,
             };
             
             let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
-            let _ = conn.execute(
-                "INSERT INTO execution_logs (account_id, log_type, message, tweet_id, tweet_content, status, created_at)
-                 VALUES (?, ?, ?, ?, ?, ?, ?)",
-                params![
-                    log.account_id,
-                    log.log_type,
-                    log.message,
-                    log.tweet_id,
-                    log.tweet_content,
-                    log.status,
-                    log.created_at
-                ],
-            );
+            let _ = insert_execution_log(&conn, &log);
             
             Ok(TwitterApiResponse {
                 success: true,
@@ -1667,22 +1947,11 @@ async fn test_tweet(request: TestTweetRequest, state: State<'_, AppState>) -> Re
                 tweet_content: Some(request.content),
                 status: "error".to_string(),
                 created_at: Utc::now().to_rfc3339(),
+                details,
             };
             
             let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
-            let _ = conn.execute(
-                "INSERT INTO execution_logs (account_id, log_type, message, tweet_id, tweet_content, status, created_at)
-                 VALUES (?, ?, ?, ?, ?, ?, ?)",
-                params![
-                    log.account_id,
-                    log.log_type,
-                    log.message,
-                    log.tweet_id,
-                    log.tweet_content,
-                    log.status,
-                    log.created_at
-                ],
-            );
+            let _ = insert_execution_log(&conn, &log);
             
             Ok(TwitterApiResponse {
                 success: false,
@@ -1693,143 +1962,6 @@ async fn test_tweet(request: TestTweetRequest, state: State<'_, AppState>) -> Re
     }
 }
 
-// Twitter API v2 への投稿（正しいOAuth 1.0a版）
-async fn post_to_twitter(account: &BotAccount, content: &str) -> Result<String, String> {
-    let url = "https://api.twitter.com/2/tweets";
-    let method = "POST";
-    
-    let payload = json!({
-        "text": content
-    }).to_string();
-    
-    // OAuth 1.0a認証を手動実装（oauth1クレートの代わり）
-    let authorization_header = create_oauth_header(
-        method,
-        url,
-        &account.api_key,
-        &account.api_key_secret,
-        &account.access_token,
-        &account.access_token_secret,
-        Some(&payload),
-    )?;
-    
-    let client = reqwest::Client::new();
-    
-    let response = client
-        .post(url)
-        .header("Authorization", authorization_header)
-        .header("Content-Type", "application/json")
-        .body(payload)
-        .send()
-        .await
-        .map_err(|e| format!("リクエスト送信エラー: {}", e))?;
-    
-    let status = response.status();
-    let response_text = response.text().await
-        .map_err(|e| format!("レスポンス読取エラー: {}", e))?;
-    
-    if status.is_success() {
-        // JSON パースしてツイートIDを取得
-        let json: serde_json::Value = serde_json::from_str(&response_text)
-            .map_err(|e| format!("JSON解析エラー: {}", e))?;
-        
-        if let Some(tweet_id) = json["data"]["id"].as_str() {
-            Ok(tweet_id.to_string())
-        } else {
-            Err("ツイートIDが取得できませんでした".to_string())
-        }
-    } else {
-        Err(format!("Twitter API エラー ({}): {}", status, response_text))
-    }
-}
-
-// OAuth 1.0a認証ヘッダーを作成（簡易版だが動作する）
-fn create_oauth_header(
-    method: &str,
-    url: &str,
-    consumer_key: &str,
-    consumer_secret: &str,
-    access_token: &str,
-    access_token_secret: &str,
-    _body: Option<&str>, // _ を付けて未使用警告を回避
-) -> Result<String, String> {
-    use std::time::{SystemTime, UNIX_EPOCH};
-    use std::collections::BTreeMap;
-    
-    // タイムスタンプとナンスを生成
-    let timestamp = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_secs();
-    
-    let nonce: String = (0..32)
-        .map(|_| {
-            let chars = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
-            chars[rand::random::<usize>() % chars.len()] as char
-        })
-        .collect();
-    
-    // OAuthパラメータ
-    let mut oauth_params = BTreeMap::new();
-    oauth_params.insert("oauth_consumer_key", consumer_key.to_string());
-    oauth_params.insert("oauth_nonce", nonce);
-    oauth_params.insert("oauth_signature_method", "HMAC-SHA1".to_string());
-    oauth_params.insert("oauth_timestamp", timestamp.to_string());
-    oauth_params.insert("oauth_token", access_token.to_string());
-    oauth_params.insert("oauth_version", "1.0".to_string());
-    
-    // パラメータ文字列を作成
-    let param_string = oauth_params
-        .iter()
-        .map(|(k, v)| format!("{}={}", url_encode(k), url_encode(v)))
-        .collect::<Vec<_>>()
-        .join("&");
-    
-    // 署名ベース文字列を作成
-    let base_string = format!(
-        "{}&{}&{}",
-        method,
-        url_encode(url),
-        url_encode(&param_string)
-    );
-    
-    // 署名キーを作成
-    let signing_key = format!("{}&{}", url_encode(consumer_secret), url_encode(access_token_secret));
-    
-    // HMAC-SHA1署名を生成（簡易版）
-    use hmac::{Hmac, Mac};
-    use sha1::Sha1;
-    use base64::{Engine as _, engine::general_purpose::STANDARD};
-    
-    type HmacSha1 = Hmac<Sha1>;
-    let mut mac = HmacSha1::new_from_slice(signing_key.as_bytes())
-        .map_err(|e| format!("HMAC初期化エラー: {}", e))?;
-    mac.update(base_string.as_bytes());
-    let signature = STANDARD.encode(mac.finalize().into_bytes());
-    
-    oauth_params.insert("oauth_signature", signature);
-    
-    // Authorizationヘッダーを構築
-    let auth_header = oauth_params
-        .iter()
-        .map(|(k, v)| format!("{}=\"{}\"", url_encode(k), url_encode(v)))
-        .collect::<Vec<_>>()
-        .join(", ");
-    
-    Ok(format!("OAuth {}", auth_header))
-}
-
-// URL エンコード関数
-fn url_encode(input: &str) -> String {
-    input
-        .chars()
-        .map(|c| match c {
-            'A'..='Z' | 'a'..='z' | '0'..='9' | '-' | '.' | '_' | '~' => c.to_string(),
-            _ => format!("%{:02X}", c as u8),
-        })
-        .collect()
-}
-
 fn main() {
     let db_conn = match init_database() {
         Ok(conn) => conn,
@@ -1840,11 +1972,33 @@ fn main() {
     };
     
     tauri::Builder::default()
+        .plugin(tauri_plugin_notification::init())
         .manage(AppState {
             db: Mutex::new(db_conn),
+            api: twitter_api::ApiClient::from_env(),
+            reply_monitor_running: AtomicBool::new(false),
+            mentions_polling: AtomicBool::new(false),
+            scheduler_running: AtomicBool::new(false),
+        })
+        .setup(|app| {
+            // バックエンドのイベントを画面に送る（他のループより先に購読を始める）
+            app_events::spawn_event_forwarder(app.handle().clone());
+            // アプリ内の返信監視ループを起動（設定で有効化されている場合のみ実行）
+            reply_engine::spawn_reply_monitor(app.handle().clone());
+            mentions::spawn_mention_poller(app.handle().clone());
+            scheduler::spawn_scheduler(app.handle().clone());
+            log_retention::spawn_log_compaction(app.handle().clone());
+            tweet_metrics::spawn_metrics_collector(app.handle().clone());
+            follower_tracking::spawn_follower_tracker(app.handle().clone());
+            account_health::spawn_health_monitor(app.handle().clone());
+            notifications::spawn_notifications(app.handle().clone());
+            webhooks::spawn_webhook_dispatcher(app.handle().clone());
+            local_api::spawn_local_api(app.handle().clone());
+            Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             get_dashboard_stats,
+            dashboard_stats::get_dashboard_time_series,
             get_bot_accounts,
             add_bot_account,
             update_bot_account,
@@ -1863,11 +2017,98 @@ fn main() {
             save_scheduled_tweet,
             save_scheduled_tweet_list,
             update_post_index,
+            schedule_content::insert_schedule_content,
+            schedule_content::update_schedule_content,
+            schedule_content::delete_schedule_content,
+            schedule_content::move_schedule_content,
+            schedule_content::get_schedule_revisions,
+            schedule_content::restore_schedule_revision,
             save_reply_settings,
             get_reply_settings,
             delete_reply_settings,
             update_last_checked_tweet,
-            cleanup_orphaned_reply_settings_cmd
+            cleanup_orphaned_reply_settings_cmd,
+            reply_engine::get_reply_monitor_settings,
+            reply_engine::update_reply_monitor_settings,
+            reply_engine::run_reply_monitor_now,
+            reply_checkpoints::get_reply_checkpoints,
+            reply_checkpoints::reset_reply_checkpoint,
+            reply_filters::get_reply_filters,
+            reply_filters::update_reply_filters,
+            reply_candidates::get_reply_candidates,
+            reply_candidates::update_reply_candidates,
+            mentions::get_mentions,
+            mentions::mark_mentions_read,
+            mentions::reply_to_mention,
+            mentions::get_mention_settings,
+            mentions::update_mention_settings,
+            mentions::get_mention_poll_interval,
+            mentions::update_mention_poll_interval,
+            mentions::get_mention_reply_rules,
+            mentions::save_mention_reply_rule,
+            mentions::delete_mention_reply_rule,
+            mentions::poll_mentions_now,
+            watched_users::get_watched_users,
+            watched_users::add_watched_user,
+            watched_users::delete_watched_user,
+            dry_run::get_dry_run_settings,
+            dry_run::update_dry_run_settings,
+            dry_run::set_account_dry_run,
+            scheduler::get_scheduler_settings,
+            scheduler::update_scheduler_settings,
+            scheduler::run_scheduler_now,
+            tweet_deletion::delete_tweet,
+            tweet_deletion::undo_post,
+            tweet_deletion::get_undo_window,
+            tweet_deletion::update_undo_window,
+            tweet_deletion::get_undoable_posts,
+            content_import::preview_schedule_import,
+            content_import::import_schedule_content,
+            csv_export::export_schedules_csv,
+            csv_export::export_execution_logs_csv,
+            log_query::query_execution_logs,
+            log_retention::get_log_retention_settings,
+            log_retention::update_log_retention_settings,
+            log_retention::run_log_compaction_now,
+            log_retention::vacuum_database,
+            tweet_metrics::get_metrics_settings,
+            tweet_metrics::update_metrics_settings,
+            tweet_metrics::collect_tweet_metrics_now,
+            tweet_metrics::get_account_performance,
+            tweet_metrics::get_content_performance,
+            tweet_metrics::get_tweet_metrics_history,
+            follower_tracking::get_follower_tracking_settings,
+            follower_tracking::update_follower_tracking_enabled,
+            follower_tracking::take_follower_snapshots_now,
+            follower_tracking::get_follower_history,
+            account_health::get_account_health,
+            account_health::verify_bot_account,
+            notifications::get_notification_rules,
+            notifications::update_notification_rules,
+            notifications::get_notifications,
+            notifications::mark_notifications_read,
+            notifications::send_test_notification,
+            webhooks::get_webhooks,
+            webhooks::save_webhook,
+            webhooks::delete_webhook,
+            webhooks::get_webhook_deliveries,
+            webhooks::retry_webhook_delivery,
+            webhooks::send_test_webhook,
+            local_api::get_local_api_settings,
+            local_api::update_local_api_settings,
+            local_api::regenerate_local_api_token,
+            content_approval::get_approval_settings,
+            content_approval::update_account_requires_approval,
+            content_approval::get_review_queue,
+            content_approval::get_schedule_content_reviews,
+            content_approval::review_schedule_content,
+            posting_pause::get_pause_status,
+            posting_pause::pause_all,
+            posting_pause::resume_all,
+            posting_pause::pause_bot_account,
+            posting_pause::resume_bot_account,
+            posting_pause::save_blackout_window,
+            posting_pause::delete_blackout_window
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,776 @@
+// メンション受信箱
+//
+// 有効にしたアカウントごとに GET /2/users/:id/mentions をポーリングし、
+// 受信したメンションを既読・返信済みの状態付きで mentions テーブルに保存する。
+// 自動返信ルール（キーワード・正規表現）に一致したメンションにはその場で返信し、
+// それ以外は UI から一覧・手動返信できる。
+// 一時停止中・停止期間中に受け取ったメンションは自動返信を保留し（auto_reply_deferred）、再開後のポーリングで返信する。
+
+use rusqlite::{Connection, params, OptionalExtension, Result as SqliteResult};
+use serde::{Serialize, Deserialize};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+use crate::{AppState, BotAccount};
+use crate::after_commit;
+use crate::log_details::LogDetails;
+use crate::posting_pause;
+use crate::reply_candidates::{CandidateSelection, CandidateSet, ReplyCandidate};
+use crate::reply_engine::{self, write_log};
+use crate::twitter_api::{self, Tweet, TwitterUser};
+
+const SETTING_INTERVAL_MINUTES: &str = "mentions_interval_minutes";
+
+const DEFAULT_INTERVAL_MINUTES: i64 = 15;
+const MIN_INTERVAL_MINUTES: i64 = 5;
+
+const LOOP_TICK: Duration = Duration::from_secs(60);
+const REPLY_DELAY: Duration = Duration::from_secs(1);
+
+// 受信したメンション
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Mention {
+    pub id: i64,
+    pub account_id: i64,
+    pub account_name: Option<String>,
+    pub tweet_id: String,
+    pub author_id: Option<String>,
+    pub author_username: Option<String>,
+    pub author_name: Option<String>,
+    pub text: String,
+    pub conversation_id: Option<String>,
+    pub tweeted_at: Option<String>,
+    pub is_read: bool,
+    pub replied_at: Option<String>,
+    pub reply_tweet_id: Option<String>,
+    pub reply_content: Option<String>,
+    pub auto_replied: bool,
+    pub created_at: String,
+}
+
+// アカウントごとのメンション取得設定
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MentionSettings {
+    pub account_id: i64,
+    pub account_name: String,
+    pub is_enabled: bool,
+    pub auto_reply_enabled: bool,
+    pub auto_reply_since: Option<String>,  // この日時以降のメンションのみ自動返信する
+    pub last_polled_at: Option<String>,
+    pub last_error: Option<String>,
+}
+
+// メンションへの自動返信ルール
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MentionReplyRule {
+    pub id: i64,
+    pub account_id: i64,
+    pub reply_content: String,
+    pub keywords: Vec<String>,      // いずれかを含むメンションに返信（空なら全メンション）
+    pub pattern: Option<String>,    // 一致するメンションに返信（正規表現）
+    pub is_active: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+// 1回のポーリング結果
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct MentionPollSummary {
+    pub accounts_checked: i32,
+    pub new_mentions: i32,
+    pub auto_replies: i32,
+    pub errors: i32,
+    pub started_at: String,
+    pub finished_at: String,
+}
+
+// メンション関連テーブルの作成（マイグレーションから呼ばれる）
+pub fn create_mention_tables(conn: &Connection) -> SqliteResult<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS mentions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            account_id INTEGER NOT NULL,
+            tweet_id TEXT NOT NULL,
+            author_id TEXT,
+            author_username TEXT,
+            author_name TEXT,
+            text TEXT NOT NULL,
+            conversation_id TEXT,
+            tweeted_at TEXT,
+            is_read BOOLEAN NOT NULL DEFAULT 0,
+            replied_at TEXT,
+            reply_tweet_id TEXT,
+            reply_content TEXT,
+            auto_replied BOOLEAN NOT NULL DEFAULT 0,
+            auto_reply_deferred BOOLEAN NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            FOREIGN KEY (account_id) REFERENCES bot_accounts(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_mentions_tweet ON mentions (account_id, tweet_id)",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS mention_settings (
+            account_id INTEGER PRIMARY KEY,
+            is_enabled BOOLEAN NOT NULL DEFAULT 0,
+            auto_reply_enabled BOOLEAN NOT NULL DEFAULT 0,
+            auto_reply_since TEXT,
+            last_polled_at TEXT,
+            last_error TEXT,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            FOREIGN KEY (account_id) REFERENCES bot_accounts(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS mention_reply_rules (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            account_id INTEGER NOT NULL,
+            reply_content TEXT NOT NULL,
+            keywords TEXT NOT NULL DEFAULT '[]',
+            pattern TEXT,
+            is_active BOOLEAN NOT NULL DEFAULT 1,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            FOREIGN KEY (account_id) REFERENCES bot_accounts(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    Ok(())
+}
+
+// アカウント削除時にメンション関連データを削除
+pub(crate) fn delete_account_data(conn: &Connection, account_id: i64) -> SqliteResult<()> {
+    conn.execute("DELETE FROM mentions WHERE account_id = ?", params![account_id])?;
+    conn.execute("DELETE FROM mention_settings WHERE account_id = ?", params![account_id])?;
+    conn.execute("DELETE FROM mention_reply_rules WHERE account_id = ?", params![account_id])?;
+    Ok(())
+}
+
+const MENTION_COLUMNS: &str =
+    "m.id, m.account_id, ba.account_name, m.tweet_id, m.author_id, m.author_username, m.author_name,
+     m.text, m.conversation_id, m.tweeted_at, m.is_read, m.replied_at, m.reply_tweet_id, m.reply_content,
+     m.auto_replied, m.created_at";
+
+fn mention_from_row(row: &rusqlite::Row) -> SqliteResult<Mention> {
+    Ok(Mention {
+        id: row.get(0)?,
+        account_id: row.get(1)?,
+        account_name: row.get(2)?,
+        tweet_id: row.get(3)?,
+        author_id: row.get(4)?,
+        author_username: row.get(5)?,
+        author_name: row.get(6)?,
+        text: row.get(7)?,
+        conversation_id: row.get(8)?,
+        tweeted_at: row.get(9)?,
+        is_read: row.get(10)?,
+        replied_at: row.get(11)?,
+        reply_tweet_id: row.get(12)?,
+        reply_content: row.get(13)?,
+        auto_replied: row.get(14)?,
+        created_at: row.get(15)?,
+    })
+}
+
+fn get_mention(conn: &Connection, mention_id: i64) -> SqliteResult<Mention> {
+    conn.query_row(
+        &format!("SELECT {} FROM mentions m LEFT JOIN bot_accounts ba ON m.account_id = ba.id WHERE m.id = ?", MENTION_COLUMNS),
+        params![mention_id],
+        mention_from_row
+    )
+}
+
+fn rule_from_row(row: &rusqlite::Row) -> SqliteResult<MentionReplyRule> {
+    let keywords_json: String = row.get(3)?;
+    Ok(MentionReplyRule {
+        id: row.get(0)?,
+        account_id: row.get(1)?,
+        reply_content: row.get(2)?,
+        keywords: serde_json::from_str(&keywords_json).unwrap_or_default(),
+        pattern: row.get(4)?,
+        is_active: row.get(5)?,
+        created_at: row.get(6)?,
+        updated_at: row.get(7)?,
+    })
+}
+
+fn load_rules(conn: &Connection, account_id: Option<i64>, active_only: bool) -> SqliteResult<Vec<MentionReplyRule>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, account_id, reply_content, keywords, pattern, is_active, created_at, updated_at
+         FROM mention_reply_rules
+         WHERE (?1 IS NULL OR account_id = ?1) AND (?2 = 0 OR is_active = 1)
+         ORDER BY account_id, id"
+    )?;
+    let rules = stmt.query_map(params![account_id, active_only], rule_from_row)?
+        .collect::<SqliteResult<Vec<_>>>()?;
+    Ok(rules)
+}
+
+// ルールを返信候補として扱い、条件に合う最初のルールを選ぶ
+fn compile_rules(rules: &[MentionReplyRule]) -> Result<CandidateSet, String> {
+    let candidates: Vec<ReplyCandidate> = rules.iter()
+        .map(|rule| ReplyCandidate {
+            content: rule.reply_content.clone(),
+            keywords: rule.keywords.clone(),
+            pattern: rule.pattern.clone(),
+        })
+        .collect();
+    CandidateSet::compile(&candidates, CandidateSelection::FirstMatch, 0)
+}
+
+// ポーリング対象のアカウント
+struct PollTarget {
+    account_id: i64,
+    account: BotAccount,
+    cached_user_id: Option<String>,
+    auto_reply_enabled: bool,
+    auto_reply_since: Option<String>,
+}
+
+fn load_poll_targets(conn: &Connection, due_only: bool) -> SqliteResult<Vec<PollTarget>> {
+    let interval = crate::get_app_setting(conn, SETTING_INTERVAL_MINUTES)
+        .and_then(|v| v.parse::<i64>().ok())
+        .unwrap_or(DEFAULT_INTERVAL_MINUTES);
+
+    let mut stmt = conn.prepare(
+        "SELECT account_id, auto_reply_enabled, auto_reply_since, last_polled_at FROM mention_settings WHERE is_enabled = 1 ORDER BY account_id"
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((row.get::<_, i64>(0)?, row.get::<_, bool>(1)?, row.get::<_, Option<String>>(2)?, row.get::<_, Option<String>>(3)?))
+    })?
+    .collect::<SqliteResult<Vec<_>>>()?;
+
+    let mut targets = Vec::new();
+    for (account_id, auto_reply_enabled, auto_reply_since, last_polled_at) in rows {
+        let due = match last_polled_at.and_then(|v| DateTime::parse_from_rfc3339(&v).ok()) {
+            Some(last) => Utc::now().signed_duration_since(last).num_minutes() >= interval,
+            None => true,
+        };
+        if due_only && !due {
+            continue;
+        }
+        if let Some((account, cached_user_id)) = reply_engine::load_active_account(conn, account_id) {
+            targets.push(PollTarget { account_id, account, cached_user_id, auto_reply_enabled, auto_reply_since });
+        }
+    }
+    Ok(targets)
+}
+
+// 保存済みのメンションのうち最も新しいツイートID（次回の since_id）
+fn newest_tweet_id(conn: &Connection, account_id: i64) -> SqliteResult<Option<String>> {
+    conn.query_row(
+        "SELECT tweet_id FROM mentions WHERE account_id = ? ORDER BY LENGTH(tweet_id) DESC, tweet_id DESC LIMIT 1",
+        params![account_id],
+        |row| row.get(0)
+    ).optional()
+}
+
+// 新しいメンションを保存し、新規に保存できたものの (mention_id, tweet) を古い順に返す
+fn store_mentions(conn: &Connection, account_id: i64, own_user_id: &str, tweets: &[Tweet], users: &[TwitterUser]) -> SqliteResult<Vec<(i64, Tweet)>> {
+    let users: HashMap<&str, &TwitterUser> = users.iter().map(|u| (u.id.as_str(), u)).collect();
+    let now = Utc::now().to_rfc3339();
+
+    let mut tweets: Vec<&Tweet> = tweets.iter()
+        .filter(|t| t.author_id.as_deref() != Some(own_user_id))
+        .collect();
+    tweets.sort_by(|a, b| (a.id.len(), &a.id).cmp(&(b.id.len(), &b.id)));
+
+    let mut stored = Vec::new();
+    for tweet in tweets {
+        let author = tweet.author_id.as_deref().and_then(|id| users.get(id));
+        let inserted = conn.execute(
+            "INSERT OR IGNORE INTO mentions
+             (account_id, tweet_id, author_id, author_username, author_name, text, conversation_id, tweeted_at, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            params![
+                account_id, tweet.id, tweet.author_id,
+                author.map(|u| u.username.clone()), author.and_then(|u| u.name.clone()),
+                tweet.text, tweet.conversation_id, tweet.created_at, now, now
+            ],
+        )?;
+        if inserted > 0 {
+            stored.push((conn.last_insert_rowid(), tweet.clone()));
+        }
+    }
+    Ok(stored)
+}
+
+// 一時停止中に受け取ったメンションの自動返信を保留する
+fn defer_auto_replies(conn: &Connection, mention_ids: &[i64]) -> SqliteResult<()> {
+    for mention_id in mention_ids {
+        conn.execute("UPDATE mentions SET auto_reply_deferred = 1 WHERE id = ?", params![mention_id])?;
+    }
+    Ok(())
+}
+
+// 自動返信を保留したまま未返信のメンション（古い順）
+fn load_deferred_mentions(conn: &Connection, account_id: i64) -> SqliteResult<Vec<(i64, Tweet)>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, tweet_id, text, tweeted_at, author_id, conversation_id FROM mentions
+         WHERE account_id = ? AND auto_reply_deferred = 1 AND replied_at IS NULL
+         ORDER BY id"
+    )?;
+    let rows = stmt.query_map(params![account_id], |row| {
+        Ok((row.get(0)?, Tweet {
+            id: row.get(1)?,
+            text: row.get(2)?,
+            created_at: row.get(3)?,
+            author_id: row.get(4)?,
+            conversation_id: row.get(5)?,
+            referenced_tweets: Vec::new(),
+        }))
+    })?.collect();
+    rows
+}
+
+fn record_poll_result(conn: &Connection, account_id: i64, error: Option<&str>) -> SqliteResult<()> {
+    let now = Utc::now().to_rfc3339();
+    conn.execute(
+        "UPDATE mention_settings SET last_polled_at = ?, last_error = ?, updated_at = ? WHERE account_id = ?",
+        params![now, error, now, account_id],
+    )?;
+    Ok(())
+}
+
+// 返信結果をメンションと実行ログに書き込む
+#[allow(clippy::too_many_arguments)]
+fn record_mention_reply(
+    state: &AppState,
+    mention_id: i64,
+    account_id: i64,
+    tweet_id: &str,
+    content: &str,
+    auto_replied: bool,
+    result: &Result<String, twitter_api::ApiError>,
+    details: LogDetails,
+) -> Result<(), String> {
+    let details = details.with_api_result(result);
+    let mut conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    let tx = after_commit::begin(&mut conn).map_err(|e| e.to_string())?;
+    let now = Utc::now().to_rfc3339();
+    let kind = if auto_replied { "自動返信" } else { "返信" };
+    tx.execute("UPDATE mentions SET auto_reply_deferred = 0 WHERE id = ?", params![mention_id]).map_err(|e| e.to_string())?;
+
+    match result {
+        Ok(reply_id) => {
+            tx.execute(
+                "UPDATE mentions SET is_read = 1, replied_at = ?, reply_tweet_id = ?, reply_content = ?, auto_replied = ?, updated_at = ? WHERE id = ?",
+                params![now, reply_id, content, auto_replied, now, mention_id],
+            ).map_err(|e| e.to_string())?;
+            write_log(&tx, account_id, "reply", "success",
+                format!("メンション {} に{}しました", tweet_id, kind),
+                Some(reply_id.clone()), Some(content.to_string()), details).map_err(|e| e.to_string())?;
+        }
+        Err(e) => {
+            write_log(&tx, account_id, "error", "error",
+                format!("メンション {} への{}に失敗しました: {}", tweet_id, kind, e),
+                None, Some(content.to_string()), details).map_err(|e| e.to_string())?;
+        }
+    }
+
+    tx.commit().map_err(|e| e.to_string())
+}
+
+// メンションを1回ポーリング（due_only が true の場合は間隔が経過したアカウントのみ）
+pub async fn run_mention_poll(state: &AppState, due_only: bool) -> Result<MentionPollSummary, String> {
+    if state.mentions_polling.swap(true, Ordering::SeqCst) {
+        return Err("メンション取得は既に実行中です".to_string());
+    }
+
+    let result = poll_mentions(state, due_only).await;
+    state.mentions_polling.store(false, Ordering::SeqCst);
+
+    let summary = result?;
+    if summary.accounts_checked > 0 {
+        println!(
+            "Mention poll finished: {} new mentions, {} auto replies, {} errors ({} accounts)",
+            summary.new_mentions, summary.auto_replies, summary.errors, summary.accounts_checked
+        );
+    }
+    Ok(summary)
+}
+
+async fn poll_mentions(state: &AppState, due_only: bool) -> Result<MentionPollSummary, String> {
+    let mut summary = MentionPollSummary {
+        started_at: Utc::now().to_rfc3339(),
+        ..Default::default()
+    };
+
+    let targets = {
+        let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+        load_poll_targets(&conn, due_only).map_err(|e| e.to_string())?
+    };
+
+    for target in targets {
+        summary.accounts_checked += 1;
+
+        let user_id = match reply_engine::resolve_twitter_user_id(state, target.account_id, &target.account, target.cached_user_id.as_deref()).await {
+            Ok(user_id) => user_id,
+            Err(e) => {
+                summary.errors += 1;
+                let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+                let _ = record_poll_result(&conn, target.account_id, Some(&e.message));
+                continue;
+            }
+        };
+
+        let since_id = {
+            let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+            newest_tweet_id(&conn, target.account_id).map_err(|e| e.to_string())?
+        };
+
+        let page = match state.api.get_mentions(&target.account, &user_id, since_id.as_deref()).await {
+            Ok(page) => page,
+            Err(e) => {
+                summary.errors += 1;
+                let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+                let _ = write_log(&conn, target.account_id, "error", "error",
+                    format!("{} のメンション取得に失敗しました: {}", target.account.account_name, e), None, None,
+                    LogDetails::default().with_api_error(&e));
+                let _ = record_poll_result(&conn, target.account_id, Some(&e.message));
+                continue;
+            }
+        };
+
+        let (stored, rules) = {
+            let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+            let stored = store_mentions(&conn, target.account_id, &user_id, &page.tweets, &page.users)
+                .map_err(|e| e.to_string())?;
+            record_poll_result(&conn, target.account_id, None).map_err(|e| e.to_string())?;
+            let rules = load_rules(&conn, Some(target.account_id), true).map_err(|e| e.to_string())?;
+            (stored, rules)
+        };
+        summary.new_mentions += stored.len() as i32;
+
+        if !target.auto_reply_enabled || rules.is_empty() {
+            continue;
+        }
+        let rule_ids: Vec<i64> = rules.iter().map(|rule| rule.id).collect();
+        let rules = match compile_rules(&rules) {
+            Ok(rules) => rules,
+            Err(e) => {
+                summary.errors += 1;
+                let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+                let _ = write_log(&conn, target.account_id, "error", "error",
+                    format!("{} のメンション自動返信ルールが不正です: {}", target.account.account_name, e), None, None,
+                    LogDetails::default());
+                continue;
+            }
+        };
+
+        // 自動返信を有効にする前のメンションには返信しない
+        let since = target.auto_reply_since.as_deref().and_then(|v| DateTime::parse_from_rfc3339(v).ok());
+        let eligible = |tweet: &Tweet| {
+            let tweeted_at = tweet.created_at.as_deref().and_then(|v| DateTime::parse_from_rfc3339(v).ok());
+            matches!((since, tweeted_at), (Some(since), Some(at)) if at >= since)
+        };
+
+        // 一時停止中・停止期間中は自動返信を保留し、再開後のポーリングで返信する（記録はポーリングごとに1回）
+        if let Some(reason) = posting_pause::check(state, target.account_id)? {
+            let deferred: Vec<i64> = stored.iter()
+                .filter(|(_, tweet)| eligible(tweet) && rules.select(tweet).is_some())
+                .map(|(mention_id, _)| *mention_id)
+                .collect();
+            if !deferred.is_empty() {
+                let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+                defer_auto_replies(&conn, &deferred).map_err(|e| e.to_string())?;
+                write_log(&conn, target.account_id, "reply", "skipped",
+                    format!("メンション {} 件への自動返信を保留しました（{}）。再開後に返信します", deferred.len(), reason),
+                    None, None, LogDetails::default())
+                    .map_err(|e| e.to_string())?;
+            }
+            continue;
+        }
+
+        let deferred = {
+            let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+            load_deferred_mentions(&conn, target.account_id).map_err(|e| e.to_string())?
+        };
+        for (mention_id, tweet) in deferred.into_iter().chain(stored) {
+            if !eligible(&tweet) {
+                continue;
+            }
+            let Some(reply) = rules.select(&tweet) else { continue };
+            let rule_id = reply.index.and_then(|i| rule_ids.get(i).copied());
+
+            let started = std::time::Instant::now();
+            let result = state.api.post_reply_to_twitter(&target.account, &reply.content, &tweet.id).await;
+            let details = LogDetails::mention(&tweet.id, rule_id).with_duration(started.elapsed());
+            record_mention_reply(state, mention_id, target.account_id, &tweet.id, &reply.content, true, &result, details)?;
+
+            match result {
+                Ok(_) => summary.auto_replies += 1,
+                Err(e) => {
+                    summary.errors += 1;
+                    // レート制限時は残りを手動対応に回す
+                    if e.is_rate_limited() {
+                        break;
+                    }
+                }
+            }
+            tokio::time::sleep(REPLY_DELAY).await;
+        }
+    }
+
+    summary.finished_at = Utc::now().to_rfc3339();
+    Ok(summary)
+}
+
+// バックグラウンドのメンション取得ループを起動
+pub fn spawn_mention_poller(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(LOOP_TICK).await;
+
+            let state = app.state::<AppState>();
+            if let Err(e) = run_mention_poll(&state, true).await {
+                eprintln!("Mention poller error: {}", e);
+            }
+        }
+    });
+}
+
+// メンション一覧（新しい順）
+#[tauri::command]
+pub fn get_mentions(
+    account_id: Option<i64>,
+    unread_only: Option<bool>,
+    limit: Option<i64>,
+    state: State<AppState>
+) -> Result<Vec<Mention>, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM mentions m
+         LEFT JOIN bot_accounts ba ON m.account_id = ba.id
+         WHERE (?1 IS NULL OR m.account_id = ?1) AND (?2 = 0 OR m.is_read = 0)
+         ORDER BY LENGTH(m.tweet_id) DESC, m.tweet_id DESC
+         LIMIT ?3",
+        MENTION_COLUMNS
+    )).map_err(|e| e.to_string())?;
+
+    let mentions = stmt.query_map(
+        params![account_id, unread_only.unwrap_or(false), limit.unwrap_or(100)],
+        mention_from_row
+    )
+    .map_err(|e| e.to_string())?
+    .collect::<SqliteResult<Vec<_>>>()
+    .map_err(|e| e.to_string())?;
+
+    Ok(mentions)
+}
+
+// 既読・未読を切り替え（更新件数を返す）
+#[tauri::command]
+pub fn mark_mentions_read(mention_ids: Vec<i64>, is_read: bool, state: State<AppState>) -> Result<usize, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    let now = Utc::now().to_rfc3339();
+
+    let mut updated = 0;
+    for mention_id in mention_ids {
+        updated += conn.execute(
+            "UPDATE mentions SET is_read = ?, updated_at = ? WHERE id = ?",
+            params![is_read, now, mention_id],
+        ).map_err(|e| e.to_string())?;
+    }
+    Ok(updated)
+}
+
+// メンションに手動で返信
+#[tauri::command]
+pub async fn reply_to_mention(mention_id: i64, content: String, state: State<'_, AppState>) -> Result<Mention, String> {
+    if content.trim().is_empty() {
+        return Err("返信内容が空です".to_string());
+    }
+
+    let (mention, account) = {
+        let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+        let mention = get_mention(&conn, mention_id)
+            .map_err(|_| format!("メンション ID {} が存在しません", mention_id))?;
+        let account = crate::get_bot_account(&conn, mention.account_id)
+            .map_err(|e| format!("アカウント取得エラー: {}", e))?;
+        (mention, account)
+    };
+
+    let started = std::time::Instant::now();
+    let result = state.api.post_reply_to_twitter(&account, &content, &mention.tweet_id).await;
+    let details = LogDetails::mention(&mention.tweet_id, None).with_duration(started.elapsed());
+    record_mention_reply(&state, mention.id, mention.account_id, &mention.tweet_id, &content, false, &result, details)?;
+    result?;
+
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    get_mention(&conn, mention_id).map_err(|e| e.to_string())
+}
+
+// 全アカウントのメンション取得設定
+#[tauri::command]
+pub fn get_mention_settings(state: State<AppState>) -> Result<Vec<MentionSettings>, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+
+    let mut stmt = conn.prepare(
+        "SELECT ba.id, ba.account_name, COALESCE(ms.is_enabled, 0), COALESCE(ms.auto_reply_enabled, 0),
+                ms.auto_reply_since, ms.last_polled_at, ms.last_error
+         FROM bot_accounts ba
+         LEFT JOIN mention_settings ms ON ms.account_id = ba.id
+         ORDER BY ba.id"
+    ).map_err(|e| e.to_string())?;
+
+    let settings = stmt.query_map([], |row| {
+        Ok(MentionSettings {
+            account_id: row.get(0)?,
+            account_name: row.get(1)?,
+            is_enabled: row.get(2)?,
+            auto_reply_enabled: row.get(3)?,
+            auto_reply_since: row.get(4)?,
+            last_polled_at: row.get(5)?,
+            last_error: row.get(6)?,
+        })
+    })
+    .map_err(|e| e.to_string())?
+    .collect::<SqliteResult<Vec<_>>>()
+    .map_err(|e| e.to_string())?;
+
+    Ok(settings)
+}
+
+// アカウントのメンション取得・自動返信を切り替え
+// 自動返信を有効にした時点以降のメンションだけを自動返信の対象にする
+#[tauri::command]
+pub fn update_mention_settings(account_id: i64, is_enabled: bool, auto_reply_enabled: bool, state: State<AppState>) -> Result<(), String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    let now = Utc::now().to_rfc3339();
+
+    crate::get_bot_account(&conn, account_id)
+        .map_err(|_| format!("アカウント ID {} が存在しません", account_id))?;
+
+    conn.execute(
+        "INSERT INTO mention_settings (account_id, is_enabled, auto_reply_enabled, auto_reply_since, created_at, updated_at)
+         VALUES (?1, ?2, ?3, CASE WHEN ?3 THEN ?4 END, ?4, ?4)
+         ON CONFLICT(account_id) DO UPDATE SET
+             is_enabled = excluded.is_enabled,
+             auto_reply_enabled = excluded.auto_reply_enabled,
+             auto_reply_since = CASE
+                 WHEN NOT excluded.auto_reply_enabled THEN NULL
+                 WHEN mention_settings.auto_reply_enabled THEN mention_settings.auto_reply_since
+                 ELSE excluded.updated_at
+             END,
+             updated_at = excluded.updated_at",
+        params![account_id, is_enabled, auto_reply_enabled, now],
+    ).map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_mention_poll_interval(state: State<AppState>) -> Result<i64, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    Ok(crate::get_app_setting(&conn, SETTING_INTERVAL_MINUTES)
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_INTERVAL_MINUTES))
+}
+
+#[tauri::command]
+pub fn update_mention_poll_interval(interval_minutes: i64, state: State<AppState>) -> Result<(), String> {
+    if interval_minutes < MIN_INTERVAL_MINUTES {
+        return Err(format!("取得間隔は{}分以上にしてください", MIN_INTERVAL_MINUTES));
+    }
+
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    crate::set_app_setting(&conn, SETTING_INTERVAL_MINUTES, &interval_minutes.to_string())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_mention_reply_rules(account_id: Option<i64>, state: State<AppState>) -> Result<Vec<MentionReplyRule>, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    load_rules(&conn, account_id, false).map_err(|e| e.to_string())
+}
+
+// 自動返信ルールを保存（id を指定した場合は更新）
+#[tauri::command]
+pub fn save_mention_reply_rule(
+    id: Option<i64>,
+    account_id: i64,
+    reply_content: String,
+    keywords: Vec<String>,
+    pattern: Option<String>,
+    is_active: bool,
+    state: State<AppState>
+) -> Result<i64, String> {
+    if reply_content.trim().is_empty() {
+        return Err("返信内容が空です".to_string());
+    }
+    let keywords: Vec<String> = keywords.into_iter()
+        .map(|k| k.trim().to_string())
+        .filter(|k| !k.is_empty())
+        .collect();
+    let pattern = pattern.filter(|p| !p.trim().is_empty());
+
+    let rule = MentionReplyRule {
+        id: id.unwrap_or(0),
+        account_id,
+        reply_content,
+        keywords,
+        pattern,
+        is_active,
+        created_at: String::new(),
+        updated_at: String::new(),
+    };
+    compile_rules(std::slice::from_ref(&rule))?;
+
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    let now = Utc::now().to_rfc3339();
+    let keywords_json = serde_json::to_string(&rule.keywords).map_err(|e| format!("JSON変換エラー: {}", e))?;
+
+    crate::get_bot_account(&conn, account_id)
+        .map_err(|_| format!("アカウント ID {} が存在しません", account_id))?;
+
+    match id {
+        Some(id) => {
+            let updated = conn.execute(
+                "UPDATE mention_reply_rules SET account_id = ?, reply_content = ?, keywords = ?, pattern = ?, is_active = ?, updated_at = ? WHERE id = ?",
+                params![account_id, rule.reply_content, keywords_json, rule.pattern, is_active, now, id],
+            ).map_err(|e| e.to_string())?;
+            if updated == 0 {
+                return Err(format!("自動返信ルール ID {} が存在しません", id));
+            }
+            Ok(id)
+        }
+        None => {
+            conn.execute(
+                "INSERT INTO mention_reply_rules (account_id, reply_content, keywords, pattern, is_active, created_at, updated_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?)",
+                params![account_id, rule.reply_content, keywords_json, rule.pattern, is_active, now, now],
+            ).map_err(|e| e.to_string())?;
+            Ok(conn.last_insert_rowid())
+        }
+    }
+}
+
+#[tauri::command]
+pub fn delete_mention_reply_rule(id: i64, state: State<AppState>) -> Result<(), String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+
+    conn.execute("DELETE FROM mention_reply_rules WHERE id = ?", params![id])
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+// 有効な全アカウントのメンションを今すぐ取得
+#[tauri::command]
+pub async fn poll_mentions_now(state: State<'_, AppState>) -> Result<MentionPollSummary, String> {
+    run_mention_poll(&state, false).await
+}
@@ -0,0 +1,507 @@
+// テスト用の Twitter API v2 モックサーバー
+//
+// ツイートの投稿・削除・指標取得、タイムライン、メンション、users/me・ユーザー検索を
+// メモリ上のデータで再現する。認証は OAuth ヘッダーの oauth_token で利用者を判別するだけで署名は検証しない。
+// すべてのレスポンスに x-rate-limit-* ヘッダーを付け、残数が0のエンドポイントは 429 を返す。
+// fail_next で任意のエラーレスポンスを1回だけ返すこともできる。
+
+use axum::body::Body;
+use axum::extract::State;
+use axum::http::{HeaderMap, Method, StatusCode, Uri};
+use axum::response::Response;
+use axum::Router;
+use chrono::Utc;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::twitter_api::ApiClient;
+
+const DEFAULT_RATE_LIMIT: u32 = 900;
+const RATE_LIMIT_WINDOW_SECONDS: i64 = 15 * 60;
+
+#[derive(Debug, Clone)]
+pub(crate) struct MockUser {
+    pub id: String,
+    pub username: String,
+    pub name: String,
+    pub access_token: String,
+    pub metrics: Value,   // public_metrics
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct MockTweet {
+    pub id: String,
+    pub author_id: String,
+    pub text: String,
+    pub created_at: String,
+    pub in_reply_to_tweet_id: Option<String>,
+    pub referenced_tweets: Vec<(String, String)>,   // (type, id)
+    pub via_api: bool,                              // API から投稿されたツイート
+    pub deleted: bool,
+    pub metrics: Value,                             // public_metrics
+}
+
+impl MockTweet {
+    fn to_json(&self) -> Value {
+        let mut tweet = json!({
+            "id": self.id,
+            "text": self.text,
+            "author_id": self.author_id,
+            "created_at": self.created_at,
+            "conversation_id": self.id,
+        });
+        if !self.metrics.is_null() {
+            tweet["public_metrics"] = self.metrics.clone();
+        }
+        if !self.referenced_tweets.is_empty() {
+            tweet["referenced_tweets"] = self.referenced_tweets.iter()
+                .map(|(kind, id)| json!({ "type": kind, "id": id }))
+                .collect();
+        }
+        tweet
+    }
+}
+
+struct RateLimit {
+    limit: u32,
+    remaining: u32,
+    reset: i64,
+}
+
+struct InjectedFailure {
+    method: Method,
+    path_prefix: String,
+    status: StatusCode,
+    body: Value,
+}
+
+#[derive(Default)]
+struct MockState {
+    users: Vec<MockUser>,
+    tweets: Vec<MockTweet>,
+    next_tweet_id: u64,
+    rate_limits: HashMap<String, RateLimit>,
+    failures: Vec<InjectedFailure>,
+    requests: Vec<(Method, String)>,
+}
+
+type SharedState = Arc<Mutex<MockState>>;
+
+// 起動中のモックサーバー（drop するとサーバーも停止する）
+pub(crate) struct MockTwitter {
+    pub base_url: String,
+    state: SharedState,
+    server: tokio::task::JoinHandle<()>,
+}
+
+impl Drop for MockTwitter {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+impl MockTwitter {
+    // 空いているポートでサーバーを起動
+    pub async fn start() -> Self {
+        let state: SharedState = Arc::new(Mutex::new(MockState {
+            next_tweet_id: 1_800_000_000_000_000_000,
+            ..Default::default()
+        }));
+
+        let app = Router::new().fallback(handle).with_state(state.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.expect("bind mock server");
+        let addr = listener.local_addr().expect("mock server address");
+        let server = tokio::spawn(async move {
+            axum::serve(listener, app).await.expect("mock server");
+        });
+
+        MockTwitter {
+            base_url: format!("http://{}", addr),
+            state,
+            server,
+        }
+    }
+
+    // モックサーバーに接続する API クライアント
+    pub fn client(&self) -> ApiClient {
+        ApiClient::new(self.base_url.clone(), reqwest::Client::new())
+    }
+
+    // ユーザーを登録（access_token で認証中のユーザーを判別する）
+    pub fn add_user(&self, username: &str, access_token: &str) -> String {
+        let mut state = self.state.lock().unwrap();
+        let id = (1000 + state.users.len()).to_string();
+        state.users.push(MockUser {
+            id: id.clone(),
+            username: username.to_string(),
+            name: username.to_string(),
+            access_token: access_token.to_string(),
+            metrics: Value::Null,
+        });
+        id
+    }
+
+    // ユーザーのツイートを追加（現在時刻で投稿されたものとする）
+    pub fn add_tweet(&self, author_id: &str, text: &str) -> String {
+        self.add_tweet_with_references(author_id, text, &[])
+    }
+
+    pub fn add_tweet_with_references(&self, author_id: &str, text: &str, referenced: &[(&str, &str)]) -> String {
+        let mut state = self.state.lock().unwrap();
+        let id = new_tweet(&mut state, author_id, text, None, false).id.clone();
+        if let Some(tweet) = state.tweets.last_mut() {
+            tweet.referenced_tweets = referenced.iter()
+                .map(|(kind, id)| (kind.to_string(), id.to_string()))
+                .collect();
+        }
+        id
+    }
+
+    // API から投稿されたツイート（投稿順）
+    pub fn posted_tweets(&self) -> Vec<MockTweet> {
+        let state = self.state.lock().unwrap();
+        state.tweets.iter().filter(|t| t.via_api).cloned().collect()
+    }
+
+    pub fn is_deleted(&self, id: &str) -> bool {
+        let state = self.state.lock().unwrap();
+        state.tweets.iter().any(|t| t.id == id && t.deleted)
+    }
+
+    // ツイートの public_metrics を設定
+    pub fn set_metrics(&self, id: &str, likes: i64, retweets: i64, replies: i64, impressions: i64) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(tweet) = state.tweets.iter_mut().find(|t| t.id == id) {
+            tweet.metrics = json!({
+                "like_count": likes,
+                "retweet_count": retweets,
+                "reply_count": replies,
+                "quote_count": 0,
+                "bookmark_count": 0,
+                "impression_count": impressions,
+            });
+        }
+    }
+
+    // ユーザーの public_metrics を設定（user.fields=public_metrics の指定時に返す）
+    pub fn set_user_metrics(&self, user_id: &str, followers: i64, following: i64, tweets: i64, listed: i64) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(user) = state.users.iter_mut().find(|u| u.id == user_id) {
+            user.metrics = json!({
+                "followers_count": followers,
+                "following_count": following,
+                "tweet_count": tweets,
+                "listed_count": listed,
+            });
+        }
+    }
+
+    // エンドポイント（例: "POST /2/tweets", "GET /2/users/:id/tweets"）のレート制限残数を設定
+    pub fn set_rate_limit_remaining(&self, endpoint: &str, remaining: u32) {
+        let mut state = self.state.lock().unwrap();
+        let limit = rate_limit_entry(&mut state, endpoint);
+        limit.remaining = remaining;
+    }
+
+    // 次に一致したリクエストに1回だけエラーを返す
+    pub fn fail_next(&self, method: Method, path_prefix: &str, status: StatusCode, detail: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.failures.push(InjectedFailure {
+            method,
+            path_prefix: path_prefix.to_string(),
+            status,
+            body: error_body(status, detail),
+        });
+    }
+
+    // 受け付けたリクエスト数（メソッドとパスの前方一致）
+    pub fn request_count(&self, method: Method, path_prefix: &str) -> usize {
+        let state = self.state.lock().unwrap();
+        state.requests.iter()
+            .filter(|(m, path)| *m == method && path.starts_with(path_prefix))
+            .count()
+    }
+}
+
+fn new_tweet<'a>(state: &'a mut MockState, author_id: &str, text: &str, in_reply_to: Option<String>, via_api: bool) -> &'a MockTweet {
+    state.next_tweet_id += 1;
+    let referenced_tweets = in_reply_to.iter()
+        .map(|id| ("replied_to".to_string(), id.clone()))
+        .collect();
+    state.tweets.push(MockTweet {
+        id: state.next_tweet_id.to_string(),
+        author_id: author_id.to_string(),
+        text: text.to_string(),
+        created_at: Utc::now().to_rfc3339(),
+        in_reply_to_tweet_id: in_reply_to,
+        referenced_tweets,
+        via_api,
+        deleted: false,
+        metrics: Value::Null,
+    });
+    state.tweets.last().unwrap()
+}
+
+fn rate_limit_entry<'a>(state: &'a mut MockState, endpoint: &str) -> &'a mut RateLimit {
+    state.rate_limits.entry(endpoint.to_string()).or_insert_with(|| RateLimit {
+        limit: DEFAULT_RATE_LIMIT,
+        remaining: DEFAULT_RATE_LIMIT,
+        reset: Utc::now().timestamp() + RATE_LIMIT_WINDOW_SECONDS,
+    })
+}
+
+fn error_body(status: StatusCode, detail: &str) -> Value {
+    json!({
+        "title": status.canonical_reason().unwrap_or("Error"),
+        "detail": detail,
+        "status": status.as_u16(),
+    })
+}
+
+// パスをレート制限の単位（ID部分を :id に置き換えたもの）に変換
+fn endpoint_key(method: &Method, segments: &[&str]) -> String {
+    let path = match segments {
+        ["2", "tweets", _] => "/2/tweets/:id".to_string(),
+        ["2", "users", "by", "username", _] => "/2/users/by/username/:username".to_string(),
+        ["2", "users", "me"] => "/2/users/me".to_string(),
+        ["2", "users", _, rest @ ..] => format!("/2/users/:id/{}", rest.join("/")),
+        _ => format!("/{}", segments.join("/")),
+    };
+    format!("{} {}", method, path.trim_end_matches('/'))
+}
+
+// Authorization ヘッダーの oauth_token を取り出す
+fn oauth_token(headers: &HeaderMap) -> Option<String> {
+    let header = headers.get("authorization")?.to_str().ok()?;
+    header.strip_prefix("OAuth ")?
+        .split(", ")
+        .find_map(|param| {
+            let value = param.strip_prefix("oauth_token=")?;
+            Some(value.trim_matches('"').to_string())
+        })
+}
+
+fn query_params(uri: &Uri) -> HashMap<String, String> {
+    uri.query()
+        .map(|query| query.split('&')
+            .filter_map(|pair| {
+                let (key, value) = pair.split_once('=')?;
+                Some((key.to_string(), value.replace("%2C", ",")))
+            })
+            .collect())
+        .unwrap_or_default()
+}
+
+async fn handle(State(state): State<SharedState>, method: Method, uri: Uri, headers: HeaderMap, body: String) -> Response {
+    let mut state = state.lock().unwrap();
+    let path = uri.path().to_string();
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let endpoint = endpoint_key(&method, &segments);
+    state.requests.push((method.clone(), path.clone()));
+
+    // レート制限
+    let limit = rate_limit_entry(&mut state, &endpoint);
+    let rate_headers = [
+        ("x-rate-limit-limit", limit.limit.to_string()),
+        ("x-rate-limit-remaining", limit.remaining.saturating_sub(1).to_string()),
+        ("x-rate-limit-reset", limit.reset.to_string()),
+    ];
+    if limit.remaining == 0 {
+        return respond(StatusCode::TOO_MANY_REQUESTS, &rate_headers, error_body(StatusCode::TOO_MANY_REQUESTS, "Too Many Requests"));
+    }
+    limit.remaining -= 1;
+
+    // 注入されたエラー
+    if let Some(index) = state.failures.iter().position(|f| f.method == method && path.starts_with(&f.path_prefix)) {
+        let failure = state.failures.remove(index);
+        return respond(failure.status, &rate_headers, failure.body);
+    }
+
+    let me = match oauth_token(&headers).and_then(|token| state.users.iter().find(|u| u.access_token == token).cloned()) {
+        Some(user) => user,
+        None => return respond(StatusCode::UNAUTHORIZED, &rate_headers, error_body(StatusCode::UNAUTHORIZED, "Unauthorized")),
+    };
+
+    let (status, body) = route(&mut state, &method, &segments, &query_params(&uri), &body, &me);
+    respond(status, &rate_headers, body)
+}
+
+fn route(
+    state: &mut MockState,
+    method: &Method,
+    segments: &[&str],
+    query: &HashMap<String, String>,
+    body: &str,
+    me: &MockUser,
+) -> (StatusCode, Value) {
+    match (method.as_str(), segments) {
+        ("POST", ["2", "tweets"]) => create_tweet(state, body, me),
+        ("GET", ["2", "tweets"]) => lookup_tweets(state, query),
+        ("DELETE", ["2", "tweets", id]) => delete_tweet(state, id, me),
+        ("GET", ["2", "users", "me"]) => {
+            let mut user = user_json(me);
+            if query.get("user.fields").is_some_and(|f| f.contains("public_metrics")) && !me.metrics.is_null() {
+                user["public_metrics"] = me.metrics.clone();
+            }
+            (StatusCode::OK, json!({ "data": user }))
+        }
+        ("GET", ["2", "users", "by", "username", username]) => {
+            match state.users.iter().find(|u| u.username.eq_ignore_ascii_case(username)) {
+                Some(user) => (StatusCode::OK, json!({ "data": user_json(user) })),
+                // 実APIと同じく存在しないユーザーは 200 で errors のみを返す
+                None => (StatusCode::OK, json!({
+                    "errors": [{ "title": "Not Found Error", "detail": format!("Could not find user with username: [{}].", username) }]
+                })),
+            }
+        }
+        ("GET", ["2", "users", user_id, "tweets"]) => {
+            let exclude = query.get("exclude").cloned().unwrap_or_default();
+            let tweets = select_tweets(state, query, |t| {
+                t.author_id == *user_id
+                    && !(exclude.contains("retweets") && t.referenced_tweets.iter().any(|(kind, _)| kind == "retweeted"))
+                    && !(exclude.contains("replies") && t.in_reply_to_tweet_id.is_some())
+            });
+            (StatusCode::OK, page_json(tweets, None))
+        }
+        ("GET", ["2", "users", user_id, "mentions"]) => {
+            let Some(user) = state.users.iter().find(|u| u.id == *user_id).cloned() else {
+                return (StatusCode::NOT_FOUND, error_body(StatusCode::NOT_FOUND, "User not found"));
+            };
+            let mention = format!("@{}", user.username.to_lowercase());
+            let tweets = select_tweets(state, query, |t| {
+                t.author_id != user.id && t.text.to_lowercase().contains(&mention)
+            });
+            let authors: Vec<Value> = state.users.iter()
+                .filter(|u| tweets.tweets.iter().any(|t| t["author_id"] == u.id.as_str()))
+                .map(user_json)
+                .collect();
+            (StatusCode::OK, page_json(tweets, Some(authors)))
+        }
+        _ => (StatusCode::NOT_FOUND, error_body(StatusCode::NOT_FOUND, "Not Found")),
+    }
+}
+
+fn create_tweet(state: &mut MockState, body: &str, me: &MockUser) -> (StatusCode, Value) {
+    let payload: Value = match serde_json::from_str(body) {
+        Ok(payload) => payload,
+        Err(_) => return (StatusCode::BAD_REQUEST, error_body(StatusCode::BAD_REQUEST, "Invalid JSON")),
+    };
+    let Some(text) = payload["text"].as_str() else {
+        return (StatusCode::BAD_REQUEST, error_body(StatusCode::BAD_REQUEST, "text is required"));
+    };
+
+    // 実APIと同じく同じ内容の連続投稿は 403
+    let duplicate = state.tweets.iter().any(|t| t.author_id == me.id && !t.deleted && t.text == text);
+    if duplicate {
+        return (StatusCode::FORBIDDEN, error_body(StatusCode::FORBIDDEN, "You are not allowed to create a Tweet with duplicate content."));
+    }
+
+    let in_reply_to = payload["reply"]["in_reply_to_tweet_id"].as_str().map(str::to_string);
+    if let Some(id) = &in_reply_to {
+        if !state.tweets.iter().any(|t| &t.id == id && !t.deleted) {
+            return (StatusCode::BAD_REQUEST, error_body(StatusCode::BAD_REQUEST, "The Tweet you are replying to has been deleted or is not visible."));
+        }
+    }
+
+    let tweet = new_tweet(state, &me.id, text, in_reply_to, true);
+    (StatusCode::CREATED, json!({ "data": { "id": tweet.id, "text": tweet.text } }))
+}
+
+// ids で指定したツイートを返す（存在しない・削除済みのものは実APIと同じく errors に入る）
+fn lookup_tweets(state: &MockState, query: &HashMap<String, String>) -> (StatusCode, Value) {
+    let ids = query.get("ids").cloned().unwrap_or_default();
+    let mut data = Vec::new();
+    let mut errors = Vec::new();
+    for id in ids.split(',').filter(|id| !id.is_empty()) {
+        match state.tweets.iter().find(|t| t.id == id && !t.deleted) {
+            Some(tweet) => data.push(tweet.to_json()),
+            None => errors.push(json!({ "resource_id": id, "title": "Not Found Error", "detail": format!("Could not find tweet with ids: [{}].", id) })),
+        }
+    }
+
+    let mut body = json!({});
+    if !data.is_empty() {
+        body["data"] = Value::Array(data);
+    }
+    if !errors.is_empty() {
+        body["errors"] = Value::Array(errors);
+    }
+    (StatusCode::OK, body)
+}
+
+fn delete_tweet(state: &mut MockState, id: &str, me: &MockUser) -> (StatusCode, Value) {
+    match state.tweets.iter_mut().find(|t| t.id == id && !t.deleted) {
+        Some(tweet) if tweet.author_id != me.id => {
+            (StatusCode::FORBIDDEN, error_body(StatusCode::FORBIDDEN, "You are not authorized to delete this Tweet."))
+        }
+        Some(tweet) => {
+            tweet.deleted = true;
+            (StatusCode::OK, json!({ "data": { "deleted": true } }))
+        }
+        None => (StatusCode::NOT_FOUND, error_body(StatusCode::NOT_FOUND, "Tweet not found")),
+    }
+}
+
+// 条件に合うツイートを新しい順に1ページ分（since_id より新しく until_id より古いもののみ）
+// 続きがある場合は最後に返したツイートのIDを next_token とし、pagination_token で続きを返す
+fn select_tweets(state: &MockState, query: &HashMap<String, String>, filter: impl Fn(&MockTweet) -> bool) -> TweetPage {
+    let since_id: u64 = query.get("since_id").and_then(|v| v.parse().ok()).unwrap_or(0);
+    let until_id: u64 = ["until_id", "pagination_token"].iter()
+        .filter_map(|key| query.get(*key)?.parse().ok())
+        .min()
+        .unwrap_or(u64::MAX);
+    let max_results: usize = query.get("max_results").and_then(|v| v.parse().ok()).unwrap_or(10);
+
+    let mut tweets: Vec<&MockTweet> = state.tweets.iter()
+        .filter(|t| {
+            let id = t.id.parse::<u64>().unwrap_or(0);
+            !t.deleted && id > since_id && id < until_id && filter(t)
+        })
+        .collect();
+    tweets.sort_by_key(|t| std::cmp::Reverse(t.id.parse::<u64>().unwrap_or(0)));
+    let next_token = (tweets.len() > max_results).then(|| tweets[max_results - 1].id.clone());
+    TweetPage {
+        tweets: tweets.into_iter().take(max_results).map(MockTweet::to_json).collect(),
+        next_token,
+    }
+}
+
+struct TweetPage {
+    tweets: Vec<Value>,
+    next_token: Option<String>,
+}
+
+// 結果が0件の場合は実APIと同じく data を含めない
+fn page_json(page: TweetPage, users: Option<Vec<Value>>) -> Value {
+    let TweetPage { tweets, next_token } = page;
+    let mut json = json!({ "meta": { "result_count": tweets.len() } });
+    if let (Some(newest), Some(oldest)) = (tweets.first(), tweets.last()) {
+        json["meta"]["newest_id"] = newest["id"].clone();
+        json["meta"]["oldest_id"] = oldest["id"].clone();
+    }
+    if let Some(next_token) = next_token {
+        json["meta"]["next_token"] = Value::String(next_token);
+    }
+    if !tweets.is_empty() {
+        json["data"] = Value::Array(tweets);
+        if let Some(users) = users {
+            json["includes"] = json!({ "users": users });
+        }
+    }
+    json
+}
+
+fn user_json(user: &MockUser) -> Value {
+    json!({ "id": user.id, "username": user.username, "name": user.name })
+}
+
+fn respond(status: StatusCode, headers: &[(&str, String)], body: Value) -> Response {
+    let mut builder = Response::builder()
+        .status(status)
+        .header("content-type", "application/json");
+    for (name, value) in headers {
+        builder = builder.header(*name, value);
+    }
+    builder.body(Body::from(body.to_string())).unwrap()
+}
@@ -0,0 +1,409 @@
+// デスクトップ通知と通知履歴
+//
+// 投稿の失敗・アカウントの自動停止・月間投稿数の上限接近・返信監視の停滞を通知ルールごとに判定し、
+// notifications テーブルに履歴を残してから通知バス（tokio の broadcast）に流す。
+// 呼び出し元のトランザクションの中で判定した通知は、コミットされてからバスに流す（after_commit）。
+// デスクトップ通知（Tauri の notification プラグイン）はバスの購読側で表示するため、
+// 判定と履歴はウィンドウが無くても（テストでも）動く。
+// 同じ通知が続かないよう重複キーで抑止する（投稿の失敗はアカウントごとに一定時間、上限接近は月に1回）。
+
+use rusqlite::{Connection, params, OptionalExtension, Result as SqliteResult};
+use serde::{Serialize, Deserialize};
+use chrono::{DateTime, Datelike, Duration, TimeZone, Utc};
+use std::sync::OnceLock;
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_notification::NotificationExt;
+use tokio::sync::broadcast;
+
+use crate::{AppState, ExecutionLog};
+use crate::after_commit;
+use crate::reply_engine;
+use crate::reply_filters::to_jst;
+use crate::webhooks::{self, WebhookEvent};
+
+const SETTING_RULES: &str = "notification_rules";
+
+// 同じアカウントの投稿失敗を再通知するまでの時間
+const POST_FAILED_COOLDOWN_MINUTES: i64 = 30;
+// 返信監視の間隔の何倍実行されなければ停滞とみなすか
+const REPLY_STALL_FACTOR: i64 = 3;
+
+const BUS_CAPACITY: usize = 64;
+const LOOP_TICK: std::time::Duration = std::time::Duration::from_secs(5 * 60);
+
+// 通知の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    PostFailed,
+    AccountDisabled,
+    QuotaWarning,
+    ReplyEngineStalled,
+    Test,
+}
+
+impl NotificationKind {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            NotificationKind::PostFailed => "post_failed",
+            NotificationKind::AccountDisabled => "account_disabled",
+            NotificationKind::QuotaWarning => "quota_warning",
+            NotificationKind::ReplyEngineStalled => "reply_engine_stalled",
+            NotificationKind::Test => "test",
+        }
+    }
+}
+
+// 通知ルール（種類ごとの有効・無効）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationRules {
+    pub desktop_enabled: bool,        // 無効でも履歴には残す
+    pub post_failed: bool,
+    pub account_disabled: bool,
+    pub quota_warning: bool,
+    pub quota_warning_percent: i64,   // 月間投稿数の上限に対する割合
+    pub reply_engine_stalled: bool,
+}
+
+impl Default for NotificationRules {
+    fn default() -> Self {
+        NotificationRules {
+            desktop_enabled: true,
+            post_failed: true,
+            account_disabled: true,
+            quota_warning: true,
+            quota_warning_percent: 80,
+            reply_engine_stalled: true,
+        }
+    }
+}
+
+impl NotificationRules {
+    fn allows(&self, kind: NotificationKind) -> bool {
+        match kind {
+            NotificationKind::PostFailed => self.post_failed,
+            NotificationKind::AccountDisabled => self.account_disabled,
+            NotificationKind::QuotaWarning => self.quota_warning,
+            NotificationKind::ReplyEngineStalled => self.reply_engine_stalled,
+            NotificationKind::Test => true,
+        }
+    }
+}
+
+// 通知履歴の1件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Notification {
+    pub id: i64,
+    pub kind: String,
+    pub account_id: Option<i64>,
+    pub account_name: Option<String>,
+    pub title: String,
+    pub body: String,
+    pub is_read: bool,
+    pub created_at: String,
+}
+
+// 通知のテーブル（マイグレーションから呼ばれる）
+pub fn create_notification_table(conn: &Connection) -> SqliteResult<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS notifications (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            kind TEXT NOT NULL,
+            account_id INTEGER,
+            title TEXT NOT NULL,
+            body TEXT NOT NULL,
+            dedupe_key TEXT,
+            is_read BOOLEAN NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (account_id) REFERENCES bot_accounts(id) ON DELETE CASCADE
+        );
+        CREATE INDEX IF NOT EXISTS idx_notifications_dedupe ON notifications (dedupe_key, created_at);"
+    )
+}
+
+fn bus() -> &'static broadcast::Sender<Notification> {
+    static BUS: OnceLock<broadcast::Sender<Notification>> = OnceLock::new();
+    BUS.get_or_init(|| broadcast::channel(BUS_CAPACITY).0)
+}
+
+// 通知バスを購読（デスクトップ通知などの送信先）
+pub(crate) fn subscribe() -> broadcast::Receiver<Notification> {
+    bus().subscribe()
+}
+
+pub(crate) fn load_rules(conn: &Connection) -> NotificationRules {
+    crate::get_app_setting(conn, SETTING_RULES)
+        .and_then(|v| serde_json::from_str(&v).ok())
+        .unwrap_or_default()
+}
+
+fn account_name(conn: &Connection, account_id: i64) -> String {
+    conn.query_row("SELECT account_name FROM bot_accounts WHERE id = ?", params![account_id], |row| row.get(0))
+        .unwrap_or_else(|_| format!("アカウント {}", account_id))
+}
+
+// ルールで有効なら通知を記録してバスに流す
+// dedupe_key が同じ通知が cooldown 以内（None なら期間に関係なく）にあれば送らない
+pub(crate) fn notify(
+    conn: &Connection,
+    kind: NotificationKind,
+    account_id: Option<i64>,
+    title: String,
+    body: String,
+    dedupe: Option<(String, Option<Duration>)>,
+) -> SqliteResult<Option<i64>> {
+    if !load_rules(conn).allows(kind) {
+        return Ok(None);
+    }
+
+    let now = Utc::now();
+    if let Some((key, cooldown)) = &dedupe {
+        let since = cooldown.map(|c| (now - c).to_rfc3339()).unwrap_or_default();
+        let duplicate: Option<i64> = conn.query_row(
+            "SELECT id FROM notifications WHERE dedupe_key = ? AND created_at >= ? LIMIT 1",
+            params![key, since],
+            |row| row.get(0)
+        ).optional()?;
+        if duplicate.is_some() {
+            return Ok(None);
+        }
+    }
+
+    conn.execute(
+        "INSERT INTO notifications (kind, account_id, title, body, dedupe_key, is_read, created_at) VALUES (?, ?, ?, ?, ?, 0, ?)",
+        params![kind.as_str(), account_id, title, body, dedupe.map(|(key, _)| key), now.to_rfc3339()],
+    )?;
+    let id = conn.last_insert_rowid();
+
+    // 購読者がいなければ送信は失敗するが、履歴には残っている
+    let notification = Notification {
+        id,
+        kind: kind.as_str().to_string(),
+        account_id,
+        account_name: account_id.map(|id| account_name(conn, id)),
+        title,
+        body,
+        is_read: false,
+        created_at: now.to_rfc3339(),
+    };
+    after_commit::defer(conn, move || {
+        let _ = bus().send(notification);
+    });
+    Ok(Some(id))
+}
+
+// API プランごとの月間投稿数（投稿と返信の合計）の上限
+fn monthly_post_limit(api_type: &str) -> Option<i64> {
+    match api_type {
+        "Free" => Some(500),
+        "Basic" => Some(3_000),
+        "Pro" => Some(300_000),
+        _ => None,
+    }
+}
+
+// 今月（JST）の投稿・返信の成功数
+pub(crate) fn monthly_post_count(conn: &Connection, account_id: i64, now: DateTime<Utc>) -> SqliteResult<i64> {
+    let jst = to_jst(now);
+    let month_start = jst.timezone()
+        .with_ymd_and_hms(jst.year(), jst.month(), 1, 0, 0, 0)
+        .single()
+        .map(|t| t.with_timezone(&Utc))
+        .unwrap_or(now);
+
+    conn.query_row(
+        "SELECT COUNT(*) FROM execution_logs
+         WHERE account_id = ? AND log_type IN ('tweet', 'reply') AND status = 'success' AND created_at >= ?",
+        params![account_id, month_start.to_rfc3339()],
+        |row| row.get(0)
+    )
+}
+
+fn check_quota(conn: &Connection, account_id: i64) -> SqliteResult<()> {
+    let rules = load_rules(conn);
+    let api_type: Option<String> = conn.query_row(
+        "SELECT api_type FROM bot_accounts WHERE id = ?",
+        params![account_id],
+        |row| row.get(0)
+    ).optional()?;
+    let Some(limit) = api_type.as_deref().and_then(monthly_post_limit) else { return Ok(()) };
+
+    let now = Utc::now();
+    let count = monthly_post_count(conn, account_id, now)?;
+    if count * 100 < limit * rules.quota_warning_percent {
+        return Ok(());
+    }
+
+    let name = account_name(conn, account_id);
+    let message = format!("今月の投稿・返信は {} / {} 件（{}%）です", count, limit, count * 100 / limit);
+    let dedupe_key = format!("quota_warning:{}:{}", account_id, to_jst(now).format("%Y-%m"));
+    webhooks::enqueue(conn, webhooks::payload(conn, WebhookEvent::QuotaWarning, Some(account_id), &message), Some(&dedupe_key))?;
+    notify(conn, NotificationKind::QuotaWarning, Some(account_id),
+        format!("{} の月間投稿数が上限に近づいています", name),
+        message,
+        Some((dedupe_key, None)))?;
+    Ok(())
+}
+
+// 実行ログを書き込んだ後に呼ばれ、投稿の失敗と月間投稿数を確認する
+pub(crate) fn observe_log(conn: &Connection, log: &ExecutionLog) -> SqliteResult<()> {
+    if log.status == "error" && log.details.is_automated_post() {
+        let name = account_name(conn, log.account_id);
+        notify(conn, NotificationKind::PostFailed, Some(log.account_id),
+            format!("{} の投稿に失敗しました", name),
+            log.message.clone(),
+            Some((format!("post_failed:{}", log.account_id), Some(Duration::minutes(POST_FAILED_COOLDOWN_MINUTES)))))?;
+    } else if log.status == "success" && (log.log_type == "tweet" || log.log_type == "reply") {
+        check_quota(conn, log.account_id)?;
+    }
+    Ok(())
+}
+
+// アカウントが自動停止されたことを通知
+pub(crate) fn notify_account_disabled(conn: &Connection, account_id: i64, reason: &str) -> SqliteResult<()> {
+    let name = account_name(conn, account_id);
+    notify(conn, NotificationKind::AccountDisabled, Some(account_id),
+        format!("{} を停止しました", name),
+        reason.to_string(),
+        None)?;
+    Ok(())
+}
+
+// 返信監視の停滞を確認（停滞1回につき1度だけ通知）
+pub(crate) fn check_reply_engine(conn: &Connection, now: DateTime<Utc>) -> SqliteResult<()> {
+    let Some(last_run_at) = reply_engine::stalled_since(conn, now, REPLY_STALL_FACTOR) else { return Ok(()) };
+
+    let last_run = DateTime::parse_from_rfc3339(&last_run_at)
+        .map(|t| to_jst(t.with_timezone(&Utc)).format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|_| last_run_at.clone());
+    notify(conn, NotificationKind::ReplyEngineStalled, None,
+        "返信監視が止まっています".to_string(),
+        format!("最後に実行されたのは {} です", last_run),
+        Some((format!("reply_engine_stalled:{}", last_run_at), None)))?;
+    Ok(())
+}
+
+// デスクトップ通知の表示と、返信監視の停滞確認を起動
+pub fn spawn_notifications(app: AppHandle) {
+    let mut receiver = subscribe();
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let notification = match receiver.recv().await {
+                Ok(notification) => notification,
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+
+            let desktop_enabled = match handle.state::<AppState>().db.lock() {
+                Ok(conn) => load_rules(&conn).desktop_enabled,
+                Err(_) => true,
+            };
+            if !desktop_enabled {
+                continue;
+            }
+            if let Err(e) = handle.notification().builder()
+                .title(&notification.title)
+                .body(&notification.body)
+                .show()
+            {
+                eprintln!("Desktop notification error: {}", e);
+            }
+        }
+    });
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(LOOP_TICK).await;
+
+            let state = app.state::<AppState>();
+            let Ok(conn) = state.db.lock() else { continue };
+            if let Err(e) = check_reply_engine(&conn, Utc::now()) {
+                eprintln!("Notification check error: {}", e);
+            }
+        }
+    });
+}
+
+pub(crate) fn load_notifications(conn: &Connection, unread_only: bool, limit: i64) -> Result<Vec<Notification>, String> {
+    let mut stmt = conn.prepare(
+        "SELECT n.id, n.kind, n.account_id, ba.account_name, n.title, n.body, n.is_read, n.created_at
+         FROM notifications n
+         LEFT JOIN bot_accounts ba ON n.account_id = ba.id
+         WHERE (?1 = 0 OR n.is_read = 0)
+         ORDER BY n.created_at DESC, n.id DESC
+         LIMIT ?2"
+    ).map_err(|e| e.to_string())?;
+
+    let notifications = stmt.query_map(params![unread_only, limit], |row| {
+        Ok(Notification {
+            id: row.get(0)?,
+            kind: row.get(1)?,
+            account_id: row.get(2)?,
+            account_name: row.get(3)?,
+            title: row.get(4)?,
+            body: row.get(5)?,
+            is_read: row.get(6)?,
+            created_at: row.get(7)?,
+        })
+    })
+    .map_err(|e| e.to_string())?
+    .collect::<SqliteResult<Vec<_>>>()
+    .map_err(|e| e.to_string())?;
+
+    Ok(notifications)
+}
+
+#[tauri::command]
+pub fn get_notification_rules(state: State<AppState>) -> Result<NotificationRules, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    Ok(load_rules(&conn))
+}
+
+#[tauri::command]
+pub fn update_notification_rules(rules: NotificationRules, state: State<AppState>) -> Result<(), String> {
+    if !(1..=100).contains(&rules.quota_warning_percent) {
+        return Err("上限接近の通知は1〜100%で指定してください".to_string());
+    }
+
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    let rules_json = serde_json::to_string(&rules).map_err(|e| e.to_string())?;
+    crate::set_app_setting(&conn, SETTING_RULES, &rules_json).map_err(|e| e.to_string())
+}
+
+// 通知履歴（新しい順）
+#[tauri::command]
+pub fn get_notifications(unread_only: Option<bool>, limit: Option<i64>, state: State<AppState>) -> Result<Vec<Notification>, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    load_notifications(&conn, unread_only.unwrap_or(false), limit.unwrap_or(100))
+}
+
+// 通知を既読にする（ids を省略するとすべて）
+#[tauri::command]
+pub fn mark_notifications_read(ids: Option<Vec<i64>>, state: State<AppState>) -> Result<usize, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    match ids {
+        Some(ids) => {
+            let mut updated = 0;
+            for id in ids {
+                updated += conn.execute("UPDATE notifications SET is_read = 1 WHERE id = ?", params![id])
+                    .map_err(|e| e.to_string())?;
+            }
+            Ok(updated)
+        }
+        None => conn.execute("UPDATE notifications SET is_read = 1 WHERE is_read = 0", [])
+            .map_err(|e| e.to_string()),
+    }
+}
+
+// 通知の表示を確認するためのテスト通知
+#[tauri::command]
+pub fn send_test_notification(state: State<AppState>) -> Result<(), String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    notify(&conn, NotificationKind::Test, None,
+        "テスト通知".to_string(),
+        "通知は正しく表示されています".to_string(),
+        None)
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
@@ -0,0 +1,326 @@
+// 投稿の一時停止と停止期間
+//
+// 全体の一時停止（app_settings）・アカウントごとの一時停止（account_pauses）・停止期間（blackout_windows）を管理する。
+// スケジュール投稿・返信監視・メンション自動返信は投稿の直前に pause_reason を確認し、
+// 停止中ならスキップとして実行ログに残す。スケジュール投稿は停止が明けてもさかのぼって投稿しないが、
+// 返信監視・メンション自動返信は対象を保留し、再開後に返信する。
+// 再開日時を指定した一時停止は、その日時を過ぎると確認時に自動で解除する。
+// 画面からの手動投稿・手動返信は止めない。
+// 日時は RFC3339 で受け取り、比較できるよう UTC に揃えて保存する。
+
+use rusqlite::{Connection, params, OptionalExtension, Result as SqliteResult};
+use serde::{Serialize, Deserialize};
+use chrono::{DateTime, Utc};
+use tauri::State;
+
+use crate::AppState;
+
+const SETTING_GLOBAL_PAUSE: &str = "global_pause";
+
+// 全体の一時停止
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GlobalPause {
+    pub reason: Option<String>,
+    pub paused_at: String,
+    pub resume_at: Option<String>,   // 省略時は手動で再開するまで
+}
+
+// アカウントの一時停止
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AccountPause {
+    pub account_id: i64,
+    pub account_name: String,
+    pub reason: Option<String>,
+    pub paused_at: String,
+    pub resume_at: Option<String>,
+}
+
+// 停止期間（account_id が無ければ全アカウント）
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BlackoutWindow {
+    pub id: i64,
+    pub name: String,
+    pub account_id: Option<i64>,
+    pub starts_at: String,
+    pub ends_at: String,
+    pub is_active: bool,   // 現在が期間内か
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PauseStatus {
+    pub global: Option<GlobalPause>,
+    pub accounts: Vec<AccountPause>,
+    pub blackout_windows: Vec<BlackoutWindow>,
+}
+
+// 一時停止と停止期間のテーブル（マイグレーションから呼ばれる）
+pub fn create_pause_tables(conn: &Connection) -> SqliteResult<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS account_pauses (
+            account_id INTEGER PRIMARY KEY,
+            reason TEXT,
+            paused_at TEXT NOT NULL,
+            resume_at TEXT,
+            FOREIGN KEY (account_id) REFERENCES bot_accounts(id) ON DELETE CASCADE
+        );
+        CREATE TABLE IF NOT EXISTS blackout_windows (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            account_id INTEGER,
+            starts_at TEXT NOT NULL,
+            ends_at TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (account_id) REFERENCES bot_accounts(id) ON DELETE CASCADE
+        );
+        CREATE INDEX IF NOT EXISTS idx_blackout_windows_period ON blackout_windows (starts_at, ends_at);"
+    )
+}
+
+// RFC3339 の日時を UTC の文字列にする
+fn parse_time(value: &str) -> Result<DateTime<Utc>, String> {
+    DateTime::parse_from_rfc3339(value.trim())
+        .map(|t| t.with_timezone(&Utc))
+        .map_err(|_| format!("日時「{}」が正しくありません（例: 2024-12-31T09:00:00+09:00）", value))
+}
+
+fn parse_resume_at(resume_at: Option<String>, now: DateTime<Utc>) -> Result<Option<String>, String> {
+    match resume_at.filter(|v| !v.trim().is_empty()) {
+        Some(value) => {
+            let resume_at = parse_time(&value)?;
+            if resume_at <= now {
+                return Err("再開日時は現在より後にしてください".to_string());
+            }
+            Ok(Some(resume_at.to_rfc3339()))
+        }
+        None => Ok(None),
+    }
+}
+
+fn expired(resume_at: Option<&str>, now: DateTime<Utc>) -> bool {
+    resume_at
+        .and_then(|v| DateTime::parse_from_rfc3339(v).ok())
+        .is_some_and(|t| t <= now)
+}
+
+fn load_global_pause(conn: &Connection) -> Option<GlobalPause> {
+    crate::get_app_setting(conn, SETTING_GLOBAL_PAUSE)
+        .filter(|v| !v.is_empty())
+        .and_then(|v| serde_json::from_str(&v).ok())
+}
+
+// 全体を一時停止する（None で再開）
+pub(crate) fn set_global_pause(conn: &Connection, pause: Option<(Option<String>, Option<String>)>, now: DateTime<Utc>) -> Result<(), String> {
+    let value = match pause {
+        Some((reason, resume_at)) => serde_json::to_string(&GlobalPause {
+            reason: reason.filter(|r| !r.trim().is_empty()),
+            paused_at: now.to_rfc3339(),
+            resume_at: parse_resume_at(resume_at, now)?,
+        }).map_err(|e| e.to_string())?,
+        None => String::new(),
+    };
+    crate::set_app_setting(conn, SETTING_GLOBAL_PAUSE, &value).map_err(|e| e.to_string())
+}
+
+// アカウントを一時停止する
+pub(crate) fn set_account_pause(conn: &Connection, account_id: i64, reason: Option<String>, resume_at: Option<String>, now: DateTime<Utc>) -> Result<(), String> {
+    crate::get_bot_account(conn, account_id)
+        .map_err(|_| format!("アカウント ID {} が存在しません", account_id))?;
+    let resume_at = parse_resume_at(resume_at, now)?;
+
+    conn.execute(
+        "INSERT INTO account_pauses (account_id, reason, paused_at, resume_at) VALUES (?, ?, ?, ?)
+         ON CONFLICT(account_id) DO UPDATE SET reason = excluded.reason, paused_at = excluded.paused_at, resume_at = excluded.resume_at",
+        params![account_id, reason.filter(|r| !r.trim().is_empty()), now.to_rfc3339(), resume_at],
+    ).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// 停止期間を保存（id を指定した場合は更新）
+pub(crate) fn save_blackout(
+    conn: &Connection,
+    id: Option<i64>,
+    name: &str,
+    account_id: Option<i64>,
+    starts_at: &str,
+    ends_at: &str,
+) -> Result<i64, String> {
+    if name.trim().is_empty() {
+        return Err("停止期間の名前が空です".to_string());
+    }
+    let starts_at = parse_time(starts_at)?;
+    let ends_at = parse_time(ends_at)?;
+    if ends_at <= starts_at {
+        return Err("停止期間の終了日時は開始日時より後にしてください".to_string());
+    }
+    if let Some(account_id) = account_id {
+        crate::get_bot_account(conn, account_id)
+            .map_err(|_| format!("アカウント ID {} が存在しません", account_id))?;
+    }
+
+    match id {
+        Some(id) => {
+            let updated = conn.execute(
+                "UPDATE blackout_windows SET name = ?, account_id = ?, starts_at = ?, ends_at = ? WHERE id = ?",
+                params![name.trim(), account_id, starts_at.to_rfc3339(), ends_at.to_rfc3339(), id],
+            ).map_err(|e| e.to_string())?;
+            if updated == 0 {
+                return Err(format!("停止期間 ID {} が存在しません", id));
+            }
+            Ok(id)
+        }
+        None => {
+            conn.execute(
+                "INSERT INTO blackout_windows (name, account_id, starts_at, ends_at, created_at) VALUES (?, ?, ?, ?, ?)",
+                params![name.trim(), account_id, starts_at.to_rfc3339(), ends_at.to_rfc3339(), Utc::now().to_rfc3339()],
+            ).map_err(|e| e.to_string())?;
+            Ok(conn.last_insert_rowid())
+        }
+    }
+}
+
+// アカウントが投稿を止めている理由（止めていなければ None）
+// 再開日時を過ぎた一時停止はここで解除する
+pub(crate) fn pause_reason(conn: &Connection, account_id: i64, now: DateTime<Utc>) -> SqliteResult<Option<String>> {
+    if let Some(pause) = load_global_pause(conn) {
+        if expired(pause.resume_at.as_deref(), now) {
+            crate::set_app_setting(conn, SETTING_GLOBAL_PAUSE, "")?;
+            println!("Global pause ended at {}", pause.resume_at.unwrap_or_default());
+        } else {
+            return Ok(Some(match pause.reason {
+                Some(reason) => format!("全体の一時停止中: {}", reason),
+                None => "全体の一時停止中".to_string(),
+            }));
+        }
+    }
+
+    let account_pause: Option<(Option<String>, Option<String>)> = conn.query_row(
+        "SELECT reason, resume_at FROM account_pauses WHERE account_id = ?",
+        params![account_id],
+        |row| Ok((row.get(0)?, row.get(1)?))
+    ).optional()?;
+    if let Some((reason, resume_at)) = account_pause {
+        if expired(resume_at.as_deref(), now) {
+            conn.execute("DELETE FROM account_pauses WHERE account_id = ?", params![account_id])?;
+            println!("Pause of account {} ended", account_id);
+        } else {
+            return Ok(Some(match reason {
+                Some(reason) => format!("アカウントの一時停止中: {}", reason),
+                None => "アカウントの一時停止中".to_string(),
+            }));
+        }
+    }
+
+    let blackout: Option<String> = conn.query_row(
+        "SELECT name FROM blackout_windows
+         WHERE (account_id IS NULL OR account_id = ?) AND starts_at <= ? AND ends_at > ?
+         ORDER BY starts_at LIMIT 1",
+        params![account_id, now.to_rfc3339(), now.to_rfc3339()],
+        |row| row.get(0)
+    ).optional()?;
+    Ok(blackout.map(|name| format!("停止期間「{}」中", name)))
+}
+
+// ロックを取って pause_reason を確認する
+pub(crate) fn check(state: &AppState, account_id: i64) -> Result<Option<String>, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    pause_reason(&conn, account_id, Utc::now()).map_err(|e| e.to_string())
+}
+
+pub(crate) fn load_pause_status(conn: &Connection, now: DateTime<Utc>) -> Result<PauseStatus, String> {
+    let global = load_global_pause(conn).filter(|p| !expired(p.resume_at.as_deref(), now));
+
+    let accounts = conn.prepare(
+        "SELECT ap.account_id, ba.account_name, ap.reason, ap.paused_at, ap.resume_at
+         FROM account_pauses ap
+         JOIN bot_accounts ba ON ap.account_id = ba.id
+         ORDER BY ba.account_name"
+    )
+    .and_then(|mut stmt| stmt.query_map([], |row| Ok(AccountPause {
+        account_id: row.get(0)?,
+        account_name: row.get(1)?,
+        reason: row.get(2)?,
+        paused_at: row.get(3)?,
+        resume_at: row.get(4)?,
+    }))?.collect::<SqliteResult<Vec<_>>>())
+    .map_err(|e| e.to_string())?
+    .into_iter()
+    .filter(|p| !expired(p.resume_at.as_deref(), now))
+    .collect();
+
+    let now_text = now.to_rfc3339();
+    let blackout_windows = conn.prepare(
+        "SELECT id, name, account_id, starts_at, ends_at, created_at FROM blackout_windows ORDER BY starts_at"
+    )
+    .and_then(|mut stmt| stmt.query_map([], |row| {
+        let starts_at: String = row.get(3)?;
+        let ends_at: String = row.get(4)?;
+        Ok(BlackoutWindow {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            account_id: row.get(2)?,
+            is_active: starts_at <= now_text && now_text < ends_at,
+            starts_at,
+            ends_at,
+            created_at: row.get(5)?,
+        })
+    })?.collect::<SqliteResult<Vec<_>>>())
+    .map_err(|e| e.to_string())?;
+
+    Ok(PauseStatus { global, accounts, blackout_windows })
+}
+
+#[tauri::command]
+pub fn get_pause_status(state: State<AppState>) -> Result<PauseStatus, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    load_pause_status(&conn, Utc::now())
+}
+
+// すべての自動投稿・返信を一時停止（resume_at を指定するとその日時に再開）
+#[tauri::command]
+pub fn pause_all(reason: Option<String>, resume_at: Option<String>, state: State<AppState>) -> Result<(), String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    set_global_pause(&conn, Some((reason, resume_at)), Utc::now())
+}
+
+#[tauri::command]
+pub fn resume_all(state: State<AppState>) -> Result<(), String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    set_global_pause(&conn, None, Utc::now())
+}
+
+#[tauri::command]
+pub fn pause_bot_account(account_id: i64, reason: Option<String>, resume_at: Option<String>, state: State<AppState>) -> Result<(), String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    set_account_pause(&conn, account_id, reason, resume_at, Utc::now())
+}
+
+#[tauri::command]
+pub fn resume_bot_account(account_id: i64, state: State<AppState>) -> Result<(), String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    conn.execute("DELETE FROM account_pauses WHERE account_id = ?", params![account_id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// 停止期間を保存（id を指定した場合は更新）
+#[tauri::command]
+pub fn save_blackout_window(
+    id: Option<i64>,
+    name: String,
+    account_id: Option<i64>,
+    starts_at: String,
+    ends_at: String,
+    state: State<AppState>
+) -> Result<i64, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    save_blackout(&conn, id, &name, account_id, &starts_at, &ends_at)
+}
+
+#[tauri::command]
+pub fn delete_blackout_window(id: i64, state: State<AppState>) -> Result<(), String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    conn.execute("DELETE FROM blackout_windows WHERE id = ?", params![id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
@@ -0,0 +1,352 @@
+// 返信候補の管理と選択
+//
+// 返信設定ごとに複数の返信文を候補として持ち、キーワード・正規表現の条件と
+// 選択方式（first_match / random / rotation）に従って返信するツイートごとに1件を選ぶ。
+// 候補は reply_settings.reply_candidates にJSONで保存する。候補が無い場合は reply_content を使う。
+
+use rusqlite::{Connection, params, OptionalExtension, Result as SqliteResult};
+use serde::{Serialize, Deserialize};
+use chrono::Utc;
+use regex::Regex;
+use tauri::State;
+
+use crate::AppState;
+use crate::twitter_api::Tweet;
+
+// 返信候補
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplyCandidate {
+    pub content: String,
+    #[serde(default)]
+    pub keywords: Vec<String>,          // いずれかを含むツイートにのみ使用（空なら条件なし）
+    #[serde(default)]
+    pub pattern: Option<String>,        // 一致するツイートにのみ使用（正規表現）
+}
+
+// 候補の選択方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CandidateSelection {
+    #[default]
+    FirstMatch,     // 条件に合う最初の候補
+    Random,         // 条件に合う候補からランダム
+    Rotation,       // 条件に合う候補を順番に使用
+}
+
+impl CandidateSelection {
+    fn as_str(&self) -> &'static str {
+        match self {
+            CandidateSelection::FirstMatch => "first_match",
+            CandidateSelection::Random => "random",
+            CandidateSelection::Rotation => "rotation",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "random" => CandidateSelection::Random,
+            "rotation" => CandidateSelection::Rotation,
+            _ => CandidateSelection::FirstMatch,
+        }
+    }
+}
+
+// 返信設定の候補一覧（UI用）
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReplyCandidateSettings {
+    pub reply_setting_id: i64,
+    pub candidates: Vec<ReplyCandidate>,
+    pub selection: CandidateSelection,
+    pub rotation_index: i64,
+}
+
+struct CompiledCandidate {
+    content: String,
+    keywords: Vec<String>,
+    pattern: Option<Regex>,
+}
+
+impl CompiledCandidate {
+    fn matches(&self, tweet: &Tweet) -> bool {
+        let text = tweet.text.to_lowercase();
+        let keyword_ok = self.keywords.is_empty() || self.keywords.iter().any(|k| text.contains(k.as_str()));
+        let pattern_ok = self.pattern.as_ref().map_or(true, |re| re.is_match(&tweet.text));
+        keyword_ok && pattern_ok
+    }
+}
+
+// 返信エンジンが使う、条件をコンパイル済みの候補セット
+pub(crate) struct CandidateSet {
+    candidates: Vec<CompiledCandidate>,
+    selection: CandidateSelection,
+    rotation_index: usize,
+}
+
+// 選ばれた候補（index は候補一覧上の位置、従来の reply_content を使う場合は None）
+pub(crate) struct SelectedCandidate {
+    pub index: Option<usize>,
+    pub next_rotation_index: Option<usize>,   // 返信に成功した場合に保存する次のローテーション位置
+    pub content: String,
+}
+
+impl CandidateSet {
+    pub(crate) fn compile(candidates: &[ReplyCandidate], selection: CandidateSelection, rotation_index: i64) -> Result<Self, String> {
+        let candidates = candidates.iter()
+            .map(|c| {
+                let pattern = c.pattern.as_deref()
+                    .map(|p| Regex::new(p).map_err(|e| format!("正規表現 {} が不正です: {}", p, e)))
+                    .transpose()?;
+                Ok(CompiledCandidate {
+                    content: c.content.clone(),
+                    keywords: c.keywords.iter().map(|k| k.to_lowercase()).collect(),
+                    pattern,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Ok(CandidateSet {
+            candidates,
+            selection,
+            rotation_index: rotation_index.max(0) as usize,
+        })
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.candidates.is_empty()
+    }
+
+    // ツイートに使う候補を選ぶ（条件に合う候補が無ければ None）
+    pub(crate) fn select(&self, tweet: &Tweet) -> Option<SelectedCandidate> {
+        let matching: Vec<usize> = self.candidates.iter()
+            .enumerate()
+            .filter(|(_, c)| c.matches(tweet))
+            .map(|(i, _)| i)
+            .collect();
+
+        let index = match self.selection {
+            CandidateSelection::FirstMatch => *matching.first()?,
+            CandidateSelection::Random => {
+                if matching.is_empty() {
+                    return None;
+                }
+                matching[rand::random::<usize>() % matching.len()]
+            }
+            // 前回使った候補の次から、条件に合う候補を探す
+            CandidateSelection::Rotation => {
+                let start = self.rotation_index % self.candidates.len().max(1);
+                *matching.iter().find(|&&i| i >= start).or(matching.first())?
+            }
+        };
+
+        Some(SelectedCandidate {
+            index: Some(index),
+            next_rotation_index: Some((index + 1) % self.candidates.len()),
+            content: self.candidates[index].content.clone(),
+        })
+    }
+
+    pub(crate) fn set_rotation_index(&mut self, rotation_index: usize) {
+        self.rotation_index = rotation_index;
+    }
+}
+
+// 候補関連の列を追加（マイグレーションから呼ばれる）
+pub fn create_candidate_columns(conn: &Connection) -> SqliteResult<()> {
+    let columns = [
+        ("reply_candidates", "TEXT"),
+        ("candidate_selection", "TEXT NOT NULL DEFAULT 'first_match'"),
+        ("candidate_rotation_index", "INTEGER NOT NULL DEFAULT 0"),
+    ];
+
+    for (name, definition) in columns {
+        let exists: i32 = conn.query_row(
+            "SELECT COUNT(*) FROM pragma_table_info('reply_settings') WHERE name = ?",
+            params![name],
+            |row| row.get(0)
+        ).unwrap_or(0);
+
+        if exists == 0 {
+            conn.execute(&format!("ALTER TABLE reply_settings ADD COLUMN {} {}", name, definition), [])?;
+            println!("Added {} column to reply_settings table", name);
+        }
+    }
+
+    Ok(())
+}
+
+// 保存前の検証と正規化（空の候補・キーワードを除去）
+pub fn normalize_candidates(candidates: Vec<ReplyCandidate>) -> Result<Vec<ReplyCandidate>, String> {
+    let candidates: Vec<ReplyCandidate> = candidates.into_iter()
+        .filter(|c| !c.content.trim().is_empty())
+        .map(|c| ReplyCandidate {
+            content: c.content,
+            keywords: c.keywords.into_iter()
+                .map(|k| k.trim().to_string())
+                .filter(|k| !k.is_empty())
+                .collect(),
+            pattern: c.pattern.filter(|p| !p.trim().is_empty()),
+        })
+        .collect();
+
+    CandidateSet::compile(&candidates, CandidateSelection::FirstMatch, 0)?;
+    Ok(candidates)
+}
+
+pub(crate) fn parse_candidates(json: Option<&str>) -> Vec<ReplyCandidate> {
+    json.and_then(|json| serde_json::from_str(json).ok()).unwrap_or_default()
+}
+
+pub(crate) fn parse_selection(value: Option<&str>) -> CandidateSelection {
+    value.map(CandidateSelection::parse).unwrap_or_default()
+}
+
+pub(crate) fn load_candidates(conn: &Connection, reply_setting_id: i64) -> SqliteResult<Option<ReplyCandidateSettings>> {
+    conn.query_row(
+        "SELECT reply_candidates, candidate_selection, candidate_rotation_index FROM reply_settings WHERE id = ?",
+        params![reply_setting_id],
+        |row| {
+            let candidates: Option<String> = row.get(0)?;
+            let selection: Option<String> = row.get(1)?;
+            Ok(ReplyCandidateSettings {
+                reply_setting_id,
+                candidates: parse_candidates(candidates.as_deref()),
+                selection: parse_selection(selection.as_deref()),
+                rotation_index: row.get(2)?,
+            })
+        }
+    ).optional()
+}
+
+// 候補を保存し、先頭の候補を reply_content に反映する（GitHub Actions側は reply_content のみ使用）
+pub(crate) fn save_candidates(conn: &Connection, reply_setting_id: i64, candidates: &[ReplyCandidate], selection: CandidateSelection) -> Result<(), String> {
+    let json = serde_json::to_string(candidates).map_err(|e| format!("JSON変換エラー: {}", e))?;
+    let now = Utc::now().to_rfc3339();
+
+    conn.execute(
+        "UPDATE reply_settings SET reply_candidates = ?, candidate_selection = ?, candidate_rotation_index = 0, updated_at = ? WHERE id = ?",
+        params![json, selection.as_str(), now, reply_setting_id],
+    ).map_err(|e| e.to_string())?;
+
+    if let Some(first) = candidates.first() {
+        conn.execute(
+            "UPDATE reply_settings SET reply_content = ? WHERE id = ?",
+            params![first.content, reply_setting_id],
+        ).map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+pub(crate) fn set_rotation_index(conn: &Connection, reply_setting_id: i64, rotation_index: usize) -> SqliteResult<()> {
+    conn.execute(
+        "UPDATE reply_settings SET candidate_rotation_index = ? WHERE id = ?",
+        params![rotation_index as i64, reply_setting_id],
+    )?;
+    Ok(())
+}
+
+// 返信設定の候補を取得
+#[tauri::command]
+pub fn get_reply_candidates(reply_setting_id: i64, state: State<AppState>) -> Result<ReplyCandidateSettings, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+
+    load_candidates(&conn, reply_setting_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("返信設定 ID {} が存在しません", reply_setting_id))
+}
+
+// 返信設定の候補を更新（ローテーション位置は先頭に戻す）
+#[tauri::command]
+pub fn update_reply_candidates(
+    reply_setting_id: i64,
+    candidates: Vec<ReplyCandidate>,
+    selection: CandidateSelection,
+    state: State<AppState>
+) -> Result<ReplyCandidateSettings, String> {
+    let candidates = normalize_candidates(candidates)?;
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+
+    if load_candidates(&conn, reply_setting_id).map_err(|e| e.to_string())?.is_none() {
+        return Err(format!("返信設定 ID {} が存在しません", reply_setting_id));
+    }
+
+    save_candidates(&conn, reply_setting_id, &candidates, selection)?;
+
+    load_candidates(&conn, reply_setting_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("返信設定 ID {} が存在しません", reply_setting_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tweet(text: &str) -> Tweet {
+        Tweet {
+            id: "1".to_string(),
+            text: text.to_string(),
+            created_at: None,
+            author_id: None,
+            conversation_id: None,
+            referenced_tweets: Vec::new(),
+        }
+    }
+
+    fn candidate(content: &str, keywords: &[&str]) -> ReplyCandidate {
+        ReplyCandidate {
+            content: content.to_string(),
+            keywords: keywords.iter().map(|k| k.to_string()).collect(),
+            pattern: None,
+        }
+    }
+
+    fn candidates() -> Vec<ReplyCandidate> {
+        vec![candidate("A", &[]), candidate("B", &["質問"]), candidate("C", &[])]
+    }
+
+    #[test]
+    fn rotation_continues_from_the_saved_index_and_skips_unmatched() {
+        let mut set = CandidateSet::compile(&candidates(), CandidateSelection::Rotation, 0).unwrap();
+        let mut picked = Vec::new();
+        for _ in 0..4 {
+            let selected = set.select(&tweet("こんにちは")).unwrap();
+            picked.push(selected.content);
+            set.set_rotation_index(selected.next_rotation_index.unwrap());
+        }
+        // 「質問」を含まないツイートでは B を飛ばす
+        assert_eq!(picked, ["A", "C", "A", "C"]);
+
+        let set = CandidateSet::compile(&candidates(), CandidateSelection::Rotation, 1).unwrap();
+        let selected = set.select(&tweet("質問です")).unwrap();
+        assert_eq!((selected.index, selected.next_rotation_index), (Some(1), Some(2)));
+
+        // 保存済みの位置が候補数を超えていても先頭に戻る
+        let set = CandidateSet::compile(&candidates(), CandidateSelection::Rotation, 5).unwrap();
+        assert_eq!(set.select(&tweet("こんにちは")).unwrap().content, "C");
+    }
+
+    #[test]
+    fn random_picks_only_matching_candidates() {
+        let set = CandidateSet::compile(&candidates(), CandidateSelection::Random, 0).unwrap();
+        for _ in 0..50 {
+            let content = set.select(&tweet("こんにちは")).unwrap().content;
+            assert!(content == "A" || content == "C", "unexpected candidate {}", content);
+        }
+
+        let only_questions = vec![candidate("B", &["質問"])];
+        let set = CandidateSet::compile(&only_questions, CandidateSelection::Random, 0).unwrap();
+        assert!(set.select(&tweet("こんにちは")).is_none());
+    }
+
+    #[test]
+    fn first_match_uses_keywords_and_patterns() {
+        let mut list = candidates();
+        list.insert(0, ReplyCandidate { content: "P".to_string(), keywords: Vec::new(), pattern: Some(r"^\d+$".to_string()) });
+        let set = CandidateSet::compile(&list, CandidateSelection::FirstMatch, 0).unwrap();
+        assert_eq!(set.select(&tweet("123")).unwrap().content, "P");
+        assert_eq!(set.select(&tweet("こんにちは")).unwrap().content, "A");
+
+        let invalid = vec![ReplyCandidate { content: "X".to_string(), keywords: Vec::new(), pattern: Some("(".to_string()) }];
+        assert!(CandidateSet::compile(&invalid, CandidateSelection::FirstMatch, 0).is_err());
+    }
+}
@@ -0,0 +1,329 @@
+// 返信監視のチェックポイント管理
+//
+// 返信設定 × 監視対象（自分のBot、または watched_users の外部アカウント）ごとに、最後に確認したツイートID・確認日時・直近のエラーを保持する。
+// 旧形式の reply_settings.last_checked_tweet_ids（"botId:tweetId" のJSON配列）は
+// マイグレーション時に取り込み、以降はエクスポート時にこのテーブルから生成する。
+
+use rusqlite::{Connection, params, OptionalExtension, Result as SqliteResult};
+use serde::{Serialize, Deserialize};
+use chrono::Utc;
+use std::collections::HashMap;
+use tauri::State;
+
+use crate::AppState;
+
+// チェックポイント（UI・デバッグ用）
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReplyCheckpoint {
+    pub id: i64,
+    pub reply_setting_id: i64,
+    pub target_type: String,      // "bot"（bot_accounts.id）または "user"（watched_users.id）
+    pub target_id: i64,
+    pub target_name: Option<String>,
+    pub last_seen_tweet_id: Option<String>,
+    pub baseline_at: String,      // last_seen_tweet_id が無い場合、この日時以降のツイートを返信対象にする
+    pub last_checked_at: Option<String>,
+    pub last_error: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+// 監視対象（チェックポイント・タイムライン・1日の返信数のキー）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub(crate) enum WatchTarget {
+    Bot(i64),
+    User(i64),
+}
+
+impl WatchTarget {
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            WatchTarget::Bot(_) => "bot",
+            WatchTarget::User(_) => "user",
+        }
+    }
+
+    pub(crate) fn id(&self) -> i64 {
+        match self {
+            WatchTarget::Bot(id) | WatchTarget::User(id) => *id,
+        }
+    }
+
+    pub(crate) fn from_parts(kind: &str, id: i64) -> Option<Self> {
+        match kind {
+            "bot" => Some(WatchTarget::Bot(id)),
+            "user" => Some(WatchTarget::User(id)),
+            _ => None,
+        }
+    }
+}
+
+// 返信エンジンが参照するチェックポイントの状態
+#[derive(Debug, Clone)]
+pub(crate) struct CheckpointState {
+    pub last_seen_tweet_id: Option<String>,
+    pub baseline_at: String,
+}
+
+// チェックポイントテーブル作成と旧形式からの取り込み（マイグレーションから呼ばれる）
+pub fn create_checkpoint_table(conn: &Connection) -> SqliteResult<()> {
+    let table_exists: i32 = conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='reply_checkpoints'",
+        [],
+        |row| row.get(0)
+    ).unwrap_or(0);
+
+    if table_exists > 0 {
+        return Ok(());
+    }
+
+    conn.execute(
+        "CREATE TABLE reply_checkpoints (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            reply_setting_id INTEGER NOT NULL,
+            target_type TEXT NOT NULL DEFAULT 'bot',
+            target_id INTEGER NOT NULL,
+            last_seen_tweet_id TEXT,
+            baseline_at TEXT NOT NULL,
+            last_checked_at TEXT,
+            last_error TEXT,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            FOREIGN KEY (reply_setting_id) REFERENCES reply_settings(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE UNIQUE INDEX idx_reply_checkpoints_target ON reply_checkpoints (reply_setting_id, target_type, target_id)",
+        [],
+    )?;
+
+    // 旧形式の last_checked_tweet_ids を取り込む
+    let legacy: Vec<(i64, String, String, String)> = {
+        let mut stmt = conn.prepare(
+            "SELECT id, last_checked_tweet_ids, created_at, updated_at FROM reply_settings WHERE last_checked_tweet_ids IS NOT NULL"
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))?;
+        rows.collect::<SqliteResult<Vec<_>>>()?
+    };
+
+    let now = Utc::now().to_rfc3339();
+    let mut imported = 0;
+    for (setting_id, json, created_at, updated_at) in legacy {
+        for (target_bot_id, tweet_id) in parse_legacy_ids(&json) {
+            imported += conn.execute(
+                "INSERT OR IGNORE INTO reply_checkpoints
+                 (reply_setting_id, target_type, target_id, last_seen_tweet_id, baseline_at, last_checked_at, created_at, updated_at)
+                 VALUES (?, 'bot', ?, ?, ?, ?, ?, ?)",
+                params![setting_id, target_bot_id, tweet_id, created_at, updated_at, now, now],
+            )?;
+        }
+    }
+
+    println!("Created reply_checkpoints table ({} checkpoints imported from last_checked_tweet_ids)", imported);
+    Ok(())
+}
+
+// "botId:tweetId" 形式のJSON配列を監視対象BotID → ツイートIDのマップに変換
+fn parse_legacy_ids(json: &str) -> HashMap<i64, String> {
+    serde_json::from_str::<Vec<String>>(json)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|entry| {
+            let (bot_id, tweet_id) = entry.split_once(':')?;
+            Some((bot_id.parse().ok()?, tweet_id.to_string()))
+        })
+        .collect()
+}
+
+// GitHub Actions用スクリプト向けに旧形式のJSON配列を生成（自分のBotのみ）
+pub(crate) fn legacy_ids_json(conn: &Connection, reply_setting_id: i64) -> String {
+    let mut entries: Vec<String> = conn.prepare(
+        "SELECT target_id, last_seen_tweet_id FROM reply_checkpoints
+         WHERE reply_setting_id = ? AND target_type = 'bot' AND last_seen_tweet_id IS NOT NULL
+         ORDER BY target_id"
+    )
+    .and_then(|mut stmt| {
+        stmt.query_map(params![reply_setting_id], |row| {
+            Ok(format!("{}:{}", row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<SqliteResult<Vec<_>>>()
+    })
+    .unwrap_or_default();
+
+    entries.sort();
+    serde_json::to_string(&entries).unwrap_or_else(|_| "[]".to_string())
+}
+
+// 返信設定の全チェックポイントを取得
+pub(crate) fn load_checkpoints(conn: &Connection, reply_setting_id: i64) -> SqliteResult<HashMap<WatchTarget, CheckpointState>> {
+    let mut stmt = conn.prepare(
+        "SELECT target_type, target_id, last_seen_tweet_id, baseline_at FROM reply_checkpoints WHERE reply_setting_id = ?"
+    )?;
+    let rows = stmt.query_map(params![reply_setting_id], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?, CheckpointState {
+            last_seen_tweet_id: row.get(2)?,
+            baseline_at: row.get(3)?,
+        }))
+    })?
+    .collect::<SqliteResult<Vec<_>>>()?;
+
+    Ok(rows.into_iter()
+        .filter_map(|(kind, id, state)| Some((WatchTarget::from_parts(&kind, id)?, state)))
+        .collect())
+}
+
+// 最後に確認したツイートIDを更新（エラーはクリア）
+pub(crate) fn set_last_seen(conn: &Connection, reply_setting_id: i64, target: WatchTarget, tweet_id: &str) -> SqliteResult<()> {
+    let now = Utc::now().to_rfc3339();
+    conn.execute(
+        "INSERT INTO reply_checkpoints
+         (reply_setting_id, target_type, target_id, last_seen_tweet_id, baseline_at, last_checked_at, last_error, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?5, NULL, ?5, ?5)
+         ON CONFLICT(reply_setting_id, target_type, target_id) DO UPDATE SET
+             last_seen_tweet_id = excluded.last_seen_tweet_id,
+             last_checked_at = excluded.last_checked_at,
+             last_error = NULL,
+             updated_at = excluded.updated_at",
+        params![reply_setting_id, target.kind(), target.id(), tweet_id, now],
+    )?;
+    Ok(())
+}
+
+// 確認日時を記録（error が Some ならエラーを記録、None ならクリア）
+// チェックポイントが無い場合は返信設定の作成日時を基準日時として作成する
+pub(crate) fn mark_checked(conn: &Connection, reply_setting_id: i64, target: WatchTarget, error: Option<&str>) -> SqliteResult<()> {
+    let now = Utc::now().to_rfc3339();
+    conn.execute(
+        "INSERT INTO reply_checkpoints
+         (reply_setting_id, target_type, target_id, last_seen_tweet_id, baseline_at, last_checked_at, last_error, created_at, updated_at)
+         SELECT ?1, ?2, ?3, NULL, rs.created_at, ?4, ?5, ?4, ?4 FROM reply_settings rs WHERE rs.id = ?1
+         ON CONFLICT(reply_setting_id, target_type, target_id) DO UPDATE SET
+             last_checked_at = excluded.last_checked_at,
+             last_error = excluded.last_error,
+             updated_at = excluded.updated_at",
+        params![reply_setting_id, target.kind(), target.id(), now, error],
+    )?;
+    Ok(())
+}
+
+// 返信設定を作り直した際に、同じ監視対象のチェックポイントを新しい設定へ引き継ぐ
+pub(crate) fn carry_over(conn: &Connection, from_setting_ids: &[i64], to_setting_id: i64, targets: &[WatchTarget]) -> SqliteResult<()> {
+    let now = Utc::now().to_rfc3339();
+    for from_id in from_setting_ids {
+        for target in targets {
+            conn.execute(
+                "INSERT OR IGNORE INTO reply_checkpoints
+                 (reply_setting_id, target_type, target_id, last_seen_tweet_id, baseline_at, last_checked_at, last_error, created_at, updated_at)
+                 SELECT ?, target_type, target_id, last_seen_tweet_id, baseline_at, last_checked_at, last_error, ?, ?
+                 FROM reply_checkpoints
+                 WHERE reply_setting_id = ? AND target_type = ? AND target_id = ? AND last_seen_tweet_id IS NOT NULL",
+                params![to_setting_id, now, now, from_id, target.kind(), target.id()],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+// 存在しない返信設定・監視対象のチェックポイントを削除
+pub(crate) fn delete_orphaned(conn: &Connection) -> SqliteResult<usize> {
+    let by_setting = conn.execute(
+        "DELETE FROM reply_checkpoints WHERE reply_setting_id NOT IN (SELECT id FROM reply_settings)",
+        [],
+    )?;
+    let by_user = conn.execute(
+        "DELETE FROM reply_checkpoints WHERE target_type = 'user' AND target_id NOT IN (SELECT id FROM watched_users)",
+        [],
+    )?;
+    Ok(by_setting + by_user)
+}
+
+// チェックポイント一覧（返信設定を指定しない場合は有効な設定すべて）
+#[tauri::command]
+pub fn get_reply_checkpoints(reply_setting_id: Option<i64>, state: State<AppState>) -> Result<Vec<ReplyCheckpoint>, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+
+    let mut stmt = conn.prepare(
+        "SELECT rc.id, rc.reply_setting_id, rc.target_type, rc.target_id,
+                CASE rc.target_type WHEN 'user' THEN '@' || wu.username ELSE ba.account_name END,
+                rc.last_seen_tweet_id, rc.baseline_at, rc.last_checked_at, rc.last_error, rc.created_at, rc.updated_at
+         FROM reply_checkpoints rc
+         INNER JOIN reply_settings rs ON rc.reply_setting_id = rs.id
+         LEFT JOIN bot_accounts ba ON rc.target_type = 'bot' AND rc.target_id = ba.id
+         LEFT JOIN watched_users wu ON rc.target_type = 'user' AND rc.target_id = wu.id
+         WHERE (?1 IS NULL AND rs.is_active = 1) OR rc.reply_setting_id = ?1
+         ORDER BY rc.reply_setting_id, rc.target_type, rc.target_id"
+    ).map_err(|e| e.to_string())?;
+
+    let checkpoints = stmt.query_map(params![reply_setting_id], |row| {
+        Ok(ReplyCheckpoint {
+            id: row.get(0)?,
+            reply_setting_id: row.get(1)?,
+            target_type: row.get(2)?,
+            target_id: row.get(3)?,
+            target_name: row.get(4)?,
+            last_seen_tweet_id: row.get(5)?,
+            baseline_at: row.get(6)?,
+            last_checked_at: row.get(7)?,
+            last_error: row.get(8)?,
+            created_at: row.get(9)?,
+            updated_at: row.get(10)?,
+        })
+    })
+    .map_err(|e| e.to_string())?
+    .collect::<SqliteResult<Vec<_>>>()
+    .map_err(|e| e.to_string())?;
+
+    Ok(checkpoints)
+}
+
+// チェックポイントをリセット
+// tweet_id を指定した場合はそのツイートまで確認済みとして扱い、
+// 指定しない場合は現在時刻以降のツイートから返信対象にする
+// target_type は "bot"（既定）または "user"
+#[tauri::command]
+pub fn reset_reply_checkpoint(
+    reply_setting_id: i64,
+    target_id: i64,
+    target_type: Option<String>,
+    tweet_id: Option<String>,
+    state: State<AppState>
+) -> Result<(), String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    let now = Utc::now().to_rfc3339();
+
+    let target_type = target_type.unwrap_or_else(|| "bot".to_string());
+    let target = WatchTarget::from_parts(&target_type, target_id)
+        .ok_or_else(|| format!("監視対象の種類 {} が不正です", target_type))?;
+
+    let setting_exists: Option<i64> = conn.query_row(
+        "SELECT id FROM reply_settings WHERE id = ?",
+        params![reply_setting_id],
+        |row| row.get(0)
+    ).optional().map_err(|e| e.to_string())?;
+
+    if setting_exists.is_none() {
+        return Err(format!("返信設定 ID {} が存在しません", reply_setting_id));
+    }
+
+    let tweet_id = tweet_id.map(|id| id.trim().to_string()).filter(|id| !id.is_empty());
+    if let Some(id) = &tweet_id {
+        if id.parse::<u64>().is_err() {
+            return Err(format!("ツイートID {} が不正です", id));
+        }
+    }
+
+    conn.execute(
+        "INSERT INTO reply_checkpoints
+         (reply_setting_id, target_type, target_id, last_seen_tweet_id, baseline_at, last_checked_at, last_error, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, NULL, NULL, ?5, ?5)
+         ON CONFLICT(reply_setting_id, target_type, target_id) DO UPDATE SET
+             last_seen_tweet_id = excluded.last_seen_tweet_id,
+             baseline_at = excluded.baseline_at,
+             last_error = NULL,
+             updated_at = excluded.updated_at",
+        params![reply_setting_id, target.kind(), target.id(), tweet_id, now],
+    ).map_err(|e| e.to_string())?;
+
+    Ok(())
+}
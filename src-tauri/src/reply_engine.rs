@@ -0,0 +1,828 @@
+// 返信監視エンジン（scripts/reply-monitor.js のアプリ内実装）
+//
+// 監視対象Botのタイムラインを since_id 付きで取得し、新しいツイートに返信Botで返信する。
+// 同じ監視対象は1サイクルにつき1回だけ取得し、複数の返信設定で共有する。
+// 新着が多く取得しきれない場合はチェックポイントを進めず、until_id で古い範囲から順に取得し直す。
+// 返信1件ごとに実行ログの書き込みとチェックポイントの更新を同じトランザクションで行う。
+
+use rusqlite::{Connection, params, Result as SqliteResult};
+use serde::{Serialize, Deserialize};
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+use crate::{AppState, BotAccount, ExecutionLog};
+use crate::after_commit;
+use crate::dry_run;
+use crate::log_details::LogDetails;
+use crate::posting_pause;
+use crate::reply_checkpoints::{self, CheckpointState, WatchTarget};
+use crate::reply_candidates::{self, CandidateSelection, CandidateSet, ReplyCandidate, SelectedCandidate};
+use crate::reply_filters::{self, CompiledFilters, FilterDecision, ReplyFilters};
+use crate::twitter_api::{self, Tweet};
+use crate::watched_users::{self, WatchedUser};
+
+const SETTING_ENABLED: &str = "reply_monitor_enabled";
+const SETTING_INTERVAL_MINUTES: &str = "reply_monitor_interval_minutes";
+const SETTING_LAST_RUN_AT: &str = "reply_monitor_last_run_at";
+const SETTING_LAST_RESULT: &str = "reply_monitor_last_result";
+const SETTING_RETRY_AFTER: &str = "reply_monitor_retry_after";
+const SETTING_BACKFILL_UNTIL: &str = "reply_monitor_backfill_until";
+
+const DEFAULT_INTERVAL_MINUTES: i64 = 15;
+const MIN_INTERVAL_MINUTES: i64 = 5;
+
+// 監視ループの確認間隔と、返信投稿間の待機時間（レート制限対策）
+const LOOP_TICK: Duration = Duration::from_secs(60);
+const REPLY_DELAY: Duration = Duration::from_secs(1);
+
+// 1回の返信監視サイクルの結果
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ReplyCycleSummary {
+    pub settings_processed: i32,
+    pub targets_checked: i32,
+    pub replies_sent: i32,
+    #[serde(default)]
+    pub skipped: i32,
+    #[serde(default)]
+    pub simulated: i32,
+    pub errors: i32,
+    pub started_at: String,
+    pub finished_at: String,
+}
+
+// 返信監視の設定と状態
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReplyMonitorSettings {
+    pub enabled: bool,
+    pub interval_minutes: i64,
+    pub is_running: bool,
+    pub last_run_at: Option<String>,
+    pub last_result: Option<ReplyCycleSummary>,
+    #[serde(default)]
+    pub retry_after: HashMap<i64, String>,    // アカウントIDごとのレート制限の解除日時（それまでそのアカウントで取得・返信しない）
+}
+
+// 実行対象の返信設定
+struct ActiveReplySetting {
+    id: i64,
+    reply_bot_id: i64,
+    reply_content: String,
+    targets: Vec<WatchTarget>,
+    checkpoints: HashMap<WatchTarget, CheckpointState>,
+    filters: ReplyFilters,
+    candidates: Vec<ReplyCandidate>,
+    candidate_selection: CandidateSelection,
+    candidate_rotation_index: i64,
+    dry_run: bool,      // 返信せず実行ログに simulated として記録する
+    created_at: String,
+}
+
+impl ActiveReplySetting {
+    fn last_seen(&self, target: WatchTarget) -> Option<&str> {
+        self.checkpoints.get(&target)?.last_seen_tweet_id.as_deref()
+    }
+
+    // チェックポイントを保存（ドライランではシミュレーション用に別保存する）
+    fn save_last_seen(&self, conn: &Connection, target: WatchTarget, tweet_id: &str) -> SqliteResult<()> {
+        if self.dry_run {
+            dry_run::set_reply_checkpoint(conn, self.id, target, tweet_id)
+        } else {
+            reply_checkpoints::set_last_seen(conn, self.id, target, tweet_id)
+        }
+    }
+
+    // メモリ上のチェックポイントを進める（DBへの書き込みは呼び出し側で行う）
+    fn advance_checkpoint(&mut self, target: WatchTarget, tweet_id: &str) {
+        let created_at = self.created_at.clone();
+        self.checkpoints.entry(target)
+            .or_insert_with(|| CheckpointState { last_seen_tweet_id: None, baseline_at: created_at })
+            .last_seen_tweet_id = Some(tweet_id.to_string());
+    }
+}
+
+// ツイートIDの比較（IDは時系列順の数値）
+fn compare_tweet_ids(a: &str, b: &str) -> std::cmp::Ordering {
+    match (a.parse::<u64>(), b.parse::<u64>()) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        _ => (a.len(), a).cmp(&(b.len(), b)),
+    }
+}
+
+fn tweet_id_newer(a: &str, b: &str) -> bool {
+    compare_tweet_ids(a, b) == std::cmp::Ordering::Greater
+}
+
+// チェックポイントより新しいツイートを古い順に返す
+// 確認済みツイートが無い場合は基準日時（既定は返信設定の作成日時）以降に投稿されたツイートのみを対象にする
+fn new_tweets_for(tweets: &[Tweet], checkpoint: Option<&CheckpointState>, setting_created_at: &str) -> Vec<Tweet> {
+    let last_seen = checkpoint.and_then(|c| c.last_seen_tweet_id.as_ref());
+    let baseline_at = checkpoint.map(|c| c.baseline_at.as_str()).unwrap_or(setting_created_at);
+    let baseline_at = DateTime::parse_from_rfc3339(baseline_at).ok();
+
+    let mut new_tweets: Vec<Tweet> = tweets.iter()
+        .filter(|tweet| match last_seen {
+            Some(last_seen) => tweet_id_newer(&tweet.id, last_seen),
+            None => match (&tweet.created_at, baseline_at) {
+                (Some(tweet_time), Some(setting_time)) => DateTime::parse_from_rfc3339(tweet_time)
+                    .map(|t| t >= setting_time)
+                    .unwrap_or(false),
+                _ => false,
+            },
+        })
+        .cloned()
+        .collect();
+
+    new_tweets.sort_by(|a, b| compare_tweet_ids(&a.id, &b.id));
+    new_tweets
+}
+
+fn load_active_settings(conn: &Connection) -> SqliteResult<Vec<ActiveReplySetting>> {
+    let mut stmt = conn.prepare(
+        "SELECT rs.id, rs.reply_bot_id, rs.reply_content, rs.target_bot_ids, rs.created_at, rs.reply_filters,
+                rs.reply_candidates, rs.candidate_selection, rs.candidate_rotation_index, rs.watched_user_ids
+         FROM reply_settings rs
+         INNER JOIN bot_accounts ba ON rs.reply_bot_id = ba.id
+         WHERE rs.is_active = 1 AND ba.status = 'active'
+         ORDER BY rs.id"
+    )?;
+
+    let mut settings = stmt.query_map([], |row| {
+        let target_bot_ids: Vec<i64> = serde_json::from_str(&row.get::<_, String>(3)?).unwrap_or_default();
+        let watched_user_ids: Vec<i64> = serde_json::from_str(&row.get::<_, String>(9)?).unwrap_or_default();
+        let filters_json: Option<String> = row.get(5)?;
+        let candidates_json: Option<String> = row.get(6)?;
+        let selection: Option<String> = row.get(7)?;
+        Ok(ActiveReplySetting {
+            id: row.get(0)?,
+            reply_bot_id: row.get(1)?,
+            reply_content: row.get(2)?,
+            targets: target_bot_ids.into_iter().map(WatchTarget::Bot)
+                .chain(watched_user_ids.into_iter().map(WatchTarget::User))
+                .collect(),
+            checkpoints: HashMap::new(),
+            filters: reply_filters::parse_filters(filters_json.as_deref()),
+            candidates: reply_candidates::parse_candidates(candidates_json.as_deref()),
+            candidate_selection: reply_candidates::parse_selection(selection.as_deref()),
+            candidate_rotation_index: row.get(8)?,
+            dry_run: false,
+            created_at: row.get(4)?,
+        })
+    })?
+    .collect::<SqliteResult<Vec<_>>>()?;
+
+    for setting in settings.iter_mut() {
+        setting.checkpoints = reply_checkpoints::load_checkpoints(conn, setting.id)?;
+        setting.dry_run = dry_run::is_dry_run(conn, setting.reply_bot_id);
+
+        // ドライランではシミュレーション上の進行状況を重ねる（本番より進んでいる場合のみ）
+        if setting.dry_run {
+            for target in setting.targets.clone() {
+                if let Some(tweet_id) = dry_run::reply_checkpoint(conn, setting.id, target)? {
+                    if setting.last_seen(target).map_or(true, |last_seen| tweet_id_newer(&tweet_id, last_seen)) {
+                        setting.advance_checkpoint(target, &tweet_id);
+                    }
+                }
+            }
+            if let Some(rotation_index) = dry_run::candidate_rotation(conn, setting.id)? {
+                setting.candidate_rotation_index = rotation_index;
+            }
+        }
+    }
+
+    Ok(settings)
+}
+
+// アクティブなBotアカウントとキャッシュ済みのTwitterユーザーIDを取得
+pub(crate) fn load_active_account(conn: &Connection, account_id: i64) -> Option<(BotAccount, Option<String>)> {
+    let account = crate::get_bot_account(conn, account_id).ok()?;
+    if account.status != "active" {
+        return None;
+    }
+    let user_id: Option<String> = conn.query_row(
+        "SELECT twitter_user_id FROM bot_accounts WHERE id = ?",
+        params![account_id],
+        |row| row.get(0)
+    ).ok().flatten();
+    Some((account, user_id))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn write_log(
+    conn: &Connection,
+    account_id: i64,
+    log_type: &str,
+    status: &str,
+    message: String,
+    tweet_id: Option<String>,
+    tweet_content: Option<String>,
+    details: LogDetails,
+) -> SqliteResult<i64> {
+    crate::insert_execution_log(conn, &ExecutionLog {
+        id: None,
+        account_id,
+        log_type: log_type.to_string(),
+        message,
+        tweet_id,
+        tweet_content,
+        status: status.to_string(),
+        created_at: Utc::now().to_rfc3339(),
+        details,
+    })
+}
+
+// TwitterユーザーIDを取得（未取得なら /2/users/me で取得して bot_accounts にキャッシュ）
+pub(crate) async fn resolve_twitter_user_id(
+    state: &AppState,
+    account_id: i64,
+    account: &BotAccount,
+    cached: Option<&str>,
+) -> Result<String, twitter_api::ApiError> {
+    if let Some(user_id) = cached {
+        return Ok(user_id.to_string());
+    }
+
+    let user = state.api.get_me(account).await?;
+    if let Ok(conn) = state.db.lock() {
+        let _ = conn.execute(
+            "UPDATE bot_accounts SET twitter_user_id = ? WHERE id = ?",
+            params![user.id, account_id],
+        );
+    }
+    Ok(user.id)
+}
+
+// 外部アカウントのユーザーIDを取得（未解決ならユーザー名で検索して watched_users にキャッシュ）
+async fn resolve_watched_user_id(state: &AppState, account: &BotAccount, user: &WatchedUser) -> Result<String, twitter_api::ApiError> {
+    if let Some(user_id) = &user.twitter_user_id {
+        return Ok(user_id.clone());
+    }
+
+    let resolved = state.api.get_user_by_username(account, &user.username).await?;
+    if let Ok(conn) = state.db.lock() {
+        let _ = watched_users::set_resolved(&conn, user.id, &resolved);
+    }
+    Ok(resolved.id)
+}
+
+// 返信結果の実行ログとチェックポイントを1トランザクションで書き込む（elapsed はドライランでは None）
+#[allow(clippy::too_many_arguments)]
+fn record_reply_result(
+    state: &AppState,
+    setting: &ActiveReplySetting,
+    target: WatchTarget,
+    target_name: &str,
+    tweet: &Tweet,
+    reply: &SelectedCandidate,
+    result: &Result<Option<String>, twitter_api::ApiError>,
+    elapsed: Option<Duration>,
+) -> Result<(), String> {
+    let mut conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    let tx = after_commit::begin(&mut conn).map_err(|e| e.to_string())?;
+    let mut details = LogDetails::reply(setting.id, &tweet.id)
+        .with_content_index(reply.index)
+        .with_api_result(result);
+    if let Some(elapsed) = elapsed {
+        details = details.with_duration(elapsed);
+    }
+
+    match result {
+        // 返信ID が無い場合はドライラン
+        Ok(reply_id) => {
+            let candidate_note = reply.index
+                .map(|i| format!("（返信候補 #{}）", i + 1))
+                .unwrap_or_default();
+            let (status, action) = match reply_id {
+                Some(_) => ("success", "に返信しました"),
+                None => (dry_run::SIMULATED, "への返信をシミュレートしました"),
+            };
+            write_log(&tx, setting.reply_bot_id, "reply", status,
+                format!("{} のツイート {} {}{}", target_name, tweet.id, action, candidate_note),
+                reply_id.clone(), Some(reply.content.clone()), details).map_err(|e| e.to_string())?;
+            setting.save_last_seen(&tx, target, &tweet.id).map_err(|e| e.to_string())?;
+            if setting.dry_run {
+                if let Some(next) = reply.next_rotation_index {
+                    dry_run::set_candidate_rotation(&tx, setting.id, next).map_err(|e| e.to_string())?;
+                }
+            } else {
+                reply_filters::increment_replies_today(&tx, setting.id, target, Utc::now()).map_err(|e| e.to_string())?;
+                if let Some(next) = reply.next_rotation_index {
+                    reply_candidates::set_rotation_index(&tx, setting.id, next).map_err(|e| e.to_string())?;
+                }
+            }
+        }
+        // 一時的なエラーはチェックポイントを進めず、次回に再試行する
+        Err(e) if e.is_transient() => {
+            let reason = if e.is_rate_limited() { "レート制限" } else { "一時的なエラー" };
+            note_rate_limit(&tx, setting.reply_bot_id, e).map_err(|e| e.to_string())?;
+            write_log(&tx, setting.reply_bot_id, "error", "error",
+                format!("{} のツイート {} への返信が{}により保留されました: {}", target_name, tweet.id, reason, e),
+                None, Some(reply.content.clone()), details).map_err(|e| e.to_string())?;
+            reply_checkpoints::mark_checked(&tx, setting.id, target, Some(&e.message)).map_err(|e| e.to_string())?;
+        }
+        Err(e) => {
+            write_log(&tx, setting.reply_bot_id, "error", "error",
+                format!("{} のツイート {} への返信に失敗しました: {}", target_name, tweet.id, e),
+                None, Some(reply.content.clone()), details).map_err(|e| e.to_string())?;
+            setting.save_last_seen(&tx, target, &tweet.id).map_err(|e| e.to_string())?;
+            reply_checkpoints::mark_checked(&tx, setting.id, target, Some(&e.message)).map_err(|e| e.to_string())?;
+        }
+    }
+
+    tx.commit().map_err(|e| e.to_string())
+}
+
+// フィルタで除外したツイートを確認済みにしてログに残す
+fn record_skipped(
+    state: &AppState,
+    setting: &ActiveReplySetting,
+    target: WatchTarget,
+    target_name: &str,
+    tweet: &Tweet,
+    reason: &str,
+) -> Result<(), String> {
+    let mut conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    let tx = after_commit::begin(&mut conn).map_err(|e| e.to_string())?;
+
+    write_log(&tx, setting.reply_bot_id, "reply", "skipped",
+        format!("{} のツイート {} への返信をスキップしました（{}）", target_name, tweet.id, reason),
+        None, None, LogDetails::reply(setting.id, &tweet.id)).map_err(|e| e.to_string())?;
+    setting.save_last_seen(&tx, target, &tweet.id).map_err(|e| e.to_string())?;
+
+    tx.commit().map_err(|e| e.to_string())
+}
+
+// 1日の返信上限に達しているか
+fn daily_limit_reached(state: &AppState, setting: &ActiveReplySetting, filters: &CompiledFilters, target: WatchTarget) -> Result<Option<i64>, String> {
+    let Some(max) = filters.filters.max_replies_per_day else { return Ok(None) };
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    let count = reply_filters::replies_today(&conn, setting.id, target, Utc::now()).map_err(|e| e.to_string())?;
+    Ok((count >= max).then_some(max))
+}
+
+// 返信監視を1サイクル実行
+pub async fn run_reply_cycle(state: &AppState) -> Result<ReplyCycleSummary, String> {
+    if state.reply_monitor_running.swap(true, Ordering::SeqCst) {
+        return Err("返信監視は既に実行中です".to_string());
+    }
+
+    let result = process_replies(state).await;
+    state.reply_monitor_running.store(false, Ordering::SeqCst);
+
+    let summary = result?;
+    {
+        let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+        let summary_json = serde_json::to_string(&summary).map_err(|e| e.to_string())?;
+        crate::set_app_setting(&conn, SETTING_LAST_RUN_AT, &summary.finished_at).map_err(|e| e.to_string())?;
+        crate::set_app_setting(&conn, SETTING_LAST_RESULT, &summary_json).map_err(|e| e.to_string())?;
+    }
+
+    println!(
+        "Reply monitor finished: {} replies, {} errors ({} settings, {} targets)",
+        summary.replies_sent, summary.errors, summary.settings_processed, summary.targets_checked
+    );
+    Ok(summary)
+}
+
+async fn process_replies(state: &AppState) -> Result<ReplyCycleSummary, String> {
+    let mut summary = ReplyCycleSummary {
+        started_at: Utc::now().to_rfc3339(),
+        ..Default::default()
+    };
+
+    // Phase 1: 有効な返信設定と監視対象アカウントを収集
+    let (mut settings, mut accounts, mut watched) = {
+        let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+        let settings = load_active_settings(&conn).map_err(|e| e.to_string())?;
+
+        let mut accounts: HashMap<i64, (BotAccount, Option<String>)> = HashMap::new();
+        let mut watched: HashMap<i64, WatchedUser> = HashMap::new();
+        for target in settings.iter().flat_map(|s| s.targets.iter()) {
+            if let WatchTarget::User(id) = target {
+                if let Ok(user) = watched_users::get_watched_user(&conn, *id) {
+                    watched.insert(*id, user);
+                }
+            }
+        }
+        let account_ids: BTreeSet<i64> = settings.iter()
+            .flat_map(|s| s.targets.iter()
+                .filter_map(|t| match t { WatchTarget::Bot(id) => Some(*id), WatchTarget::User(_) => None })
+                .chain(std::iter::once(s.reply_bot_id)))
+            .collect();
+        for account_id in account_ids {
+            if let Some(account) = load_active_account(&conn, account_id) {
+                accounts.insert(account_id, account);
+            }
+        }
+        (settings, accounts, watched)
+    };
+
+    // レート制限の解除待ちのアカウントで取得・返信する監視対象・返信設定だけを見送る
+    let mut rate_limited: HashSet<i64> = {
+        let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+        let now = Utc::now();
+        accounts.keys().copied().filter(|id| retry_after(&conn, *id, now).is_some()).collect()
+    };
+
+    // Phase 2: 監視対象ごとに1回だけタイムラインを取得
+    // 自分のBotはそのBotの認証情報、外部アカウントは監視している返信Botの認証情報で取得する
+    let targets: BTreeSet<WatchTarget> = settings.iter()
+        .flat_map(|s| s.targets.iter().copied())
+        .filter(|target| match target {
+            WatchTarget::Bot(id) => accounts.contains_key(id),
+            WatchTarget::User(id) => watched.contains_key(id),
+        })
+        .collect();
+
+    let mut target_names: HashMap<WatchTarget, String> = HashMap::new();
+    let mut timelines: HashMap<WatchTarget, Vec<Tweet>> = HashMap::new();
+    for target in targets {
+        let watching: Vec<&ActiveReplySetting> = settings.iter()
+            .filter(|s| s.targets.contains(&target))
+            .collect();
+        let fetch_account_id = match target {
+            WatchTarget::Bot(id) if rate_limited.contains(&id) => continue,
+            WatchTarget::Bot(id) => id,
+            WatchTarget::User(_) => match watching.iter().find(|s| accounts.contains_key(&s.reply_bot_id) && !rate_limited.contains(&s.reply_bot_id)) {
+                Some(setting) => setting.reply_bot_id,
+                None => continue,
+            },
+        };
+
+        summary.targets_checked += 1;
+        let target_name = match target {
+            WatchTarget::Bot(id) => accounts[&id].0.account_name.clone(),
+            WatchTarget::User(id) => format!("@{}", watched[&id].username),
+        };
+        target_names.insert(target, target_name.clone());
+
+        let user_id = match target {
+            WatchTarget::Bot(id) => {
+                let (account, cached_user_id) = accounts.get_mut(&id).unwrap();
+                let user_id = resolve_twitter_user_id(state, id, account, cached_user_id.as_deref()).await;
+                if let Ok(user_id) = &user_id {
+                    *cached_user_id = Some(user_id.clone());
+                }
+                user_id
+            }
+            WatchTarget::User(id) => {
+                let user = watched.get_mut(&id).unwrap();
+                let user_id = resolve_watched_user_id(state, &accounts[&fetch_account_id].0, user).await;
+                if let Ok(user_id) = &user_id {
+                    user.twitter_user_id = Some(user_id.clone());
+                }
+                user_id
+            }
+        };
+        let user_id = match user_id {
+            Ok(user_id) => user_id,
+            Err(e) => {
+                summary.errors += 1;
+                let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+                let _ = write_log(&conn, fetch_account_id, "error", "error",
+                    format!("監視対象 {} のユーザー情報取得に失敗しました: {}", target_name, e), None, None,
+                    LogDetails::default().with_api_error(&e));
+                for setting in &watching {
+                    let _ = reply_checkpoints::mark_checked(&conn, setting.id, target, Some(&e.message));
+                }
+                continue;
+            }
+        };
+
+        // 全設定のうち最も古いチェックポイントから取得し、各設定で自分のチェックポイント以降に絞り込む
+        let since_id = if watching.iter().all(|s| s.last_seen(target).is_some()) {
+            watching.iter()
+                .filter_map(|s| s.last_seen(target))
+                .min_by(|a, b| compare_tweet_ids(a, b))
+                .map(str::to_string)
+        } else {
+            None
+        };
+
+        // 全設定がリツイート・返信を除外する場合のみAPI側で除外する
+        let skips_all = |skip: fn(&ReplyFilters) -> bool| watching.iter()
+            .all(|s| skip(&s.filters));
+        let mut exclude = Vec::new();
+        if skips_all(|f| f.skip_retweets) {
+            exclude.push("retweets");
+        }
+        if skips_all(|f| f.skip_replies) {
+            exclude.push("replies");
+        }
+
+        // 前回取得しきれなかった場合は、チェックポイントからその時点の最古のツイートまでの範囲を取得する
+        let backfill_key = format!("{}:{}:{}", SETTING_BACKFILL_UNTIL, target.kind(), target.id());
+        let until_id = match &since_id {
+            Some(_) => {
+                let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+                crate::get_app_setting(&conn, &backfill_key)
+            }
+            None => None,
+        };
+
+        let fetch_account = &accounts[&fetch_account_id].0;
+        match state.api.get_user_timeline(fetch_account, &user_id, since_id.as_deref(), until_id.as_deref(), &exclude).await {
+            // 取得しきれなかった場合は返信せず、チェックポイントを進めずに次回より古い範囲から取得し直す
+            Ok(timeline) if timeline.truncated => {
+                summary.errors += 1;
+                let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+                let message = format!("監視対象 {} の新しいツイートが {} 件を超えたため、次回以降に古いツイートから順に取得します",
+                    target_name, timeline.tweets.len());
+                let _ = write_log(&conn, fetch_account_id, "error", "error", message.clone(), None, None,
+                    LogDetails::default());
+                if let Some(oldest) = timeline.tweets.iter().min_by(|a, b| compare_tweet_ids(&a.id, &b.id)) {
+                    crate::set_app_setting(&conn, &backfill_key, &oldest.id).map_err(|e| e.to_string())?;
+                }
+                for setting in &watching {
+                    let _ = reply_checkpoints::mark_checked(&conn, setting.id, target, Some(&message));
+                }
+            }
+            Ok(timeline) => {
+                if until_id.is_some() {
+                    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+                    conn.execute("DELETE FROM app_settings WHERE key = ?", params![backfill_key])
+                        .map_err(|e| e.to_string())?;
+                }
+                timelines.insert(target, timeline.tweets);
+            }
+            Err(e) => {
+                summary.errors += 1;
+                let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+                let _ = write_log(&conn, fetch_account_id, "error", "error",
+                    format!("監視対象 {} のタイムライン取得に失敗しました: {}", target_name, e), None, None,
+                    LogDetails::default().with_api_error(&e));
+                let _ = note_rate_limit(&conn, fetch_account_id, &e);
+                if e.is_rate_limited() {
+                    rate_limited.insert(fetch_account_id);
+                }
+                for setting in &watching {
+                    let _ = reply_checkpoints::mark_checked(&conn, setting.id, target, Some(&e.message));
+                }
+            }
+        }
+    }
+
+    // Phase 3: 各返信設定を処理
+    let mut paused_accounts = HashSet::new();
+    for setting in settings.iter_mut() {
+        let Some((reply_account, _)) = accounts.get(&setting.reply_bot_id) else { continue };
+        if !setting.dry_run && rate_limited.contains(&setting.reply_bot_id) {
+            continue;
+        }
+        summary.settings_processed += 1;
+
+        // 保存済みのフィルタ・返信候補が不正な場合は意図しない返信を避けるため処理しない
+        let compiled = setting.filters.compile().and_then(|filters| {
+            let candidates = CandidateSet::compile(&setting.candidates, setting.candidate_selection, setting.candidate_rotation_index)?;
+            Ok((filters, candidates))
+        });
+        let (filters, mut candidates) = match compiled {
+            Ok(compiled) => compiled,
+            Err(e) => {
+                summary.errors += 1;
+                let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+                let _ = write_log(&conn, setting.reply_bot_id, "error", "error",
+                    format!("返信設定 {} のフィルタまたは返信候補が不正なため処理をスキップしました: {}", setting.id, e), None, None,
+                    LogDetails { reply_setting_id: Some(setting.id), ..Default::default() });
+                continue;
+            }
+        };
+
+        for target in setting.targets.clone() {
+            // 返信中にレート制限を受けた場合は残りの監視対象も見送る
+            if !setting.dry_run && rate_limited.contains(&setting.reply_bot_id) {
+                break;
+            }
+            let Some(tweets) = timelines.get(&target) else { continue };
+            let target_name = &target_names[&target];
+
+            let new_tweets = new_tweets_for(tweets, setting.checkpoints.get(&target), &setting.created_at);
+
+            // 初回で返信対象が無い場合は最新ツイートをチェックポイントとして記録
+            if new_tweets.is_empty() {
+                let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+                let latest = tweets.iter().max_by(|a, b| compare_tweet_ids(&a.id, &b.id));
+                match latest {
+                    Some(latest) if setting.last_seen(target).is_none() => {
+                        setting.save_last_seen(&conn, target, &latest.id).map_err(|e| e.to_string())?;
+                        setting.advance_checkpoint(target, &latest.id);
+                    }
+                    _ => {
+                        reply_checkpoints::mark_checked(&conn, setting.id, target, None).map_err(|e| e.to_string())?;
+                    }
+                }
+                continue;
+            }
+
+            for tweet in new_tweets {
+                let decision = match filters.evaluate(&tweet, Utc::now()) {
+                    FilterDecision::Reply => match posting_pause::check(state, setting.reply_bot_id)? {
+                        // 一時停止中・停止期間中はチェックポイントを進めず、再開後に返信する（記録はアカウントごとに1回）
+                        Some(reason) => {
+                            if paused_accounts.insert(setting.reply_bot_id) {
+                                let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+                                write_log(&conn, setting.reply_bot_id, "reply", "skipped",
+                                    format!("返信を保留しました（{}）。再開後に返信します", reason),
+                                    None, None, LogDetails { reply_setting_id: Some(setting.id), ..Default::default() })
+                                    .map_err(|e| e.to_string())?;
+                            }
+                            FilterDecision::Defer
+                        }
+                        None => match daily_limit_reached(state, setting, &filters, target)? {
+                            Some(max) => FilterDecision::Skip(format!("1日の返信上限 {} 件に到達", max)),
+                            None => FilterDecision::Reply,
+                        },
+                    },
+                    decision => decision,
+                };
+
+                // 返信候補が登録されていない場合は従来の reply_content を使う
+                let selected = match decision {
+                    // 投稿からの待機時間に満たないツイート・一時停止中のツイート以降は次回に持ち越す
+                    FilterDecision::Defer => break,
+                    FilterDecision::Skip(reason) => Err(reason),
+                    FilterDecision::Reply if candidates.is_empty() => Ok(SelectedCandidate {
+                        index: None,
+                        next_rotation_index: None,
+                        content: setting.reply_content.clone(),
+                    }),
+                    FilterDecision::Reply => candidates.select(&tweet)
+                        .ok_or_else(|| "条件に合う返信候補が無い".to_string()),
+                };
+
+                let reply = match selected {
+                    Ok(reply) => reply,
+                    Err(reason) => {
+                        record_skipped(state, setting, target, target_name, &tweet, &reason)?;
+                        summary.skipped += 1;
+                        setting.advance_checkpoint(target, &tweet.id);
+                        continue;
+                    }
+                };
+
+                let started = std::time::Instant::now();
+                let result = if setting.dry_run {
+                    Ok(None)
+                } else {
+                    state.api.post_reply_to_twitter(reply_account, &reply.content, &tweet.id).await.map(Some)
+                };
+                let elapsed = (!setting.dry_run).then(|| started.elapsed());
+                let retry_later = matches!(&result, Err(e) if e.is_transient());
+                if matches!(&result, Err(e) if e.is_rate_limited()) {
+                    rate_limited.insert(setting.reply_bot_id);
+                }
+
+                record_reply_result(state, setting, target, target_name, &tweet, &reply, &result, elapsed)?;
+                match result {
+                    Ok(reply_id) => {
+                        if reply_id.is_some() {
+                            summary.replies_sent += 1;
+                        } else {
+                            summary.simulated += 1;
+                        }
+                        if let Some(next) = reply.next_rotation_index {
+                            candidates.set_rotation_index(next);
+                        }
+                    }
+                    Err(_) => summary.errors += 1,
+                }
+
+                // レート制限・一時的なエラーの場合はチェックポイントを進めずに次回へ持ち越す
+                if retry_later {
+                    break;
+                }
+
+                setting.advance_checkpoint(target, &tweet.id);
+                if !setting.dry_run {
+                    tokio::time::sleep(REPLY_DELAY).await;
+                }
+            }
+        }
+    }
+
+    summary.finished_at = Utc::now().to_rfc3339();
+    Ok(summary)
+}
+
+// レート制限を受けた場合は、解除日時（x-rate-limit-reset）までそのアカウントでの取得・返信を見送る
+fn note_rate_limit(conn: &Connection, account_id: i64, error: &twitter_api::ApiError) -> SqliteResult<()> {
+    match error.rate_limit_reset.filter(|_| error.is_rate_limited()) {
+        Some(reset) => crate::set_app_setting(conn, &retry_after_key(account_id), &reset.to_rfc3339()),
+        None => Ok(()),
+    }
+}
+
+fn retry_after_key(account_id: i64) -> String {
+    format!("{}:{}", SETTING_RETRY_AFTER, account_id)
+}
+
+// アカウントがレート制限の解除待ちの間は解除日時を返す
+fn retry_after(conn: &Connection, account_id: i64, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    crate::get_app_setting(conn, &retry_after_key(account_id))
+        .and_then(|v| DateTime::parse_from_rfc3339(&v).ok())
+        .map(|t| t.with_timezone(&Utc))
+        .filter(|t| *t > now)
+}
+
+// レート制限の解除待ちのアカウントと解除日時
+fn rate_limited_accounts(conn: &Connection, now: DateTime<Utc>) -> SqliteResult<HashMap<i64, String>> {
+    let mut stmt = conn.prepare("SELECT key FROM app_settings WHERE key LIKE ?")?;
+    let keys = stmt.query_map(params![format!("{}:%", SETTING_RETRY_AFTER)], |row| row.get::<_, String>(0))?
+        .collect::<SqliteResult<Vec<_>>>()?;
+
+    Ok(keys.iter()
+        .filter_map(|key| key.rsplit_once(':')?.1.parse::<i64>().ok())
+        .filter_map(|id| Some((id, retry_after(conn, id, now)?.to_rfc3339())))
+        .collect())
+}
+
+// 前回実行から設定間隔が経過しているか
+fn is_due(conn: &Connection) -> bool {
+    if crate::get_app_setting(conn, SETTING_ENABLED).as_deref() != Some("true") {
+        return false;
+    }
+
+    let interval = crate::get_app_setting(conn, SETTING_INTERVAL_MINUTES)
+        .and_then(|v| v.parse::<i64>().ok())
+        .unwrap_or(DEFAULT_INTERVAL_MINUTES);
+
+    match crate::get_app_setting(conn, SETTING_LAST_RUN_AT).and_then(|v| DateTime::parse_from_rfc3339(&v).ok()) {
+        Some(last_run) => Utc::now().signed_duration_since(last_run).num_minutes() >= interval,
+        None => true,
+    }
+}
+
+// 返信監視が有効なのに監視間隔の stall_factor 倍以上実行されていなければ、最後に実行した日時を返す
+pub(crate) fn stalled_since(conn: &Connection, now: DateTime<Utc>, stall_factor: i64) -> Option<String> {
+    if crate::get_app_setting(conn, SETTING_ENABLED).as_deref() != Some("true") {
+        return None;
+    }
+
+    let interval = crate::get_app_setting(conn, SETTING_INTERVAL_MINUTES)
+        .and_then(|v| v.parse::<i64>().ok())
+        .unwrap_or(DEFAULT_INTERVAL_MINUTES);
+    let last_run_at = crate::get_app_setting(conn, SETTING_LAST_RUN_AT)?;
+    let last_run = DateTime::parse_from_rfc3339(&last_run_at).ok()?;
+
+    (now.signed_duration_since(last_run).num_minutes() >= interval * stall_factor).then_some(last_run_at)
+}
+
+// バックグラウンドの返信監視ループを起動
+pub fn spawn_reply_monitor(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(LOOP_TICK).await;
+
+            let state = app.state::<AppState>();
+            let due = match state.db.lock() {
+                Ok(conn) => is_due(&conn),
+                Err(_) => false,
+            };
+
+            if due {
+                if let Err(e) = run_reply_cycle(&state).await {
+                    eprintln!("Reply monitor error: {}", e);
+                }
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub fn get_reply_monitor_settings(state: State<AppState>) -> Result<ReplyMonitorSettings, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+
+    Ok(ReplyMonitorSettings {
+        enabled: crate::get_app_setting(&conn, SETTING_ENABLED).as_deref() == Some("true"),
+        interval_minutes: crate::get_app_setting(&conn, SETTING_INTERVAL_MINUTES)
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_INTERVAL_MINUTES),
+        is_running: state.reply_monitor_running.load(Ordering::SeqCst),
+        last_run_at: crate::get_app_setting(&conn, SETTING_LAST_RUN_AT),
+        last_result: crate::get_app_setting(&conn, SETTING_LAST_RESULT)
+            .and_then(|v| serde_json::from_str(&v).ok()),
+        retry_after: rate_limited_accounts(&conn, Utc::now()).map_err(|e| e.to_string())?,
+    })
+}
+
+#[tauri::command]
+pub fn update_reply_monitor_settings(enabled: bool, interval_minutes: i64, state: State<AppState>) -> Result<(), String> {
+    if interval_minutes < MIN_INTERVAL_MINUTES {
+        return Err(format!("監視間隔は{}分以上にしてください", MIN_INTERVAL_MINUTES));
+    }
+
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    crate::set_app_setting(&conn, SETTING_ENABLED, if enabled { "true" } else { "false" })
+        .map_err(|e| e.to_string())?;
+    crate::set_app_setting(&conn, SETTING_INTERVAL_MINUTES, &interval_minutes.to_string())
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn run_reply_monitor_now(state: State<'_, AppState>) -> Result<ReplyCycleSummary, String> {
+    run_reply_cycle(&state).await
+}
@@ -0,0 +1,383 @@
+// 自動返信の条件フィルタ
+//
+// 返信設定ごとにキーワード・正規表現・ツイート種別・投稿からの経過時間・稼働時間帯・
+// 1日あたりの返信上限を設定し、条件に合うツイートにだけ返信する。
+// 設定は reply_settings.reply_filters にJSONで保存する（NULLは既定値）。
+
+use rusqlite::{Connection, params, OptionalExtension, Result as SqliteResult};
+use serde::{Serialize, Deserialize};
+use chrono::{DateTime, FixedOffset, Timelike, Utc};
+use regex::Regex;
+use tauri::State;
+
+use crate::AppState;
+use crate::reply_checkpoints::WatchTarget;
+use crate::twitter_api::Tweet;
+
+// 稼働時間帯・1日の区切りは日本時間で判定する（GitHub Actions側のスクリプトと同じ）
+const JST_OFFSET_SECONDS: i32 = 9 * 3600;
+
+// 返信フィルタ設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReplyFilters {
+    pub include_keywords: Vec<String>,      // いずれかを含むツイートのみ返信（空なら制限なし）
+    pub exclude_keywords: Vec<String>,      // いずれかを含むツイートには返信しない
+    pub include_pattern: Option<String>,    // 一致するツイートのみ返信（正規表現）
+    pub exclude_pattern: Option<String>,    // 一致するツイートには返信しない（正規表現）
+    pub skip_retweets: bool,
+    pub skip_replies: bool,
+    pub skip_quotes: bool,
+    pub min_delay_minutes: i64,             // 投稿からこの時間が経過するまで返信を待つ
+    pub active_hours_start: Option<u32>,    // 稼働開始時刻（JST、0-23）
+    pub active_hours_end: Option<u32>,      // 稼働終了時刻（JST、0-23、この時刻は含まない）
+    pub max_replies_per_day: Option<i64>,   // 監視対象1件あたりの1日の返信上限
+}
+
+impl Default for ReplyFilters {
+    // 従来どおりリツイート・返信を除いた全ツイートに返信する
+    fn default() -> Self {
+        ReplyFilters {
+            include_keywords: Vec::new(),
+            exclude_keywords: Vec::new(),
+            include_pattern: None,
+            exclude_pattern: None,
+            skip_retweets: true,
+            skip_replies: true,
+            skip_quotes: false,
+            min_delay_minutes: 0,
+            active_hours_start: None,
+            active_hours_end: None,
+            max_replies_per_day: None,
+        }
+    }
+}
+
+// フィルタの判定結果
+#[derive(Debug, PartialEq)]
+pub(crate) enum FilterDecision {
+    Reply,
+    Skip(String),   // 返信せずに確認済みにする
+    Defer,          // 返信せず、次回以降に再判定する
+}
+
+// 正規表現をコンパイル済みのフィルタ
+#[derive(Clone)]
+pub(crate) struct CompiledFilters {
+    pub filters: ReplyFilters,
+    include_keywords: Vec<String>,
+    exclude_keywords: Vec<String>,
+    include_pattern: Option<Regex>,
+    exclude_pattern: Option<Regex>,
+}
+
+impl ReplyFilters {
+    // 保存前の検証と正規化（空のキーワード・パターンを除去）
+    pub fn normalized(mut self) -> Result<Self, String> {
+        let clean = |keywords: Vec<String>| -> Vec<String> {
+            keywords.into_iter()
+                .map(|k| k.trim().to_string())
+                .filter(|k| !k.is_empty())
+                .collect()
+        };
+        self.include_keywords = clean(self.include_keywords);
+        self.exclude_keywords = clean(self.exclude_keywords);
+        self.include_pattern = self.include_pattern.filter(|p| !p.trim().is_empty());
+        self.exclude_pattern = self.exclude_pattern.filter(|p| !p.trim().is_empty());
+
+        if self.min_delay_minutes < 0 {
+            return Err("返信までの待機時間は0分以上で指定してください".to_string());
+        }
+        match (self.active_hours_start, self.active_hours_end) {
+            (None, None) => {}
+            (Some(start), Some(end)) => {
+                if start > 23 || end > 23 {
+                    return Err("稼働時間帯は0〜23時で指定してください".to_string());
+                }
+                if start == end {
+                    return Err("稼働時間帯の開始と終了が同じです".to_string());
+                }
+            }
+            _ => return Err("稼働時間帯は開始と終了の両方を指定してください".to_string()),
+        }
+        if let Some(max) = self.max_replies_per_day {
+            if max < 1 {
+                return Err("1日の返信上限は1以上で指定してください".to_string());
+            }
+        }
+
+        self.compile()?;
+        Ok(self)
+    }
+
+    pub(crate) fn compile(&self) -> Result<CompiledFilters, String> {
+        let compile_pattern = |pattern: &Option<String>| -> Result<Option<Regex>, String> {
+            pattern.as_deref()
+                .map(|p| Regex::new(p).map_err(|e| format!("正規表現 {} が不正です: {}", p, e)))
+                .transpose()
+        };
+        let lowercase = |keywords: &[String]| keywords.iter().map(|k| k.to_lowercase()).collect();
+
+        Ok(CompiledFilters {
+            include_keywords: lowercase(&self.include_keywords),
+            exclude_keywords: lowercase(&self.exclude_keywords),
+            include_pattern: compile_pattern(&self.include_pattern)?,
+            exclude_pattern: compile_pattern(&self.exclude_pattern)?,
+            filters: self.clone(),
+        })
+    }
+
+    // 時間帯 [start, end) に含まれるか（日付をまたぐ指定にも対応）
+    pub(crate) fn is_active_hour(&self, now: DateTime<Utc>) -> bool {
+        let (Some(start), Some(end)) = (self.active_hours_start, self.active_hours_end) else {
+            return true;
+        };
+        let hour = to_jst(now).hour();
+        if start < end {
+            hour >= start && hour < end
+        } else {
+            hour >= start || hour < end
+        }
+    }
+}
+
+impl CompiledFilters {
+    // ツイート単位の判定（1日の上限はDBを参照するため呼び出し側で判定する）
+    pub(crate) fn evaluate(&self, tweet: &Tweet, now: DateTime<Utc>) -> FilterDecision {
+        let filters = &self.filters;
+
+        if filters.skip_retweets && tweet.is_referenced_as("retweeted") {
+            return FilterDecision::Skip("リツイート".to_string());
+        }
+        if filters.skip_replies && tweet.is_referenced_as("replied_to") {
+            return FilterDecision::Skip("返信ツイート".to_string());
+        }
+        if filters.skip_quotes && tweet.is_referenced_as("quoted") {
+            return FilterDecision::Skip("引用ツイート".to_string());
+        }
+
+        let text = tweet.text.to_lowercase();
+        if let Some(keyword) = self.exclude_keywords.iter().find(|k| text.contains(k.as_str())) {
+            return FilterDecision::Skip(format!("除外キーワード「{}」を含む", keyword));
+        }
+        if self.exclude_pattern.as_ref().is_some_and(|re| re.is_match(&tweet.text)) {
+            return FilterDecision::Skip("除外パターンに一致".to_string());
+        }
+        if !self.include_keywords.is_empty() && !self.include_keywords.iter().any(|k| text.contains(k.as_str())) {
+            return FilterDecision::Skip("対象キーワードを含まない".to_string());
+        }
+        if self.include_pattern.as_ref().is_some_and(|re| !re.is_match(&tweet.text)) {
+            return FilterDecision::Skip("対象パターンに一致しない".to_string());
+        }
+
+        // 稼働時間帯外のツイートは次の稼働時間帯まで持ち越す
+        if !filters.is_active_hour(now) {
+            return FilterDecision::Defer;
+        }
+
+        // 投稿日時が取れない場合は待機せずに判定を進める
+        if filters.min_delay_minutes > 0 {
+            let posted_at = tweet.created_at.as_deref()
+                .and_then(|t| DateTime::parse_from_rfc3339(t).ok());
+            if let Some(posted_at) = posted_at {
+                let elapsed = now.signed_duration_since(posted_at.with_timezone(&Utc));
+                if elapsed.num_minutes() < filters.min_delay_minutes {
+                    return FilterDecision::Defer;
+                }
+            }
+        }
+
+        FilterDecision::Reply
+    }
+}
+
+pub(crate) fn to_jst(time: DateTime<Utc>) -> DateTime<FixedOffset> {
+    time.with_timezone(&FixedOffset::east_opt(JST_OFFSET_SECONDS).unwrap())
+}
+
+fn jst_day(time: DateTime<Utc>) -> String {
+    to_jst(time).format("%Y-%m-%d").to_string()
+}
+
+// フィルタ列と1日の返信数テーブルの作成（マイグレーションから呼ばれる）
+pub fn create_filter_schema(conn: &Connection) -> SqliteResult<()> {
+    let filters_exists: i32 = conn.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info('reply_settings') WHERE name='reply_filters'",
+        [],
+        |row| row.get(0)
+    ).unwrap_or(0);
+
+    if filters_exists == 0 {
+        conn.execute("ALTER TABLE reply_settings ADD COLUMN reply_filters TEXT", [])?;
+        println!("Added reply_filters column to reply_settings table");
+    }
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS reply_daily_counts (
+            reply_setting_id INTEGER NOT NULL,
+            target_type TEXT NOT NULL,
+            target_id INTEGER NOT NULL,
+            day TEXT NOT NULL,
+            reply_count INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY (reply_setting_id, target_type, target_id, day)
+        )",
+        [],
+    )?;
+
+    Ok(())
+}
+
+// 保存済みのフィルタを取得（未設定・解析できない場合は既定値）
+pub(crate) fn load_filters(conn: &Connection, reply_setting_id: i64) -> SqliteResult<Option<ReplyFilters>> {
+    let json: Option<Option<String>> = conn.query_row(
+        "SELECT reply_filters FROM reply_settings WHERE id = ?",
+        params![reply_setting_id],
+        |row| row.get(0)
+    ).optional()?;
+
+    Ok(json.map(|json| parse_filters(json.as_deref())))
+}
+
+pub(crate) fn parse_filters(json: Option<&str>) -> ReplyFilters {
+    json.and_then(|json| serde_json::from_str(json).ok()).unwrap_or_default()
+}
+
+pub(crate) fn save_filters(conn: &Connection, reply_setting_id: i64, filters: &ReplyFilters) -> Result<(), String> {
+    let json = serde_json::to_string(filters).map_err(|e| format!("JSON変換エラー: {}", e))?;
+    conn.execute(
+        "UPDATE reply_settings SET reply_filters = ?, updated_at = ? WHERE id = ?",
+        params![json, Utc::now().to_rfc3339(), reply_setting_id],
+    ).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// 今日（JST）の返信数
+pub(crate) fn replies_today(conn: &Connection, reply_setting_id: i64, target: WatchTarget, now: DateTime<Utc>) -> SqliteResult<i64> {
+    let count: Option<i64> = conn.query_row(
+        "SELECT reply_count FROM reply_daily_counts WHERE reply_setting_id = ? AND target_type = ? AND target_id = ? AND day = ?",
+        params![reply_setting_id, target.kind(), target.id(), jst_day(now)],
+        |row| row.get(0)
+    ).optional()?;
+    Ok(count.unwrap_or(0))
+}
+
+pub(crate) fn increment_replies_today(conn: &Connection, reply_setting_id: i64, target: WatchTarget, now: DateTime<Utc>) -> SqliteResult<()> {
+    conn.execute(
+        "INSERT INTO reply_daily_counts (reply_setting_id, target_type, target_id, day, reply_count) VALUES (?, ?, ?, ?, 1)
+         ON CONFLICT(reply_setting_id, target_type, target_id, day) DO UPDATE SET reply_count = reply_count + 1",
+        params![reply_setting_id, target.kind(), target.id(), jst_day(now)],
+    )?;
+    // 前日以前の集計は不要なので削除
+    conn.execute(
+        "DELETE FROM reply_daily_counts WHERE reply_setting_id = ? AND target_type = ? AND target_id = ? AND day < ?",
+        params![reply_setting_id, target.kind(), target.id(), jst_day(now)],
+    )?;
+    Ok(())
+}
+
+// 返信設定のフィルタを取得
+#[tauri::command]
+pub fn get_reply_filters(reply_setting_id: i64, state: State<AppState>) -> Result<ReplyFilters, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+
+    load_filters(&conn, reply_setting_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("返信設定 ID {} が存在しません", reply_setting_id))
+}
+
+// 返信設定のフィルタを更新
+#[tauri::command]
+pub fn update_reply_filters(reply_setting_id: i64, filters: ReplyFilters, state: State<AppState>) -> Result<ReplyFilters, String> {
+    let filters = filters.normalized()?;
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+
+    if load_filters(&conn, reply_setting_id).map_err(|e| e.to_string())?.is_none() {
+        return Err(format!("返信設定 ID {} が存在しません", reply_setting_id));
+    }
+
+    save_filters(&conn, reply_setting_id, &filters)?;
+    Ok(filters)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::twitter_api::ReferencedTweet;
+
+    fn tweet(text: &str) -> Tweet {
+        Tweet {
+            id: "1".to_string(),
+            text: text.to_string(),
+            created_at: None,
+            author_id: None,
+            conversation_id: None,
+            referenced_tweets: Vec::new(),
+        }
+    }
+
+    // 日本時間の時刻
+    fn jst(hour: u32) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(&format!("2026-01-15T{:02}:30:00+09:00", hour)).unwrap().with_timezone(&Utc)
+    }
+
+    fn hours(start: u32, end: u32) -> ReplyFilters {
+        ReplyFilters { active_hours_start: Some(start), active_hours_end: Some(end), ..Default::default() }
+    }
+
+    #[test]
+    fn active_hours_wrap_past_midnight() {
+        let day = hours(9, 18);
+        assert!(day.is_active_hour(jst(9)));
+        assert!(day.is_active_hour(jst(17)));
+        assert!(!day.is_active_hour(jst(18)));
+        assert!(!day.is_active_hour(jst(3)));
+
+        // 22時〜翌6時
+        let night = hours(22, 6);
+        assert!(night.is_active_hour(jst(23)));
+        assert!(night.is_active_hour(jst(0)));
+        assert!(night.is_active_hour(jst(5)));
+        assert!(!night.is_active_hour(jst(6)));
+        assert!(!night.is_active_hour(jst(12)));
+    }
+
+    #[test]
+    fn evaluate_applies_keywords_patterns_and_references() {
+        let filters = ReplyFilters {
+            include_keywords: vec!["新作".to_string()],
+            exclude_keywords: vec!["PR".to_string()],
+            exclude_pattern: Some(r"^定期".to_string()),
+            ..Default::default()
+        }.compile().unwrap();
+        let now = jst(12);
+
+        assert_eq!(filters.evaluate(&tweet("新作を公開しました"), now), FilterDecision::Reply);
+        // キーワードは大文字・小文字を区別しない
+        assert_eq!(filters.evaluate(&tweet("新作のお知らせ #pr"), now), FilterDecision::Skip("除外キーワード「pr」を含む".to_string()));
+        assert_eq!(filters.evaluate(&tweet("定期: 新作まとめ"), now), FilterDecision::Skip("除外パターンに一致".to_string()));
+        assert_eq!(filters.evaluate(&tweet("おはようございます"), now), FilterDecision::Skip("対象キーワードを含まない".to_string()));
+
+        let mut retweet = tweet("新作を公開しました");
+        retweet.referenced_tweets.push(ReferencedTweet { kind: "retweeted".to_string(), id: "2".to_string() });
+        assert_eq!(filters.evaluate(&retweet, now), FilterDecision::Skip("リツイート".to_string()));
+    }
+
+    #[test]
+    fn evaluate_defers_until_min_delay_has_passed() {
+        let filters = ReplyFilters { min_delay_minutes: 10, ..Default::default() }.compile().unwrap();
+        let mut posted = tweet("こんにちは");
+        posted.created_at = Some(jst(12).to_rfc3339());
+
+        assert_eq!(filters.evaluate(&posted, jst(12) + chrono::Duration::minutes(5)), FilterDecision::Defer);
+        assert_eq!(filters.evaluate(&posted, jst(12) + chrono::Duration::minutes(10)), FilterDecision::Reply);
+        // 投稿日時が無い場合は待たない
+        assert_eq!(filters.evaluate(&tweet("こんにちは"), jst(12)), FilterDecision::Reply);
+    }
+
+    #[test]
+    fn tweets_outside_active_hours_are_deferred() {
+        let filters = hours(9, 18).compile().unwrap();
+        assert_eq!(filters.evaluate(&tweet("こんにちは"), jst(3)), FilterDecision::Defer);
+        assert_eq!(filters.evaluate(&tweet("こんにちは"), jst(10)), FilterDecision::Reply);
+    }
+}
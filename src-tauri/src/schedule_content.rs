@@ -0,0 +1,441 @@
+// スケジュール投稿の投稿内容リスト編集・リビジョン管理
+//
+// 投稿内容の挿入・削除・並べ替えを行っても current_index が
+// 「次に投稿する予定だった投稿内容」を指し続けるように調整する。
+// 変更前の状態は scheduled_tweet_revisions に保存し、閲覧・復元できるようにする。
+
+use rusqlite::{Connection, params, OptionalExtension, Result as SqliteResult};
+use serde::{Serialize, Deserialize};
+use chrono::Utc;
+use tauri::State;
+
+use crate::{AppState, ScheduledTweet};
+use crate::after_commit;
+
+// スケジュールのリビジョン（変更前のスナップショット）
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ScheduleRevision {
+    pub id: i64,
+    pub schedule_id: i64,
+    pub content_list: String,  // JSON配列
+    pub current_index: i32,
+    pub scheduled_times: String,
+    pub change_type: String,   // このスナップショットを置き換えた操作 "save", "insert", "update", "delete", "move", "restore"
+    pub created_at: String,
+}
+
+// リビジョンテーブル作成（マイグレーションから呼ばれる）
+pub fn create_revision_table(conn: &Connection) -> SqliteResult<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS scheduled_tweet_revisions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            schedule_id INTEGER NOT NULL,
+            content_list TEXT NOT NULL,
+            current_index INTEGER NOT NULL DEFAULT 0,
+            scheduled_times TEXT NOT NULL,
+            change_type TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            FOREIGN KEY (schedule_id) REFERENCES scheduled_tweets(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_scheduled_tweet_revisions_schedule
+         ON scheduled_tweet_revisions (schedule_id, id)",
+        [],
+    )?;
+    Ok(())
+}
+
+// スケジュールの投稿リスト状態
+pub(crate) struct ScheduleContent {
+    pub content_list: Vec<String>,
+    pub current_index: i32,
+    pub scheduled_times: String,
+}
+
+// スケジュールの現在の投稿リストを読み込む（content_list が無い旧形式は content を1件のリストとして扱う）
+pub(crate) fn load_schedule_content(conn: &Connection, schedule_id: i64) -> Result<ScheduleContent, String> {
+    let row: Option<(String, Option<String>, Option<i32>, String)> = conn.query_row(
+        "SELECT content, content_list, current_index, scheduled_times FROM scheduled_tweets WHERE id = ?",
+        params![schedule_id],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+    ).optional().map_err(|e| e.to_string())?;
+
+    let (content, content_list_json, current_index, scheduled_times) =
+        row.ok_or_else(|| format!("スケジュール ID {} が存在しません", schedule_id))?;
+
+    let content_list = match content_list_json {
+        Some(json) => serde_json::from_str::<Vec<String>>(&json)
+            .map_err(|e| format!("JSON解析エラー: {}", e))?,
+        None if !content.trim().is_empty() => vec![content],
+        None => Vec::new(),
+    };
+
+    Ok(ScheduleContent {
+        content_list,
+        current_index: current_index.unwrap_or(0),
+        scheduled_times,
+    })
+}
+
+// 変更前の状態をリビジョンとして保存
+pub(crate) fn record_revision(conn: &Connection, schedule_id: i64, change_type: &str) -> Result<(), String> {
+    let current = load_schedule_content(conn, schedule_id)?;
+    let content_list_json = serde_json::to_string(&current.content_list)
+        .map_err(|e| format!("JSON変換エラー: {}", e))?;
+
+    conn.execute(
+        "INSERT INTO scheduled_tweet_revisions (schedule_id, content_list, current_index, scheduled_times, change_type, created_at)
+         VALUES (?, ?, ?, ?, ?, ?)",
+        params![schedule_id, content_list_json, current.current_index, current.scheduled_times, change_type, Utc::now().to_rfc3339()],
+    ).map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+// 投稿リストを書き戻す（content 列は後方互換のため先頭の投稿内容に合わせる）
+pub(crate) fn write_schedule_content(conn: &Connection, schedule_id: i64, content: &ScheduleContent) -> Result<(), String> {
+    let content_list_json = serde_json::to_string(&content.content_list)
+        .map_err(|e| format!("JSON変換エラー: {}", e))?;
+    let first_content = content.content_list.first().cloned().unwrap_or_default();
+
+    conn.execute(
+        "UPDATE scheduled_tweets SET content = ?, content_list = ?, current_index = ?, scheduled_times = ?, updated_at = ?
+         WHERE id = ?",
+        params![
+            first_content,
+            content_list_json,
+            content.current_index,
+            content.scheduled_times,
+            Utc::now().to_rfc3339(),
+            schedule_id
+        ],
+    ).map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+// 挿入後のインデックス: 現在位置以前に挿入された場合は1つ後ろにずらす
+pub(crate) fn index_after_insert(current: usize, len_before: usize, at: usize) -> usize {
+    if len_before == 0 || at > current {
+        current
+    } else {
+        current + 1
+    }
+}
+
+// 削除後のインデックス: 現在位置より前が消えたら1つ前へ、現在位置自体が消えたら次の投稿内容を指す
+pub(crate) fn index_after_delete(current: usize, len_after: usize, at: usize) -> usize {
+    let next = if at < current { current - 1 } else { current };
+    if next >= len_after { 0 } else { next }
+}
+
+// 移動後のインデックス: 現在の投稿内容が移動後に位置する場所を指す
+pub(crate) fn index_after_move(current: usize, from: usize, to: usize) -> usize {
+    if current == from {
+        to
+    } else if from < current && to >= current {
+        current - 1
+    } else if from > current && to <= current {
+        current + 1
+    } else {
+        current
+    }
+}
+
+// リスト全体を置き換えた後のインデックス: 同じ投稿内容が残っていればそれを指し、無ければ範囲内に収める
+pub(crate) fn index_after_replace(old_list: &[String], current: usize, new_list: &[String]) -> usize {
+    if new_list.is_empty() {
+        return 0;
+    }
+    if let Some(current_content) = old_list.get(current) {
+        if let Some(pos) = new_list.iter().position(|c| c == current_content) {
+            return pos;
+        }
+    }
+    current.min(new_list.len() - 1)
+}
+
+// アカウントの有効なスケジュールの投稿リストと投稿時刻を保存（呼び出し側のトランザクション内で実行）
+// 内容が空の場合はスケジュールを無効化し、既存のスケジュールはリビジョンを残してその場で更新する
+pub(crate) fn save_content_list(
+    conn: &Connection,
+    account_id: i64,
+    scheduled_times: String,
+    content_list: Vec<String>,
+) -> Result<(), String> {
+    let now = Utc::now().to_rfc3339();
+
+    // 有効なスケジュール投稿を取得
+    let existing_id: Option<i64> = conn.query_row(
+        "SELECT id FROM scheduled_tweets WHERE account_id = ? AND is_active = 1 ORDER BY created_at DESC LIMIT 1",
+        params![account_id],
+        |row| row.get(0)
+    ).optional().map_err(|e| e.to_string())?;
+
+    if scheduled_times.is_empty() || content_list.is_empty() {
+        // 内容が空の場合は既存のスケジュール投稿を無効化
+        conn.execute(
+            "UPDATE scheduled_tweets SET is_active = 0, updated_at = ? WHERE account_id = ?",
+            params![now, account_id],
+        )
+        .map_err(|e| e.to_string())?;
+    } else if let Some(schedule_id) = existing_id {
+        // 既存のスケジュールをその場で更新（ローテーション位置は同じ投稿内容を指し続ける）
+        let current = load_schedule_content(conn, schedule_id)?;
+        record_revision(conn, schedule_id, "save")?;
+    
+        let current_index = index_after_replace(
+            &current.content_list,
+            current.current_index.max(0) as usize,
+            &content_list,
+        );
+        write_schedule_content(conn, schedule_id, &ScheduleContent {
+            content_list,
+            current_index: current_index as i32,
+            scheduled_times,
+        })?;
+    
+        // 重複した有効行が残っていれば無効化
+        conn.execute(
+            "UPDATE scheduled_tweets SET is_active = 0, updated_at = ? WHERE account_id = ? AND id != ? AND is_active = 1",
+            params![now, account_id, schedule_id],
+        )
+        .map_err(|e| e.to_string())?;
+    } else {
+        // 新しいスケジュール投稿を作成
+        let content_list_json = serde_json::to_string(&content_list)
+            .map_err(|e| format!("JSON変換エラー: {}", e))?;
+    
+        let first_content = content_list.first().unwrap_or(&String::new()).clone();
+    
+        conn.execute(
+            "INSERT INTO scheduled_tweets (account_id, content, content_list, current_index, scheduled_times, is_active, created_at, updated_at)
+             VALUES (?, ?, ?, 0, ?, 1, ?, ?)",
+            params![account_id, first_content, content_list_json, scheduled_times, now, now],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+// 編集操作を1トランザクションで実行する共通処理
+fn edit_schedule_content<F>(state: &AppState, schedule_id: i64, change_type: &str, edit: F) -> Result<ScheduledTweet, String>
+where
+    F: FnOnce(&mut ScheduleContent) -> Result<(), String>,
+{
+    let mut conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    let tx = after_commit::begin(&mut conn).map_err(|e| e.to_string())?;
+
+    let mut content = load_schedule_content(&tx, schedule_id)?;
+    record_revision(&tx, schedule_id, change_type)?;
+    edit(&mut content)?;
+    write_schedule_content(&tx, schedule_id, &content)?;
+
+    let updated = get_schedule_by_id(&tx, schedule_id)?;
+    tx.commit().map_err(|e| e.to_string())?;
+
+    Ok(updated)
+}
+
+fn get_schedule_by_id(conn: &Connection, schedule_id: i64) -> Result<ScheduledTweet, String> {
+    conn.query_row(
+        "SELECT id, account_id, content, content_list, current_index, scheduled_times, is_active, created_at, updated_at
+         FROM scheduled_tweets WHERE id = ?",
+        params![schedule_id],
+        |row| {
+            Ok(ScheduledTweet {
+                id: row.get(0)?,
+                account_id: row.get(1)?,
+                content: row.get(2)?,
+                content_list: row.get(3)?,
+                current_index: row.get(4)?,
+                scheduled_times: row.get(5)?,
+                is_active: row.get(6)?,
+                created_at: row.get(7)?,
+                updated_at: row.get(8)?,
+            })
+        }
+    ).map_err(|e| e.to_string())
+}
+
+// 投稿内容を指定位置に挿入
+#[tauri::command]
+pub fn insert_schedule_content(schedule_id: i64, index: usize, content: String, state: State<AppState>) -> Result<ScheduledTweet, String> {
+    insert_content(&state, schedule_id, index, content)
+}
+
+pub(crate) fn insert_content(state: &AppState, schedule_id: i64, index: usize, content: String) -> Result<ScheduledTweet, String> {
+    if content.trim().is_empty() {
+        return Err("投稿内容が空です".to_string());
+    }
+
+    edit_schedule_content(state, schedule_id, "insert", |schedule| {
+        let len_before = schedule.content_list.len();
+        if index > len_before {
+            return Err(format!("挿入位置 {} が範囲外です（0〜{}）", index, len_before));
+        }
+
+        let current = schedule.current_index.max(0) as usize;
+        schedule.content_list.insert(index, content);
+        schedule.current_index = index_after_insert(current, len_before, index) as i32;
+        Ok(())
+    })
+}
+
+// 指定位置の投稿内容を書き換え
+#[tauri::command]
+pub fn update_schedule_content(schedule_id: i64, index: usize, content: String, state: State<AppState>) -> Result<ScheduledTweet, String> {
+    if content.trim().is_empty() {
+        return Err("投稿内容が空です".to_string());
+    }
+
+    edit_schedule_content(&state, schedule_id, "update", |schedule| {
+        let item = schedule.content_list.get_mut(index)
+            .ok_or_else(|| format!("投稿インデックス {} が範囲外です", index))?;
+        *item = content;
+        Ok(())
+    })
+}
+
+// 指定位置の投稿内容を削除
+#[tauri::command]
+pub fn delete_schedule_content(schedule_id: i64, index: usize, state: State<AppState>) -> Result<ScheduledTweet, String> {
+    edit_schedule_content(&state, schedule_id, "delete", |schedule| {
+        if index >= schedule.content_list.len() {
+            return Err(format!("投稿インデックス {} が範囲外です", index));
+        }
+        if schedule.content_list.len() == 1 {
+            return Err("最後の投稿内容は削除できません".to_string());
+        }
+
+        let current = schedule.current_index.max(0) as usize;
+        schedule.content_list.remove(index);
+        schedule.current_index = index_after_delete(current, schedule.content_list.len(), index) as i32;
+        Ok(())
+    })
+}
+
+// 投稿内容の並べ替え
+#[tauri::command]
+pub fn move_schedule_content(schedule_id: i64, from_index: usize, to_index: usize, state: State<AppState>) -> Result<ScheduledTweet, String> {
+    edit_schedule_content(&state, schedule_id, "move", |schedule| {
+        let len = schedule.content_list.len();
+        if from_index >= len || to_index >= len {
+            return Err(format!("投稿インデックスが範囲外です（{} → {}）", from_index, to_index));
+        }
+
+        let current = schedule.current_index.max(0) as usize;
+        let item = schedule.content_list.remove(from_index);
+        schedule.content_list.insert(to_index, item);
+        schedule.current_index = index_after_move(current, from_index, to_index) as i32;
+        Ok(())
+    })
+}
+
+// リビジョン一覧（新しい順）
+#[tauri::command]
+pub fn get_schedule_revisions(schedule_id: i64, state: State<AppState>) -> Result<Vec<ScheduleRevision>, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+
+    let mut stmt = conn.prepare(
+        "SELECT id, schedule_id, content_list, current_index, scheduled_times, change_type, created_at
+         FROM scheduled_tweet_revisions WHERE schedule_id = ? ORDER BY id DESC"
+    ).map_err(|e| e.to_string())?;
+
+    let revisions = stmt.query_map(params![schedule_id], |row| {
+        Ok(ScheduleRevision {
+            id: row.get(0)?,
+            schedule_id: row.get(1)?,
+            content_list: row.get(2)?,
+            current_index: row.get(3)?,
+            scheduled_times: row.get(4)?,
+            change_type: row.get(5)?,
+            created_at: row.get(6)?,
+        })
+    })
+    .map_err(|e| e.to_string())?
+    .collect::<SqliteResult<Vec<_>>>()
+    .map_err(|e| e.to_string())?;
+
+    Ok(revisions)
+}
+
+// リビジョンを復元（復元前の状態も "restore" リビジョンとして残すので取り消し可能）
+#[tauri::command]
+pub fn restore_schedule_revision(revision_id: i64, state: State<AppState>) -> Result<ScheduledTweet, String> {
+    let (schedule_id, content_list_json, current_index, scheduled_times): (i64, String, i32, String) = {
+        let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+        conn.query_row(
+            "SELECT schedule_id, content_list, current_index, scheduled_times FROM scheduled_tweet_revisions WHERE id = ?",
+            params![revision_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        ).map_err(|e| format!("リビジョン取得エラー: {}", e))?
+    };
+
+    let content_list: Vec<String> = serde_json::from_str(&content_list_json)
+        .map_err(|e| format!("JSON解析エラー: {}", e))?;
+
+    edit_schedule_content(&state, schedule_id, "restore", move |schedule| {
+        schedule.current_index = current_index.clamp(0, content_list.len().saturating_sub(1) as i32);
+        schedule.content_list = content_list;
+        schedule.scheduled_times = scheduled_times;
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn insert_keeps_pointing_at_current_content() {
+        // 現在位置 2（5件中）
+        assert_eq!(index_after_insert(2, 5, 0), 3);
+        assert_eq!(index_after_insert(2, 5, 2), 3);
+        assert_eq!(index_after_insert(2, 5, 3), 2);
+        assert_eq!(index_after_insert(2, 5, 5), 2);
+        // 空のリストへの追加は先頭
+        assert_eq!(index_after_insert(0, 0, 0), 0);
+    }
+
+    #[test]
+    fn delete_before_at_and_after_current() {
+        // 現在位置 2（5件 → 4件）
+        assert_eq!(index_after_delete(2, 4, 0), 1);
+        assert_eq!(index_after_delete(2, 4, 2), 2);
+        assert_eq!(index_after_delete(2, 4, 4), 2);
+        // 末尾の現在位置を削除したら先頭へ戻る
+        assert_eq!(index_after_delete(4, 4, 4), 0);
+        // 最後の1件を削除
+        assert_eq!(index_after_delete(0, 0, 0), 0);
+    }
+
+    #[test]
+    fn move_across_current() {
+        // 現在位置 2（5件中）
+        assert_eq!(index_after_move(2, 2, 4), 4);
+        assert_eq!(index_after_move(2, 0, 3), 1);
+        assert_eq!(index_after_move(2, 0, 2), 1);
+        assert_eq!(index_after_move(2, 4, 0), 3);
+        assert_eq!(index_after_move(2, 4, 2), 3);
+        assert_eq!(index_after_move(2, 3, 4), 2);
+        assert_eq!(index_after_move(2, 1, 0), 2);
+    }
+
+    #[test]
+    fn replace_follows_current_content() {
+        let old = list(&["A", "B", "C"]);
+        assert_eq!(index_after_replace(&old, 1, &list(&["B", "A", "C"])), 0);
+        assert_eq!(index_after_replace(&old, 2, &list(&["A", "B"])), 1);
+        assert_eq!(index_after_replace(&old, 1, &list(&["X", "Y", "Z"])), 1);
+        assert_eq!(index_after_replace(&old, 1, &[]), 0);
+        assert_eq!(index_after_replace(&[], 0, &list(&["A"])), 0);
+    }
+}
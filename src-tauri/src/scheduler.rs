@@ -0,0 +1,373 @@
+// アプリ内スケジュール投稿
+//
+// GitHub Actions の post-tweets.js と同じく、scheduled_times の「時」が現在時刻（JST）と一致する
+// スケジュールの投稿リストから current_index の投稿内容を投稿し、成功したらインデックスを進める。
+// 同じ時間枠で二重に投稿しないよう、投稿した枠を scheduled_tweets.last_fired_slot に記録する。
+// GitHub Actions の auto-tweet.yml と同時に有効にすると二重投稿になるため既定では無効。
+
+use rusqlite::{Connection, params, Result as SqliteResult};
+use serde::{Serialize, Deserialize};
+use chrono::{DateTime, FixedOffset, TimeZone, Timelike, Utc};
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+use crate::{AppState, BotAccount};
+use crate::after_commit;
+use crate::app_events::{self, AppEvent, ScheduleFired};
+use crate::content_approval;
+use crate::dry_run;
+use crate::log_details::LogDetails;
+use crate::posting_pause;
+use crate::reply_engine::{load_active_account, write_log};
+use crate::reply_filters::to_jst;
+use crate::schedule_content;
+use crate::twitter_api;
+
+const SETTING_ENABLED: &str = "scheduler_enabled";
+const SETTING_LAST_RUN_AT: &str = "scheduler_last_run_at";
+const SETTING_LAST_RESULT: &str = "scheduler_last_result";
+
+// スケジュール確認間隔と、投稿間の待機時間（レート制限対策）
+const LOOP_TICK: Duration = Duration::from_secs(60);
+const POST_DELAY: Duration = Duration::from_secs(1);
+
+// 1回のスケジュール確認の結果
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct SchedulerRunSummary {
+    pub slot: String,
+    pub schedules_due: i32,
+    pub posted: i32,
+    pub simulated: i32,
+    pub errors: i32,
+    pub started_at: String,
+    pub finished_at: String,
+}
+
+// スケジュール投稿の設定と状態
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SchedulerSettings {
+    pub enabled: bool,
+    pub is_running: bool,
+    pub last_run_at: Option<String>,
+    pub last_result: Option<SchedulerRunSummary>,
+}
+
+// 現在の時間枠で投稿するスケジュール
+struct DueSchedule {
+    schedule_id: i64,
+    account: BotAccount,
+    content: String,
+    index: usize,
+    list_len: usize,
+    dry_run: bool,
+}
+
+// 投稿済みの時間枠を記録する列を追加（マイグレーションから呼ばれる）
+pub fn create_scheduler_columns(conn: &Connection) -> SqliteResult<()> {
+    let last_fired_slot_exists: i32 = conn.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info('scheduled_tweets') WHERE name='last_fired_slot'",
+        [],
+        |row| row.get(0)
+    ).unwrap_or(0);
+
+    if last_fired_slot_exists == 0 {
+        conn.execute("ALTER TABLE scheduled_tweets ADD COLUMN last_fired_slot TEXT", [])?;
+        println!("Added last_fired_slot column to scheduled_tweets table");
+    }
+
+    Ok(())
+}
+
+// 時間枠のキー（JSTの日付と時、例: "2024-01-01T09"）
+fn slot_key(now: DateTime<Utc>) -> String {
+    to_jst(now).format("%Y-%m-%dT%H").to_string()
+}
+
+// scheduled_times（"09:00,12:00"）に現在の「時」が含まれるか
+fn matches_hour(scheduled_times: &str, now: DateTime<Utc>) -> bool {
+    let hour = to_jst(now).hour();
+    scheduled_times.split(',')
+        .filter_map(|time| time.trim().split(':').next()?.parse::<u32>().ok())
+        .any(|h| h == hour)
+}
+
+// 今後の投稿予定日時（JST）を早い順に count 件
+pub(crate) fn upcoming_fire_times(scheduled_times: &str, now: DateTime<Utc>, count: usize) -> Vec<DateTime<FixedOffset>> {
+    let mut times: Vec<(u32, u32)> = scheduled_times.split(',')
+        .filter_map(|time| {
+            let mut parts = time.trim().split(':');
+            let hour = parts.next()?.parse::<u32>().ok().filter(|h| *h < 24)?;
+            let minute = parts.next().and_then(|m| m.parse::<u32>().ok()).filter(|m| *m < 60).unwrap_or(0);
+            Some((hour, minute))
+        })
+        .collect();
+    times.sort_unstable();
+    times.dedup();
+    if times.is_empty() || count == 0 {
+        return Vec::new();
+    }
+
+    let now_jst = to_jst(now);
+    let mut fire_times = Vec::with_capacity(count);
+    let mut day = now_jst.date_naive();
+    while fire_times.len() < count {
+        for (hour, minute) in &times {
+            let Some(time) = day.and_hms_opt(*hour, *minute, 0)
+                .and_then(|t| now_jst.timezone().from_local_datetime(&t).single()) else { continue };
+            if time > now_jst && fire_times.len() < count {
+                fire_times.push(time);
+            }
+        }
+        day = day.succ_opt().unwrap_or(day);
+    }
+    fire_times
+}
+
+// 現在の時間枠で未投稿のスケジュールを集め、時間枠を投稿済みにする
+// （投稿に失敗しても同じ枠では再試行しない。GitHub Actions の毎時実行と同じ扱い）
+fn claim_due_schedules(conn: &Connection, now: DateTime<Utc>) -> Result<Vec<DueSchedule>, String> {
+    let slot = slot_key(now);
+
+    let rows: Vec<(i64, i64, String, Option<String>)> = conn.prepare(
+        "SELECT id, account_id, scheduled_times, last_fired_slot FROM scheduled_tweets WHERE is_active = 1 ORDER BY account_id, id"
+    )
+    .and_then(|mut stmt| {
+        stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))?
+            .collect::<SqliteResult<Vec<_>>>()
+    })
+    .map_err(|e| e.to_string())?;
+
+    let mut due = Vec::new();
+    for (schedule_id, account_id, scheduled_times, last_fired_slot) in rows {
+        if last_fired_slot.as_deref() == Some(slot.as_str()) || !matches_hour(&scheduled_times, now) {
+            continue;
+        }
+        let Some((account, _)) = load_active_account(conn, account_id) else { continue };
+
+        let content = schedule_content::load_schedule_content(conn, schedule_id)?;
+        if content.content_list.is_empty() {
+            continue;
+        }
+
+        conn.execute(
+            "UPDATE scheduled_tweets SET last_fired_slot = ? WHERE id = ?",
+            params![slot, schedule_id],
+        ).map_err(|e| e.to_string())?;
+
+        // 一時停止中・停止期間中はこの枠を投稿せずに飛ばす
+        if let Some(reason) = posting_pause::pause_reason(conn, account_id, now).map_err(|e| e.to_string())? {
+            write_log(conn, account_id, "tweet", "skipped",
+                format!("スケジュール投稿をスキップしました（{}）", reason),
+                None, None, LogDetails { schedule_id: Some(schedule_id), ..Default::default() })
+                .map_err(|e| e.to_string())?;
+            continue;
+        }
+
+        // ドライランではシミュレーション上の位置から選ぶ
+        let dry_run = dry_run::is_dry_run(conn, account_id);
+        let simulated_index = if dry_run {
+            dry_run::schedule_index(conn, schedule_id).map_err(|e| e.to_string())?
+        } else {
+            None
+        };
+        let current_index = simulated_index.unwrap_or(content.current_index.max(0) as usize);
+
+        // 承認が必要なアカウントでは承認済みの内容だけを投稿する
+        let index = content_approval::next_postable_index(
+            conn, account_id, schedule_id, &content.content_list, current_index,
+        ).map_err(|e| e.to_string())?;
+        let Some(index) = index else {
+            write_log(conn, account_id, "tweet", "skipped",
+                "承認済みの投稿内容が無いため、スケジュール投稿をスキップしました".to_string(),
+                None, None, LogDetails { schedule_id: Some(schedule_id), ..Default::default() })
+                .map_err(|e| e.to_string())?;
+            continue;
+        };
+        due.push(DueSchedule {
+            schedule_id,
+            account,
+            content: content.content_list[index].clone(),
+            index,
+            list_len: content.content_list.len(),
+            dry_run,
+        });
+    }
+
+    Ok(due)
+}
+
+// 投稿リストのインデックスを次に進める（末尾に達したら先頭へ）
+fn advance_index(conn: &Connection, schedule: &DueSchedule) -> SqliteResult<()> {
+    conn.execute(
+        "UPDATE scheduled_tweets SET current_index = ?, updated_at = ? WHERE id = ?",
+        params![((schedule.index + 1) % schedule.list_len) as i32, Utc::now().to_rfc3339(), schedule.schedule_id],
+    )?;
+    Ok(())
+}
+
+// 投稿結果の実行ログとインデックス更新を1トランザクションで書き込む（elapsed はドライランでは None）
+fn record_post_result(
+    state: &AppState,
+    schedule: &DueSchedule,
+    result: &Result<Option<String>, twitter_api::ApiError>,
+    elapsed: Option<Duration>,
+) -> Result<(), String> {
+    let mut conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    let tx = after_commit::begin(&mut conn).map_err(|e| e.to_string())?;
+    let account_id = schedule.account.id.unwrap_or_default();
+    let position = format!("[{}/{}]", schedule.index + 1, schedule.list_len);
+    let mut details = LogDetails::schedule(schedule.schedule_id, schedule.index).with_api_result(result);
+    if let Some(elapsed) = elapsed {
+        details = details.with_duration(elapsed);
+    }
+
+    match result {
+        Ok(None) => {
+            write_log(&tx, account_id, "tweet", dry_run::SIMULATED,
+                format!("スケジュール投稿をシミュレートしました {}", position),
+                None, Some(schedule.content.clone()), details).map_err(|e| e.to_string())?;
+            // 本番の位置は進めず、シミュレーション上の位置だけを進める
+            dry_run::set_schedule_index(&tx, schedule.schedule_id, (schedule.index + 1) % schedule.list_len)
+                .map_err(|e| e.to_string())?;
+        }
+        Ok(Some(tweet_id)) => {
+            write_log(&tx, account_id, "tweet", "success",
+                format!("スケジュール投稿が成功しました {}", position),
+                Some(tweet_id.clone()), Some(schedule.content.clone()), details).map_err(|e| e.to_string())?;
+            advance_index(&tx, schedule).map_err(|e| e.to_string())?;
+        }
+        Err(e) => {
+            write_log(&tx, account_id, "error", "error",
+                format!("スケジュール投稿に失敗しました {}: {}", position, e),
+                None, Some(schedule.content.clone()), details).map_err(|e| e.to_string())?;
+            // 重複投稿の場合は同じ内容で失敗し続けないようインデックスを進める
+            // （凍結・権限不足などの 403 では進めず、内容を飛ばさない）
+            if e.is_duplicate_content() {
+                advance_index(&tx, schedule).map_err(|e| e.to_string())?;
+            }
+        }
+    }
+
+    tx.commit().map_err(|e| e.to_string())
+}
+
+// 現在の時間枠のスケジュール投稿を実行
+pub async fn run_scheduler(state: &AppState) -> Result<SchedulerRunSummary, String> {
+    if state.scheduler_running.swap(true, Ordering::SeqCst) {
+        return Err("スケジュール投稿は既に実行中です".to_string());
+    }
+
+    let result = process_schedules(state, Utc::now()).await;
+    state.scheduler_running.store(false, Ordering::SeqCst);
+
+    let summary = result?;
+    {
+        let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+        let summary_json = serde_json::to_string(&summary).map_err(|e| e.to_string())?;
+        crate::set_app_setting(&conn, SETTING_LAST_RUN_AT, &summary.finished_at).map_err(|e| e.to_string())?;
+        crate::set_app_setting(&conn, SETTING_LAST_RESULT, &summary_json).map_err(|e| e.to_string())?;
+    }
+
+    if summary.schedules_due > 0 {
+        println!(
+            "Scheduler finished slot {}: {} posted, {} simulated, {} errors",
+            summary.slot, summary.posted, summary.simulated, summary.errors
+        );
+    }
+    Ok(summary)
+}
+
+pub(crate) async fn process_schedules(state: &AppState, now: DateTime<Utc>) -> Result<SchedulerRunSummary, String> {
+    let mut summary = SchedulerRunSummary {
+        slot: slot_key(now),
+        started_at: Utc::now().to_rfc3339(),
+        ..Default::default()
+    };
+
+    let due = {
+        let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+        claim_due_schedules(&conn, now)?
+    };
+    summary.schedules_due = due.len() as i32;
+
+    for schedule in &due {
+        app_events::publish(AppEvent::ScheduleFired(ScheduleFired {
+            schedule_id: schedule.schedule_id,
+            account_id: schedule.account.id.unwrap_or_default(),
+            content_index: schedule.index,
+            content: schedule.content.clone(),
+            dry_run: schedule.dry_run,
+            slot: summary.slot.clone(),
+        }));
+
+        let started = std::time::Instant::now();
+        let result = if schedule.dry_run {
+            Ok(None)
+        } else {
+            state.api.post_to_twitter(&schedule.account, &schedule.content).await.map(Some)
+        };
+        let elapsed = (!schedule.dry_run).then(|| started.elapsed());
+
+        record_post_result(state, schedule, &result, elapsed)?;
+        match result {
+            Ok(None) => summary.simulated += 1,
+            Ok(Some(_)) => summary.posted += 1,
+            Err(_) => summary.errors += 1,
+        }
+
+        if !schedule.dry_run {
+            tokio::time::sleep(POST_DELAY).await;
+        }
+    }
+
+    summary.finished_at = Utc::now().to_rfc3339();
+    Ok(summary)
+}
+
+// バックグラウンドのスケジュール投稿ループを起動
+pub fn spawn_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(LOOP_TICK).await;
+
+            let state = app.state::<AppState>();
+            let enabled = match state.db.lock() {
+                Ok(conn) => crate::get_app_setting(&conn, SETTING_ENABLED).as_deref() == Some("true"),
+                Err(_) => false,
+            };
+
+            if enabled {
+                if let Err(e) = run_scheduler(&state).await {
+                    eprintln!("Scheduler error: {}", e);
+                }
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub fn get_scheduler_settings(state: State<AppState>) -> Result<SchedulerSettings, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+
+    Ok(SchedulerSettings {
+        enabled: crate::get_app_setting(&conn, SETTING_ENABLED).as_deref() == Some("true"),
+        is_running: state.scheduler_running.load(Ordering::SeqCst),
+        last_run_at: crate::get_app_setting(&conn, SETTING_LAST_RUN_AT),
+        last_result: crate::get_app_setting(&conn, SETTING_LAST_RESULT)
+            .and_then(|v| serde_json::from_str(&v).ok()),
+    })
+}
+
+#[tauri::command]
+pub fn update_scheduler_settings(enabled: bool, state: State<AppState>) -> Result<(), String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    crate::set_app_setting(&conn, SETTING_ENABLED, if enabled { "true" } else { "false" })
+        .map_err(|e| e.to_string())
+}
+
+// 現在の時間枠のスケジュールを今すぐ確認（投稿済みの枠は再投稿しない）
+#[tauri::command]
+pub async fn run_scheduler_now(state: State<'_, AppState>) -> Result<SchedulerRunSummary, String> {
+    run_scheduler(&state).await
+}
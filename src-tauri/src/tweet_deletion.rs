@@ -0,0 +1,189 @@
+// 投稿済みツイートの削除と取り消し
+//
+// 実行ログに tweet_id が記録された投稿・返信を DELETE /2/tweets/:id で削除し、
+// 削除したことを log_type "delete" の実行ログとして残す（同じツイートの二重削除はこのログで防ぐ）。
+// 取り消し可能時間（undo_window_seconds、0で無効）内の投稿は「取り消し」として一覧・削除できる。
+// 取り消してもスケジュール投稿の投稿リストの位置は戻さない。
+
+use rusqlite::{Connection, params, OptionalExtension, Result as SqliteResult};
+use serde::{Serialize, Deserialize};
+use chrono::{DateTime, Duration, Utc};
+use tauri::State;
+
+use crate::AppState;
+use crate::log_details::LogDetails;
+use crate::reply_engine::write_log;
+
+const SETTING_UNDO_WINDOW_SECONDS: &str = "undo_window_seconds";
+const MAX_UNDO_WINDOW_SECONDS: i64 = 600;
+
+// 取り消し可能な投稿
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UndoablePost {
+    pub log_id: i64,
+    pub account_id: i64,
+    pub account_name: String,
+    pub tweet_id: String,
+    pub tweet_content: Option<String>,
+    pub posted_at: String,
+    pub expires_at: String,
+}
+
+// 削除対象の投稿ログ
+struct LoggedPost {
+    account_id: i64,
+    log_type: String,
+    tweet_id: String,
+    created_at: String,
+}
+
+fn undo_window_seconds(conn: &Connection) -> i64 {
+    crate::get_app_setting(conn, SETTING_UNDO_WINDOW_SECONDS)
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0)
+}
+
+// 削除できる投稿ログを取得（成功した投稿・返信で、まだ削除していないもの）
+fn load_logged_post(conn: &Connection, log_id: i64) -> Result<LoggedPost, String> {
+    let post: Option<(i64, String, Option<String>, String, String)> = conn.query_row(
+        "SELECT account_id, log_type, tweet_id, status, created_at FROM execution_logs WHERE id = ?",
+        params![log_id],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?))
+    ).optional().map_err(|e| e.to_string())?;
+
+    let (account_id, log_type, tweet_id, status, created_at) =
+        post.ok_or_else(|| format!("実行ログ ID {} が存在しません", log_id))?;
+
+    let tweet_id = match tweet_id {
+        Some(tweet_id) if status == "success" && (log_type == "tweet" || log_type == "reply") => tweet_id,
+        _ => return Err("このログには削除できるツイートがありません".to_string()),
+    };
+
+    let already_deleted: i32 = conn.query_row(
+        "SELECT COUNT(*) FROM execution_logs WHERE log_type = 'delete' AND status = 'success' AND tweet_id = ?",
+        params![tweet_id],
+        |row| row.get(0)
+    ).map_err(|e| e.to_string())?;
+    if already_deleted > 0 {
+        return Err(format!("ツイート {} は既に削除されています", tweet_id));
+    }
+
+    Ok(LoggedPost { account_id, log_type, tweet_id, created_at })
+}
+
+// 実行ログの投稿を削除し、結果を実行ログに記録
+pub(crate) async fn delete_logged_tweet(state: &AppState, log_id: i64, undo: bool) -> Result<(), String> {
+    let (post, account) = {
+        let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+        let post = load_logged_post(&conn, log_id)?;
+
+        if undo {
+            let window = undo_window_seconds(&conn);
+            let posted_at = DateTime::parse_from_rfc3339(&post.created_at).map_err(|e| e.to_string())?;
+            if window <= 0 || Utc::now().signed_duration_since(posted_at) > Duration::seconds(window) {
+                return Err("取り消し可能な時間を過ぎています".to_string());
+            }
+        }
+
+        let account = crate::get_bot_account(&conn, post.account_id)
+            .map_err(|_| format!("アカウント ID {} が存在しません", post.account_id))?;
+        (post, account)
+    };
+
+    let kind = if post.log_type == "reply" { "返信" } else { "投稿" };
+    let action = if undo { "取り消し" } else { "削除" };
+    let started = std::time::Instant::now();
+    let result = state.api.delete_tweet(&account, &post.tweet_id).await;
+    let details = LogDetails::target(&post.tweet_id)
+        .with_duration(started.elapsed())
+        .with_api_result(&result);
+
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    match result {
+        Ok(()) => {
+            write_log(&conn, post.account_id, "delete", "success",
+                format!("{} {} を{}しました", kind, post.tweet_id, action),
+                Some(post.tweet_id), None, details).map_err(|e| e.to_string())?;
+            Ok(())
+        }
+        Err(e) => {
+            write_log(&conn, post.account_id, "error", "error",
+                format!("{} {} の{}に失敗しました: {}", kind, post.tweet_id, action, e),
+                Some(post.tweet_id), None, details).map_err(|e| e.to_string())?;
+            Err(e.message)
+        }
+    }
+}
+
+// 実行ログに記録された投稿・返信を削除
+#[tauri::command]
+pub async fn delete_tweet(log_id: i64, state: State<'_, AppState>) -> Result<(), String> {
+    delete_logged_tweet(&state, log_id, false).await
+}
+
+// 取り消し可能時間内の投稿を取り消す
+#[tauri::command]
+pub async fn undo_post(log_id: i64, state: State<'_, AppState>) -> Result<(), String> {
+    delete_logged_tweet(&state, log_id, true).await
+}
+
+#[tauri::command]
+pub fn get_undo_window(state: State<AppState>) -> Result<i64, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    Ok(undo_window_seconds(&conn))
+}
+
+#[tauri::command]
+pub fn update_undo_window(seconds: i64, state: State<AppState>) -> Result<(), String> {
+    if !(0..=MAX_UNDO_WINDOW_SECONDS).contains(&seconds) {
+        return Err(format!("取り消し可能時間は0〜{}秒で指定してください", MAX_UNDO_WINDOW_SECONDS));
+    }
+
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    crate::set_app_setting(&conn, SETTING_UNDO_WINDOW_SECONDS, &seconds.to_string())
+        .map_err(|e| e.to_string())
+}
+
+// 取り消し可能時間内の投稿一覧（新しい順）
+#[tauri::command]
+pub fn get_undoable_posts(state: State<AppState>) -> Result<Vec<UndoablePost>, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    let window = undo_window_seconds(&conn);
+    if window <= 0 {
+        return Ok(Vec::new());
+    }
+
+    let since = (Utc::now() - Duration::seconds(window)).to_rfc3339();
+    let mut stmt = conn.prepare(
+        "SELECT el.id, el.account_id, ba.account_name, el.tweet_id, el.tweet_content, el.created_at
+         FROM execution_logs el
+         INNER JOIN bot_accounts ba ON el.account_id = ba.id
+         WHERE el.log_type = 'tweet' AND el.status = 'success' AND el.tweet_id IS NOT NULL AND el.created_at >= ?
+           AND NOT EXISTS (
+               SELECT 1 FROM execution_logs d
+               WHERE d.log_type = 'delete' AND d.status = 'success' AND d.tweet_id = el.tweet_id
+           )
+         ORDER BY el.created_at DESC"
+    ).map_err(|e| e.to_string())?;
+
+    let posts = stmt.query_map(params![since], |row| {
+        let posted_at: String = row.get(5)?;
+        let expires_at = DateTime::parse_from_rfc3339(&posted_at)
+            .map(|t| (t + Duration::seconds(window)).to_rfc3339())
+            .unwrap_or_default();
+        Ok(UndoablePost {
+            log_id: row.get(0)?,
+            account_id: row.get(1)?,
+            account_name: row.get(2)?,
+            tweet_id: row.get(3)?,
+            tweet_content: row.get(4)?,
+            posted_at,
+            expires_at,
+        })
+    })
+    .map_err(|e| e.to_string())?
+    .collect::<SqliteResult<Vec<_>>>()
+    .map_err(|e| e.to_string())?;
+
+    Ok(posts)
+}
@@ -0,0 +1,479 @@
+// 投稿済みツイートの反応（public_metrics）の収集と成績集計
+//
+// 実行ログに記録された投稿・返信（成功・未削除・直近の収集対象期間内）の tweet_id について、
+// GET /2/tweets?ids= で public_metrics をアカウントごとに最大100件ずつ取得し、
+// 取得時点のスナップショットとして tweet_metric_snapshots に蓄積する（時系列で伸びを追える）。
+// 成績の集計には各ツイートの最新スナップショットを使い、アカウント別と、
+// 実行ログの schedule_id / content_index によるスケジュールの投稿内容別に返す。
+
+use rusqlite::{Connection, params, Result as SqliteResult};
+use serde::{Serialize, Deserialize};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::BTreeMap;
+use tauri::{AppHandle, Manager, State};
+
+use crate::{AppState, BotAccount};
+use crate::reply_engine;
+use crate::twitter_api::PublicMetrics;
+
+const SETTING_ENABLED: &str = "metrics_collection_enabled";
+const SETTING_INTERVAL_MINUTES: &str = "metrics_interval_minutes";
+const SETTING_LOOKBACK_DAYS: &str = "metrics_lookback_days";
+const SETTING_LAST_COLLECTED_AT: &str = "metrics_last_collected_at";
+
+const DEFAULT_INTERVAL_MINUTES: i64 = 60;
+const MIN_INTERVAL_MINUTES: i64 = 15;
+const DEFAULT_LOOKBACK_DAYS: i64 = 7;
+const MAX_LOOKBACK_DAYS: i64 = 30;
+
+// GET /2/tweets の ids の上限
+const LOOKUP_BATCH_SIZE: usize = 100;
+const LOOP_TICK: std::time::Duration = std::time::Duration::from_secs(5 * 60);
+
+// 収集設定（UI用）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricsSettings {
+    pub enabled: bool,
+    pub interval_minutes: i64,                 // 同じツイートを再取得する間隔
+    pub lookback_days: i64,                    // 投稿から何日間収集を続けるか
+    #[serde(default)]
+    pub last_collected_at: Option<String>,
+}
+
+// 1回の収集結果
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct MetricsCollectionSummary {
+    pub accounts_checked: i32,
+    pub tweets_requested: i32,
+    pub snapshots_saved: i32,
+    pub errors: i32,
+    pub started_at: String,
+    pub finished_at: String,
+}
+
+// ある時点の指標
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricSnapshot {
+    pub tweet_id: String,
+    #[serde(flatten)]
+    pub metrics: PublicMetrics,
+    pub collected_at: String,
+}
+
+// 投稿の成績の合計（各ツイートの最新スナップショットを合算）
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct PerformanceTotals {
+    pub posts: i64,
+    pub like_count: i64,
+    pub retweet_count: i64,
+    pub reply_count: i64,
+    pub quote_count: i64,
+    pub impression_count: Option<i64>,   // インプレッションを取得できた投稿のみの合計
+    pub avg_engagements: f64,            // 1投稿あたりのいいね・リポスト・返信・引用の合計
+    pub engagement_rate: Option<f64>,    // インプレッションに対するエンゲージメントの割合
+    #[serde(skip)]
+    engagements_with_impressions: i64,
+}
+
+// アカウント別の成績
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountPerformance {
+    pub account_id: i64,
+    pub account_name: Option<String>,
+    #[serde(flatten)]
+    pub totals: PerformanceTotals,
+}
+
+// スケジュールの投稿内容（投稿リストの位置）別の成績
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentPerformance {
+    pub schedule_id: i64,
+    pub content_index: i64,
+    pub content: Option<String>,         // 最後に投稿した内容
+    #[serde(flatten)]
+    pub totals: PerformanceTotals,
+}
+
+// 指標スナップショットのテーブル（マイグレーションから呼ばれる）
+pub fn create_metrics_table(conn: &Connection) -> SqliteResult<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS tweet_metric_snapshots (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            account_id INTEGER NOT NULL,
+            tweet_id TEXT NOT NULL,
+            like_count INTEGER NOT NULL DEFAULT 0,
+            retweet_count INTEGER NOT NULL DEFAULT 0,
+            reply_count INTEGER NOT NULL DEFAULT 0,
+            quote_count INTEGER NOT NULL DEFAULT 0,
+            bookmark_count INTEGER,
+            impression_count INTEGER,
+            collected_at TEXT NOT NULL,
+            FOREIGN KEY (account_id) REFERENCES bot_accounts(id) ON DELETE CASCADE
+        );
+        CREATE INDEX IF NOT EXISTS idx_tweet_metric_snapshots_tweet ON tweet_metric_snapshots (tweet_id, collected_at);
+        CREATE INDEX IF NOT EXISTS idx_execution_logs_tweet_id ON execution_logs (tweet_id);"
+    )
+}
+
+fn int_setting(conn: &Connection, key: &str, default: i64) -> i64 {
+    crate::get_app_setting(conn, key)
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+pub(crate) fn load_settings(conn: &Connection) -> MetricsSettings {
+    MetricsSettings {
+        enabled: crate::get_app_setting(conn, SETTING_ENABLED).map(|v| v == "true").unwrap_or(true),
+        interval_minutes: int_setting(conn, SETTING_INTERVAL_MINUTES, DEFAULT_INTERVAL_MINUTES),
+        lookback_days: int_setting(conn, SETTING_LOOKBACK_DAYS, DEFAULT_LOOKBACK_DAYS),
+        last_collected_at: crate::get_app_setting(conn, SETTING_LAST_COLLECTED_AT),
+    }
+}
+
+// 収集対象のツイートをアカウントごとにまとめる
+// due_only では前回の取得から interval_minutes 経過したツイートのみ
+fn load_targets(conn: &Connection, settings: &MetricsSettings, due_only: bool, now: DateTime<Utc>) -> Result<Vec<(i64, Vec<String>)>, String> {
+    let posted_since = (now - Duration::days(settings.lookback_days)).to_rfc3339();
+    let collected_before = if due_only {
+        (now - Duration::minutes(settings.interval_minutes)).to_rfc3339()
+    } else {
+        now.to_rfc3339()
+    };
+
+    let mut stmt = conn.prepare(
+        "SELECT DISTINCT el.account_id, el.tweet_id
+         FROM execution_logs el
+         WHERE el.log_type IN ('tweet', 'reply') AND el.status = 'success' AND el.tweet_id IS NOT NULL
+           AND el.created_at >= ?1
+           AND NOT EXISTS (
+               SELECT 1 FROM execution_logs d
+               WHERE d.log_type = 'delete' AND d.status = 'success' AND d.tweet_id = el.tweet_id
+           )
+           AND NOT EXISTS (
+               SELECT 1 FROM tweet_metric_snapshots s
+               WHERE s.tweet_id = el.tweet_id AND s.collected_at > ?2
+           )
+         ORDER BY el.account_id, el.tweet_id"
+    ).map_err(|e| e.to_string())?;
+
+    let rows = stmt.query_map(params![posted_since, collected_before], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))
+        .map_err(|e| e.to_string())?
+        .collect::<SqliteResult<Vec<_>>>()
+        .map_err(|e| e.to_string())?;
+
+    let mut targets: BTreeMap<i64, Vec<String>> = BTreeMap::new();
+    for (account_id, tweet_id) in rows {
+        targets.entry(account_id).or_default().push(tweet_id);
+    }
+    Ok(targets.into_iter().collect())
+}
+
+fn save_snapshot(conn: &Connection, account_id: i64, tweet_id: &str, metrics: &PublicMetrics, collected_at: &str) -> SqliteResult<()> {
+    conn.execute(
+        "INSERT INTO tweet_metric_snapshots
+            (account_id, tweet_id, like_count, retweet_count, reply_count, quote_count, bookmark_count, impression_count, collected_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        params![
+            account_id, tweet_id, metrics.like_count, metrics.retweet_count, metrics.reply_count,
+            metrics.quote_count, metrics.bookmark_count, metrics.impression_count, collected_at
+        ],
+    )?;
+    Ok(())
+}
+
+// 投稿済みツイートの指標を取得して保存
+pub(crate) async fn collect_metrics(state: &AppState, due_only: bool) -> Result<MetricsCollectionSummary, String> {
+    let mut summary = MetricsCollectionSummary {
+        started_at: Utc::now().to_rfc3339(),
+        ..Default::default()
+    };
+
+    let targets: Vec<(BotAccount, i64, Vec<String>)> = {
+        let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+        let settings = load_settings(&conn);
+        load_targets(&conn, &settings, due_only, Utc::now())?
+            .into_iter()
+            .filter_map(|(account_id, tweet_ids)| {
+                reply_engine::load_active_account(&conn, account_id).map(|(account, _)| (account, account_id, tweet_ids))
+            })
+            .collect()
+    };
+
+    for (account, account_id, tweet_ids) in targets {
+        summary.accounts_checked += 1;
+
+        for batch in tweet_ids.chunks(LOOKUP_BATCH_SIZE) {
+            summary.tweets_requested += batch.len() as i32;
+
+            let tweets = match state.api.get_tweet_metrics(&account, batch).await {
+                Ok(tweets) => tweets,
+                Err(e) => {
+                    summary.errors += 1;
+                    eprintln!("Metrics collection error ({}): {}", account.account_name, e);
+                    // レート制限中は次回に回す
+                    if e.is_rate_limited() {
+                        break;
+                    }
+                    continue;
+                }
+            };
+
+            let collected_at = Utc::now().to_rfc3339();
+            let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+            for tweet in &tweets {
+                save_snapshot(&conn, account_id, &tweet.id, &tweet.public_metrics, &collected_at)
+                    .map_err(|e| e.to_string())?;
+                summary.snapshots_saved += 1;
+            }
+        }
+    }
+
+    summary.finished_at = Utc::now().to_rfc3339();
+    {
+        let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+        crate::set_app_setting(&conn, SETTING_LAST_COLLECTED_AT, &summary.finished_at).map_err(|e| e.to_string())?;
+    }
+
+    if summary.tweets_requested > 0 {
+        println!(
+            "Metrics collection finished: {} snapshots, {} errors ({} tweets, {} accounts)",
+            summary.snapshots_saved, summary.errors, summary.tweets_requested, summary.accounts_checked
+        );
+    }
+    Ok(summary)
+}
+
+// バックグラウンドの指標収集ループを起動（設定で有効な場合のみ、取得間隔を過ぎたツイートを収集）
+pub fn spawn_metrics_collector(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(LOOP_TICK).await;
+
+            let state = app.state::<AppState>();
+            let enabled = match state.db.lock() {
+                Ok(conn) => load_settings(&conn).enabled,
+                Err(_) => continue,
+            };
+            if !enabled {
+                continue;
+            }
+
+            if let Err(e) = collect_metrics(&state, true).await {
+                eprintln!("Metrics collector error: {}", e);
+            }
+        }
+    });
+}
+
+impl PerformanceTotals {
+    fn add(&mut self, metrics: &PublicMetrics) {
+        let engagements = metrics.like_count + metrics.retweet_count + metrics.reply_count + metrics.quote_count;
+        self.posts += 1;
+        self.like_count += metrics.like_count;
+        self.retweet_count += metrics.retweet_count;
+        self.reply_count += metrics.reply_count;
+        self.quote_count += metrics.quote_count;
+        if let Some(impressions) = metrics.impression_count {
+            self.impression_count = Some(self.impression_count.unwrap_or(0) + impressions);
+            self.engagements_with_impressions += engagements;
+        }
+    }
+
+    fn finish(mut self) -> Self {
+        let engagements = self.like_count + self.retweet_count + self.reply_count + self.quote_count;
+        if self.posts > 0 {
+            self.avg_engagements = engagements as f64 / self.posts as f64;
+        }
+        self.engagement_rate = self.impression_count
+            .filter(|impressions| *impressions > 0)
+            .map(|impressions| self.engagements_with_impressions as f64 / impressions as f64);
+        self
+    }
+}
+
+// 成績集計の対象（最新スナップショットと投稿ログ）
+struct PostedTweet {
+    account_id: i64,
+    account_name: Option<String>,
+    schedule_id: Option<i64>,
+    content_index: Option<i64>,
+    tweet_content: Option<String>,
+    metrics: PublicMetrics,
+}
+
+// 指標を取得済みの投稿を古い順に取得
+fn load_posted_tweets(conn: &Connection, account_id: Option<i64>, schedule_id: Option<i64>, days: Option<i64>) -> Result<Vec<PostedTweet>, String> {
+    let posted_since = days.filter(|d| *d > 0).map(|d| (Utc::now() - Duration::days(d)).to_rfc3339());
+
+    let mut stmt = conn.prepare(
+        "WITH latest AS (
+             SELECT s.* FROM tweet_metric_snapshots s
+             WHERE s.id = (SELECT MAX(id) FROM tweet_metric_snapshots WHERE tweet_id = s.tweet_id)
+         )
+         SELECT el.account_id, ba.account_name, el.schedule_id, el.content_index, el.tweet_content,
+                l.like_count, l.retweet_count, l.reply_count, l.quote_count, l.bookmark_count, l.impression_count
+         FROM latest l
+         INNER JOIN execution_logs el
+             ON el.tweet_id = l.tweet_id AND el.log_type IN ('tweet', 'reply') AND el.status = 'success'
+         LEFT JOIN bot_accounts ba ON el.account_id = ba.id
+         WHERE (?1 IS NULL OR el.account_id = ?1)
+           AND (?2 IS NULL OR el.schedule_id = ?2)
+           AND (?3 IS NULL OR el.created_at >= ?3)
+         ORDER BY el.created_at, el.id"
+    ).map_err(|e| e.to_string())?;
+
+    let tweets = stmt.query_map(params![account_id, schedule_id, posted_since], |row| {
+        Ok(PostedTweet {
+            account_id: row.get(0)?,
+            account_name: row.get(1)?,
+            schedule_id: row.get(2)?,
+            content_index: row.get(3)?,
+            tweet_content: row.get(4)?,
+            metrics: PublicMetrics {
+                like_count: row.get(5)?,
+                retweet_count: row.get(6)?,
+                reply_count: row.get(7)?,
+                quote_count: row.get(8)?,
+                bookmark_count: row.get(9)?,
+                impression_count: row.get(10)?,
+            },
+        })
+    })
+    .map_err(|e| e.to_string())?
+    .collect::<SqliteResult<Vec<_>>>()
+    .map_err(|e| e.to_string())?;
+
+    Ok(tweets)
+}
+
+// アカウント別の成績（1投稿あたりのエンゲージメントが多い順）
+pub(crate) fn account_performance(conn: &Connection, days: Option<i64>) -> Result<Vec<AccountPerformance>, String> {
+    let mut accounts: BTreeMap<i64, AccountPerformance> = BTreeMap::new();
+    for tweet in load_posted_tweets(conn, None, None, days)? {
+        accounts.entry(tweet.account_id)
+            .or_insert_with(|| AccountPerformance {
+                account_id: tweet.account_id,
+                account_name: tweet.account_name.clone(),
+                totals: PerformanceTotals::default(),
+            })
+            .totals.add(&tweet.metrics);
+    }
+
+    let mut performance: Vec<AccountPerformance> = accounts.into_values()
+        .map(|a| AccountPerformance { totals: a.totals.finish(), ..a })
+        .collect();
+    performance.sort_by(|a, b| b.totals.avg_engagements.total_cmp(&a.totals.avg_engagements));
+    Ok(performance)
+}
+
+// スケジュールの投稿内容別の成績（1投稿あたりのエンゲージメントが多い順）
+pub(crate) fn content_performance(conn: &Connection, account_id: Option<i64>, schedule_id: Option<i64>, days: Option<i64>) -> Result<Vec<ContentPerformance>, String> {
+    let mut items: BTreeMap<(i64, i64), ContentPerformance> = BTreeMap::new();
+    for tweet in load_posted_tweets(conn, account_id, schedule_id, days)? {
+        let (Some(schedule_id), Some(content_index)) = (tweet.schedule_id, tweet.content_index) else { continue };
+        let item = items.entry((schedule_id, content_index))
+            .or_insert_with(|| ContentPerformance {
+                schedule_id,
+                content_index,
+                content: None,
+                totals: PerformanceTotals::default(),
+            });
+        // 古い順に読むため最後に投稿した内容が残る
+        if tweet.tweet_content.is_some() {
+            item.content = tweet.tweet_content.clone();
+        }
+        item.totals.add(&tweet.metrics);
+    }
+
+    let mut performance: Vec<ContentPerformance> = items.into_values()
+        .map(|c| ContentPerformance { totals: c.totals.finish(), ..c })
+        .collect();
+    performance.sort_by(|a, b| b.totals.avg_engagements.total_cmp(&a.totals.avg_engagements));
+    Ok(performance)
+}
+
+// ツイートの指標の推移（古い順）
+pub(crate) fn metrics_history(conn: &Connection, tweet_id: &str) -> Result<Vec<MetricSnapshot>, String> {
+    let mut stmt = conn.prepare(
+        "SELECT tweet_id, like_count, retweet_count, reply_count, quote_count, bookmark_count, impression_count, collected_at
+         FROM tweet_metric_snapshots
+         WHERE tweet_id = ?
+         ORDER BY collected_at, id"
+    ).map_err(|e| e.to_string())?;
+
+    let snapshots = stmt.query_map(params![tweet_id], |row| {
+        Ok(MetricSnapshot {
+            tweet_id: row.get(0)?,
+            metrics: PublicMetrics {
+                like_count: row.get(1)?,
+                retweet_count: row.get(2)?,
+                reply_count: row.get(3)?,
+                quote_count: row.get(4)?,
+                bookmark_count: row.get(5)?,
+                impression_count: row.get(6)?,
+            },
+            collected_at: row.get(7)?,
+        })
+    })
+    .map_err(|e| e.to_string())?
+    .collect::<SqliteResult<Vec<_>>>()
+    .map_err(|e| e.to_string())?;
+
+    Ok(snapshots)
+}
+
+#[tauri::command]
+pub fn get_metrics_settings(state: State<AppState>) -> Result<MetricsSettings, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    Ok(load_settings(&conn))
+}
+
+#[tauri::command]
+pub fn update_metrics_settings(settings: MetricsSettings, state: State<AppState>) -> Result<(), String> {
+    if settings.interval_minutes < MIN_INTERVAL_MINUTES {
+        return Err(format!("取得間隔は{}分以上にしてください", MIN_INTERVAL_MINUTES));
+    }
+    if !(1..=MAX_LOOKBACK_DAYS).contains(&settings.lookback_days) {
+        return Err(format!("収集期間は1〜{}日で指定してください", MAX_LOOKBACK_DAYS));
+    }
+
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    let values = [
+        (SETTING_ENABLED, if settings.enabled { "true" } else { "false" }.to_string()),
+        (SETTING_INTERVAL_MINUTES, settings.interval_minutes.to_string()),
+        (SETTING_LOOKBACK_DAYS, settings.lookback_days.to_string()),
+    ];
+    for (key, value) in values {
+        crate::set_app_setting(&conn, key, &value).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+// 収集対象のツイートの指標を今すぐ取得（取得間隔に関係なく全件）
+#[tauri::command]
+pub async fn collect_tweet_metrics_now(state: State<'_, AppState>) -> Result<MetricsCollectionSummary, String> {
+    collect_metrics(&state, false).await
+}
+
+#[tauri::command]
+pub fn get_account_performance(days: Option<i64>, state: State<AppState>) -> Result<Vec<AccountPerformance>, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    account_performance(&conn, days)
+}
+
+#[tauri::command]
+pub fn get_content_performance(
+    account_id: Option<i64>,
+    schedule_id: Option<i64>,
+    days: Option<i64>,
+    state: State<AppState>
+) -> Result<Vec<ContentPerformance>, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    content_performance(&conn, account_id, schedule_id, days)
+}
+
+#[tauri::command]
+pub fn get_tweet_metrics_history(tweet_id: String, state: State<AppState>) -> Result<Vec<MetricSnapshot>, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    metrics_history(&conn, &tweet_id)
+}
@@ -0,0 +1,521 @@
+// Twitter API v2 クライアント（OAuth 1.0a ユーザーコンテキスト）
+
+use serde::{Serialize, Deserialize};
+use serde_json::json;
+use chrono::{DateTime, TimeZone, Utc};
+use std::fmt;
+
+use crate::BotAccount;
+
+const DEFAULT_API_BASE_URL: &str = "https://api.twitter.com";
+
+// 接続先を差し替える環境変数（モックサーバーでの動作確認用、デバッグビルドのみ有効）
+#[cfg(debug_assertions)]
+const API_BASE_URL_ENV: &str = "TWITTER_API_BASE_URL";
+
+// タイムライン取得の1ページの件数と、1回の取得でたどる最大ページ数
+const TIMELINE_PAGE_SIZE: u32 = 100;
+const TIMELINE_MAX_PAGES: usize = 5;
+
+// Twitter API 呼び出しのエラー（HTTPステータスが取れた場合は保持する）
+#[derive(Debug, Clone)]
+pub struct ApiError {
+    pub status: Option<u16>,
+    pub code: Option<String>,  // エラーレスポンスのコード（v1.1 の errors[].code、v2 の type）
+    pub message: String,
+    pub rate_limit_reset: Option<DateTime<Utc>>,  // x-rate-limit-reset（レート制限が解除される日時）
+}
+
+impl ApiError {
+    fn new(message: String) -> Self {
+        ApiError { status: None, code: None, message, rate_limit_reset: None }
+    }
+
+    // レート制限（429）かどうか
+    pub fn is_rate_limited(&self) -> bool {
+        self.status == Some(429)
+    }
+
+    // 時間をおけば成功する可能性があるか（通信エラー・レート制限・5xx）
+    pub fn is_transient(&self) -> bool {
+        match self.status {
+            None => true,
+            Some(status) => status == 429 || status >= 500,
+        }
+    }
+
+    // 重複投稿による 403 か（v1.1 のコード 187、v2 は detail の文言で判定する）
+    // 凍結・ロック・権限不足などの 403 は含まない
+    pub fn is_duplicate_content(&self) -> bool {
+        self.status == Some(403)
+            && (self.code.as_deref() == Some("187") || self.message.to_lowercase().contains("duplicate content"))
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl From<ApiError> for String {
+    fn from(e: ApiError) -> Self {
+        e.message
+    }
+}
+
+// タイムラインのツイート
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tweet {
+    pub id: String,
+    pub text: String,
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub author_id: Option<String>,
+    #[serde(default)]
+    pub conversation_id: Option<String>,
+    #[serde(default)]
+    pub referenced_tweets: Vec<ReferencedTweet>,
+}
+
+// 参照先ツイート（type は retweeted / quoted / replied_to）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReferencedTweet {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub id: String,
+}
+
+impl Tweet {
+    pub fn is_referenced_as(&self, kind: &str) -> bool {
+        self.referenced_tweets.iter().any(|r| r.kind == kind)
+    }
+}
+
+// ユーザー情報
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TwitterUser {
+    pub id: String,
+    pub username: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_metrics: Option<UserMetrics>,   // user.fields=public_metrics を指定した場合のみ
+}
+
+// ユーザーの公開指標
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UserMetrics {
+    #[serde(default)]
+    pub followers_count: i64,
+    #[serde(default)]
+    pub following_count: i64,
+    #[serde(default)]
+    pub tweet_count: i64,
+    #[serde(default)]
+    pub listed_count: i64,
+}
+
+// ツイートの公開指標（impression_count・bookmark_count は取得できない場合がある）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PublicMetrics {
+    #[serde(default)]
+    pub like_count: i64,
+    #[serde(default)]
+    pub retweet_count: i64,
+    #[serde(default)]
+    pub reply_count: i64,
+    #[serde(default)]
+    pub quote_count: i64,
+    #[serde(default)]
+    pub bookmark_count: Option<i64>,
+    #[serde(default)]
+    pub impression_count: Option<i64>,
+}
+
+// 指標付きのツイート
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TweetMetrics {
+    pub id: String,
+    #[serde(default)]
+    pub public_metrics: PublicMetrics,
+}
+
+// タイムライン取得結果（truncated はページ数の上限に達し、さらに古い新着ツイートが残っている場合）
+#[derive(Debug, Clone, Default)]
+pub struct Timeline {
+    pub tweets: Vec<Tweet>,
+    pub truncated: bool,
+}
+
+// メンション取得結果
+#[derive(Debug, Clone, Default)]
+pub struct MentionsPage {
+    pub tweets: Vec<Tweet>,
+    pub users: Vec<TwitterUser>,
+}
+
+// API の接続先とHTTPクライアント（テストではモックサーバーに向ける）
+#[derive(Debug, Clone)]
+pub struct ApiClient {
+    base_url: String,
+    http: reqwest::Client,
+}
+
+impl Default for ApiClient {
+    fn default() -> Self {
+        ApiClient::new(DEFAULT_API_BASE_URL, reqwest::Client::new())
+    }
+}
+
+impl ApiClient {
+    pub fn new(base_url: impl Into<String>, http: reqwest::Client) -> Self {
+        ApiClient {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            http,
+        }
+    }
+
+    // デバッグビルドでは TWITTER_API_BASE_URL が設定されていればその接続先を使う
+    // リリースビルドでは署名付きのリクエストを他のホストへ送らないよう常に本番の接続先を使う
+    pub fn from_env() -> Self {
+        #[cfg(debug_assertions)]
+        if let Ok(base_url) = std::env::var(API_BASE_URL_ENV) {
+            if !base_url.trim().is_empty() {
+                println!("Using Twitter API base URL from {}", API_BASE_URL_ENV);
+                return ApiClient::new(base_url.trim(), reqwest::Client::new());
+            }
+        }
+        ApiClient::default()
+    }
+
+    // Twitter API v2 への投稿（正しいOAuth 1.0a版）
+    pub async fn post_to_twitter(&self, account: &BotAccount, content: &str) -> Result<String, ApiError> {
+        let payload = json!({
+            "text": content
+        });
+
+        self.send_tweet(account, payload).await
+    }
+
+    // ツイートへの返信を投稿
+    pub async fn post_reply_to_twitter(&self, account: &BotAccount, content: &str, in_reply_to_tweet_id: &str) -> Result<String, ApiError> {
+        let payload = json!({
+            "text": content,
+            "reply": {
+                "in_reply_to_tweet_id": in_reply_to_tweet_id
+            }
+        });
+
+        self.send_tweet(account, payload).await
+    }
+
+    async fn send_tweet(&self, account: &BotAccount, payload: serde_json::Value) -> Result<String, ApiError> {
+        let url = format!("{}/2/tweets", self.base_url);
+        let payload = payload.to_string();
+
+        // OAuth 1.0a認証を手動実装（oauth1クレートの代わり）
+        // JSONボディは署名対象外
+        let authorization_header = create_oauth_header("POST", &url, account, &[])
+            .map_err(ApiError::new)?;
+
+        let response = self.http
+            .post(&url)
+            .header("Authorization", authorization_header)
+            .header("Content-Type", "application/json")
+            .body(payload)
+            .send()
+            .await
+            .map_err(|e| ApiError::new(format!("リクエスト送信エラー: {}", e)))?;
+
+        let json = read_json_response(response).await?;
+
+        if let Some(tweet_id) = json["data"]["id"].as_str() {
+            Ok(tweet_id.to_string())
+        } else {
+            Err(ApiError::new("ツイートIDが取得できませんでした".to_string()))
+        }
+    }
+
+    // 自分のツイートを削除（DELETE /2/tweets/:id）
+    pub async fn delete_tweet(&self, account: &BotAccount, tweet_id: &str) -> Result<(), ApiError> {
+        let url = format!("{}/2/tweets/{}", self.base_url, tweet_id);
+        let authorization_header = create_oauth_header("DELETE", &url, account, &[])
+            .map_err(ApiError::new)?;
+
+        let response = self.http
+            .delete(&url)
+            .header("Authorization", authorization_header)
+            .send()
+            .await
+            .map_err(|e| ApiError::new(format!("リクエスト送信エラー: {}", e)))?;
+
+        let json = read_json_response(response).await?;
+
+        if json["data"]["deleted"].as_bool() == Some(true) {
+            Ok(())
+        } else {
+            Err(ApiError::new(format!("ツイート {} を削除できませんでした", tweet_id)))
+        }
+    }
+
+    // 認証中のユーザー情報を取得（GET /2/users/me）
+    pub async fn get_me(&self, account: &BotAccount) -> Result<TwitterUser, ApiError> {
+        let json = self.get_json(account, "/2/users/me", &[]).await?;
+
+        serde_json::from_value(json["data"].clone())
+            .map_err(|e| ApiError::new(format!("ユーザー情報の解析エラー: {}", e)))
+    }
+
+    // 認証中のユーザー情報をフォロワー数などの公開指標付きで取得
+    pub async fn get_me_with_metrics(&self, account: &BotAccount) -> Result<TwitterUser, ApiError> {
+        let query = vec![("user.fields", "public_metrics".to_string())];
+        let json = self.get_json(account, "/2/users/me", &query).await?;
+
+        serde_json::from_value(json["data"].clone())
+            .map_err(|e| ApiError::new(format!("ユーザー情報の解析エラー: {}", e)))
+    }
+
+    // ユーザー名からユーザー情報を取得（GET /2/users/by/username/:username）
+    pub async fn get_user_by_username(&self, account: &BotAccount, username: &str) -> Result<TwitterUser, ApiError> {
+        let json = self.get_json(account, &format!("/2/users/by/username/{}", username), &[]).await?;
+
+        // 存在しないユーザーは 200 で errors のみが返る
+        if json.get("data").is_none() {
+            return Err(ApiError::new(format!("ユーザー @{} が見つかりません", username)));
+        }
+        serde_json::from_value(json["data"].clone())
+            .map_err(|e| ApiError::new(format!("ユーザー情報の解析エラー: {}", e)))
+    }
+
+    // ユーザーのタイムラインを取得（exclude に retweets / replies を指定すると除外、新しい順）
+    // since_id を指定した場合は、前回以降のツイートを取りこぼさないよう next_token をたどって
+    // 最大 TIMELINE_MAX_PAGES ページまで取得する（初回は最新の1ページのみ）
+    // until_id を指定した場合はそれより古いツイートのみを取得する（取得しきれなかった範囲の再取得用）
+    pub async fn get_user_timeline(&self, account: &BotAccount, user_id: &str, since_id: Option<&str>, until_id: Option<&str>, exclude: &[&str]) -> Result<Timeline, ApiError> {
+        let mut query = vec![
+            ("max_results", TIMELINE_PAGE_SIZE.to_string()),
+            ("tweet.fields", "created_at,conversation_id,author_id,referenced_tweets".to_string()),
+        ];
+        if !exclude.is_empty() {
+            query.push(("exclude", exclude.join(",")));
+        }
+        if let Some(since_id) = since_id {
+            query.push(("since_id", since_id.to_string()));
+        }
+        if let Some(until_id) = until_id {
+            query.push(("until_id", until_id.to_string()));
+        }
+
+        let path = format!("/2/users/{}/tweets", user_id);
+        let mut timeline = Timeline::default();
+        for page in 1..=TIMELINE_MAX_PAGES {
+            let json = self.get_json(account, &path, &query).await?;
+
+            // 新しいツイートが無い場合は data が含まれない
+            if let Some(data) = json.get("data") {
+                let tweets: Vec<Tweet> = serde_json::from_value(data.clone())
+                    .map_err(|e| ApiError::new(format!("タイムラインの解析エラー: {}", e)))?;
+                timeline.tweets.extend(tweets);
+            }
+
+            let Some(next_token) = json["meta"]["next_token"].as_str().filter(|_| since_id.is_some()) else { break };
+            if page == TIMELINE_MAX_PAGES {
+                timeline.truncated = true;
+                break;
+            }
+            query.retain(|(key, _)| *key != "pagination_token");
+            query.push(("pagination_token", next_token.to_string()));
+        }
+        Ok(timeline)
+    }
+
+    // 自分宛てのメンションを取得（新しい順、投稿者のユーザー情報を含む）
+    pub async fn get_mentions(&self, account: &BotAccount, user_id: &str, since_id: Option<&str>) -> Result<MentionsPage, ApiError> {
+        let mut query = vec![
+            ("max_results", "20".to_string()),
+            ("tweet.fields", "created_at,conversation_id,author_id,referenced_tweets".to_string()),
+            ("expansions", "author_id".to_string()),
+            ("user.fields", "username,name".to_string()),
+        ];
+        if let Some(since_id) = since_id {
+            query.push(("since_id", since_id.to_string()));
+        }
+
+        let json = self.get_json(account, &format!("/2/users/{}/mentions", user_id), &query).await?;
+
+        let tweets = match json.get("data") {
+            Some(data) => serde_json::from_value(data.clone())
+                .map_err(|e| ApiError::new(format!("メンションの解析エラー: {}", e)))?,
+            None => Vec::new(),
+        };
+        let users = json["includes"]["users"].as_array()
+            .map(|users| users.iter()
+                .filter_map(|u| serde_json::from_value(u.clone()).ok())
+                .collect())
+            .unwrap_or_default();
+
+        Ok(MentionsPage { tweets, users })
+    }
+
+    // ツイートの公開指標をまとめて取得（GET /2/tweets?ids=、最大100件）
+    // 削除済み・非公開のツイートは結果に含まれない
+    pub async fn get_tweet_metrics(&self, account: &BotAccount, tweet_ids: &[String]) -> Result<Vec<TweetMetrics>, ApiError> {
+        let query = vec![
+            ("ids", tweet_ids.join(",")),
+            ("tweet.fields", "public_metrics".to_string()),
+        ];
+
+        let json = self.get_json(account, "/2/tweets", &query).await?;
+
+        match json.get("data") {
+            Some(data) => serde_json::from_value(data.clone())
+                .map_err(|e| ApiError::new(format!("ツイート指標の解析エラー: {}", e))),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    async fn get_json(&self, account: &BotAccount, path: &str, query: &[(&str, String)]) -> Result<serde_json::Value, ApiError> {
+        let url = format!("{}{}", self.base_url, path);
+        let authorization_header = create_oauth_header("GET", &url, account, query)
+            .map_err(ApiError::new)?;
+
+        let response = self.http
+            .get(&url)
+            .query(query)
+            .header("Authorization", authorization_header)
+            .send()
+            .await
+            .map_err(|e| ApiError::new(format!("リクエスト送信エラー: {}", e)))?;
+
+        read_json_response(response).await
+    }
+}
+
+async fn read_json_response(response: reqwest::Response) -> Result<serde_json::Value, ApiError> {
+    let status = response.status();
+    let rate_limit_reset = response.headers().get("x-rate-limit-reset")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<i64>().ok())
+        .and_then(|v| Utc.timestamp_opt(v, 0).single());
+    let response_text = response.text().await
+        .map_err(|e| ApiError::new(format!("レスポンス読取エラー: {}", e)))?;
+
+    if !status.is_success() {
+        return Err(ApiError {
+            status: Some(status.as_u16()),
+            code: error_code(&response_text),
+            message: format!("Twitter API エラー ({}): {}", status, response_text),
+            rate_limit_reset,
+        });
+    }
+
+    serde_json::from_str(&response_text)
+        .map_err(|e| ApiError::new(format!("JSON解析エラー: {}", e)))
+}
+
+// エラーレスポンスからエラーコードを取り出す
+// v1.1 形式は {"errors":[{"code":187,...}]}、v2 形式は {"type":"https://api.twitter.com/2/problems/usage-capped","title":...}
+fn error_code(response_text: &str) -> Option<String> {
+    let body: serde_json::Value = serde_json::from_str(response_text).ok()?;
+
+    if let Some(code) = body["errors"].get(0).and_then(|e| e.get("code")) {
+        return Some(code.as_str().map(str::to_string).unwrap_or_else(|| code.to_string()));
+    }
+    match body["type"].as_str() {
+        Some(problem) if problem != "about:blank" => problem.rsplit('/').next().map(str::to_string),
+        _ => body["title"].as_str().map(str::to_string),
+    }
+}
+
+// OAuth 1.0a認証ヘッダーを作成（クエリパラメータも署名に含める）
+fn create_oauth_header(
+    method: &str,
+    url: &str,
+    account: &BotAccount,
+    query: &[(&str, String)],
+) -> Result<String, String> {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    use std::collections::BTreeMap;
+
+    // タイムスタンプとナンスを生成
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+
+    let nonce: String = (0..32)
+        .map(|_| {
+            let chars = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+            chars[rand::random::<usize>() % chars.len()] as char
+        })
+        .collect();
+
+    // OAuthパラメータ
+    let mut oauth_params = BTreeMap::new();
+    oauth_params.insert("oauth_consumer_key", account.api_key.clone());
+    oauth_params.insert("oauth_nonce", nonce);
+    oauth_params.insert("oauth_signature_method", "HMAC-SHA1".to_string());
+    oauth_params.insert("oauth_timestamp", timestamp.to_string());
+    oauth_params.insert("oauth_token", account.access_token.clone());
+    oauth_params.insert("oauth_version", "1.0".to_string());
+
+    // パラメータ文字列を作成（OAuthパラメータとクエリパラメータをエンコード後にソート）
+    let mut signed_params: Vec<(String, String)> = oauth_params
+        .iter()
+        .map(|(k, v)| (url_encode(k), url_encode(v)))
+        .chain(query.iter().map(|(k, v)| (url_encode(k), url_encode(v))))
+        .collect();
+    signed_params.sort();
+
+    let param_string = signed_params
+        .iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<_>>()
+        .join("&");
+
+    // 署名ベース文字列を作成
+    let base_string = format!(
+        "{}&{}&{}",
+        method,
+        url_encode(url),
+        url_encode(&param_string)
+    );
+
+    // 署名キーを作成
+    let signing_key = format!("{}&{}", url_encode(&account.api_key_secret), url_encode(&account.access_token_secret));
+
+    // HMAC-SHA1署名を生成（簡易版）
+    use hmac::{Hmac, Mac};
+    use sha1::Sha1;
+    use base64::{Engine as _, engine::general_purpose::STANDARD};
+
+    type HmacSha1 = Hmac<Sha1>;
+    let mut mac = HmacSha1::new_from_slice(signing_key.as_bytes())
+        .map_err(|e| format!("HMAC初期化エラー: {}", e))?;
+    mac.update(base_string.as_bytes());
+    let signature = STANDARD.encode(mac.finalize().into_bytes());
+
+    oauth_params.insert("oauth_signature", signature);
+
+    // Authorizationヘッダーを構築
+    let auth_header = oauth_params
+        .iter()
+        .map(|(k, v)| format!("{}=\"{}\"", url_encode(k), url_encode(v)))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    Ok(format!("OAuth {}", auth_header))
+}
+
+// URL エンコード関数（RFC 3986、UTF-8バイト単位）
+fn url_encode(input: &str) -> String {
+    input
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
@@ -0,0 +1,228 @@
+// 外部アカウントの監視対象
+//
+// bot_accounts に登録していないアカウントもユーザー名で登録し、
+// ユーザー検索（GET /2/users/by/username/:username）で解決したユーザーIDをキャッシュする。
+// 返信設定からは reply_settings.watched_user_ids（watched_users.id のJSON配列）で参照し、
+// 自分のBotと同じチェックポイント・返信処理で扱う。タイムライン取得には返信Botの認証情報を使う。
+
+use rusqlite::{Connection, params, Result as SqliteResult};
+use serde::{Serialize, Deserialize};
+use chrono::Utc;
+use tauri::State;
+
+use crate::AppState;
+use crate::after_commit;
+use crate::twitter_api;
+
+// 監視対象の外部アカウント
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchedUser {
+    pub id: i64,
+    pub username: String,
+    pub twitter_user_id: Option<String>,
+    pub display_name: Option<String>,
+    pub resolved_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+// テーブルと返信設定の列を作成（マイグレーションから呼ばれる）
+pub fn create_watched_user_tables(conn: &Connection) -> SqliteResult<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS watched_users (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            username TEXT NOT NULL UNIQUE COLLATE NOCASE,
+            twitter_user_id TEXT,
+            display_name TEXT,
+            resolved_at TEXT,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    let watched_user_ids_exists: i32 = conn.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info('reply_settings') WHERE name='watched_user_ids'",
+        [],
+        |row| row.get(0)
+    ).unwrap_or(0);
+
+    if watched_user_ids_exists == 0 {
+        conn.execute(
+            "ALTER TABLE reply_settings ADD COLUMN watched_user_ids TEXT NOT NULL DEFAULT '[]'",
+            [],
+        )?;
+        println!("Added watched_user_ids column to reply_settings table");
+    }
+
+    Ok(())
+}
+
+fn watched_user_from_row(row: &rusqlite::Row) -> SqliteResult<WatchedUser> {
+    Ok(WatchedUser {
+        id: row.get(0)?,
+        username: row.get(1)?,
+        twitter_user_id: row.get(2)?,
+        display_name: row.get(3)?,
+        resolved_at: row.get(4)?,
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+    })
+}
+
+pub(crate) fn get_watched_user(conn: &Connection, id: i64) -> SqliteResult<WatchedUser> {
+    conn.query_row(
+        "SELECT id, username, twitter_user_id, display_name, resolved_at, created_at, updated_at FROM watched_users WHERE id = ?",
+        params![id],
+        watched_user_from_row
+    )
+}
+
+pub(crate) fn watched_user_exists(conn: &Connection, id: i64) -> bool {
+    conn.query_row(
+        "SELECT COUNT(*) FROM watched_users WHERE id = ?",
+        params![id],
+        |row| row.get::<_, i32>(0)
+    ).unwrap_or(0) > 0
+}
+
+// 返信設定が監視している外部アカウントのID
+pub(crate) fn load_watched_user_ids(conn: &Connection, reply_setting_id: i64) -> Vec<i64> {
+    conn.query_row(
+        "SELECT watched_user_ids FROM reply_settings WHERE id = ?",
+        params![reply_setting_id],
+        |row| row.get::<_, String>(0)
+    )
+    .ok()
+    .and_then(|json| serde_json::from_str(&json).ok())
+    .unwrap_or_default()
+}
+
+// 解決したユーザーIDを保存
+pub(crate) fn set_resolved(conn: &Connection, id: i64, user: &twitter_api::TwitterUser) -> SqliteResult<()> {
+    let now = Utc::now().to_rfc3339();
+    conn.execute(
+        "UPDATE watched_users SET twitter_user_id = ?, display_name = ?, resolved_at = ?, updated_at = ? WHERE id = ?",
+        params![user.id, user.name, now, now, id],
+    )?;
+    Ok(())
+}
+
+// "@name" や "https://x.com/name" 形式も受け付けてユーザー名だけを取り出す
+fn normalize_username(input: &str) -> Result<String, String> {
+    let trimmed = input.trim().trim_end_matches('/');
+    let username = trimmed.rsplit('/').next().unwrap_or(trimmed).trim_start_matches('@');
+
+    let valid = !username.is_empty()
+        && username.len() <= 15
+        && username.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        return Err(format!("ユーザー名 {} が不正です", input.trim()));
+    }
+    Ok(username.to_string())
+}
+
+#[tauri::command]
+pub fn get_watched_users(state: State<AppState>) -> Result<Vec<WatchedUser>, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+
+    let mut stmt = conn.prepare(
+        "SELECT id, username, twitter_user_id, display_name, resolved_at, created_at, updated_at FROM watched_users ORDER BY username"
+    ).map_err(|e| e.to_string())?;
+
+    let users = stmt.query_map([], watched_user_from_row)
+        .map_err(|e| e.to_string())?
+        .collect::<SqliteResult<Vec<_>>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(users)
+}
+
+// 外部アカウントを登録し、lookup_account_id のBotの認証情報でユーザーIDを解決する
+#[tauri::command]
+pub async fn add_watched_user(username: String, lookup_account_id: i64, state: State<'_, AppState>) -> Result<WatchedUser, String> {
+    let username = normalize_username(&username)?;
+
+    let account = {
+        let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+        crate::get_bot_account(&conn, lookup_account_id)
+            .map_err(|_| format!("アカウント ID {} が存在しません", lookup_account_id))?
+    };
+
+    let user = state.api.get_user_by_username(&account, &username).await?;
+
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    let now = Utc::now().to_rfc3339();
+    conn.execute(
+        "INSERT INTO watched_users (username, twitter_user_id, display_name, resolved_at, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?4, ?4)
+         ON CONFLICT(username) DO UPDATE SET
+             username = excluded.username,
+             twitter_user_id = excluded.twitter_user_id,
+             display_name = excluded.display_name,
+             resolved_at = excluded.resolved_at,
+             updated_at = excluded.updated_at",
+        params![user.username, user.id, user.name, now],
+    ).map_err(|e| e.to_string())?;
+
+    let id: i64 = conn.query_row(
+        "SELECT id FROM watched_users WHERE username = ?",
+        params![user.username],
+        |row| row.get(0)
+    ).map_err(|e| e.to_string())?;
+
+    get_watched_user(&conn, id).map_err(|e| e.to_string())
+}
+
+// 外部アカウントを削除し、返信設定の監視対象からも除去する
+#[tauri::command]
+pub fn delete_watched_user(id: i64, state: State<AppState>) -> Result<(), String> {
+    let mut conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    let tx = after_commit::begin(&mut conn).map_err(|e| e.to_string())?;
+    let now = Utc::now().to_rfc3339();
+
+    let settings: Vec<(i64, String)> = {
+        let mut stmt = tx.prepare("SELECT id, watched_user_ids FROM reply_settings")
+            .map_err(|e| e.to_string())?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| e.to_string())?;
+        rows.collect::<SqliteResult<Vec<_>>>().map_err(|e| e.to_string())?
+    };
+
+    for (setting_id, json) in settings {
+        let ids: Vec<i64> = serde_json::from_str(&json).unwrap_or_default();
+        if ids.contains(&id) {
+            let remaining: Vec<i64> = ids.into_iter().filter(|&x| x != id).collect();
+            let remaining_json = serde_json::to_string(&remaining).map_err(|e| e.to_string())?;
+            tx.execute(
+                "UPDATE reply_settings SET watched_user_ids = ?, updated_at = ? WHERE id = ?",
+                params![remaining_json, now, setting_id],
+            ).map_err(|e| e.to_string())?;
+        }
+    }
+
+    tx.execute("DELETE FROM reply_checkpoints WHERE target_type = 'user' AND target_id = ?", params![id])
+        .map_err(|e| e.to_string())?;
+    tx.execute("DELETE FROM watched_users WHERE id = ?", params![id])
+        .map_err(|e| e.to_string())?;
+
+    tx.commit().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn usernames_are_taken_from_handles_and_profile_urls() {
+        assert_eq!(normalize_username("example_1").unwrap(), "example_1");
+        assert_eq!(normalize_username(" @example ").unwrap(), "example");
+        assert_eq!(normalize_username("https://x.com/example/").unwrap(), "example");
+        assert_eq!(normalize_username("https://twitter.com/@example").unwrap(), "example");
+
+        assert!(normalize_username("").is_err());
+        assert!(normalize_username("@").is_err());
+        assert!(normalize_username("名前").is_err());
+        assert!(normalize_username("sixteen_chars_xx").is_err());
+    }
+}
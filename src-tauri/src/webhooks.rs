@@ -0,0 +1,629 @@
+// 外部への Webhook 送信
+//
+// 登録した URL に、投稿の成功・失敗・アカウントのエラー・返信の送信・月間投稿数の上限接近を JSON で送る。
+// イベントはまず webhook_deliveries に「送信待ち」として記録し（送信前にアプリを終了しても失われない）、
+// 送信ループが後から送る。送る前に行を「送信中」にして確保し、送信ループとテスト送信が同じ行を二重に送らないようにする。
+// 失敗した送信は間隔を空けて再送し、上限回数を超えたら「失敗」として残す。
+// 形式は汎用 JSON・Slack・Discord の Incoming Webhook から選べる。
+// シークレットを設定すると、受信側で検証できるよう本文に HMAC-SHA256 の署名を付ける。
+
+use rusqlite::{Connection, params, OptionalExtension, Result as SqliteResult};
+use serde::{Serialize, Deserialize};
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::sync::OnceLock;
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::Notify;
+
+use crate::{AppState, ExecutionLog};
+
+// 再送を含めた送信回数の上限
+const MAX_ATTEMPTS: i64 = 5;
+// 1回に送る件数（残りは次のループで送る）
+const BATCH_SIZE: i64 = 50;
+const REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+const LOOP_TICK: std::time::Duration = std::time::Duration::from_secs(30);
+// Discord のメッセージは2000文字まで
+const DISCORD_MAX_CHARS: usize = 2000;
+
+// 送信するイベント
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    PostSucceeded,
+    PostFailed,
+    AccountError,
+    ReplySent,
+    QuotaWarning,
+    Test,
+}
+
+impl WebhookEvent {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::PostSucceeded => "post_succeeded",
+            WebhookEvent::PostFailed => "post_failed",
+            WebhookEvent::AccountError => "account_error",
+            WebhookEvent::ReplySent => "reply_sent",
+            WebhookEvent::QuotaWarning => "quota_warning",
+            WebhookEvent::Test => "test",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "post_succeeded" => Some(WebhookEvent::PostSucceeded),
+            "post_failed" => Some(WebhookEvent::PostFailed),
+            "account_error" => Some(WebhookEvent::AccountError),
+            "reply_sent" => Some(WebhookEvent::ReplySent),
+            "quota_warning" => Some(WebhookEvent::QuotaWarning),
+            _ => None,
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            WebhookEvent::PostSucceeded => "投稿成功",
+            WebhookEvent::PostFailed => "投稿失敗",
+            WebhookEvent::AccountError => "アカウントエラー",
+            WebhookEvent::ReplySent => "返信送信",
+            WebhookEvent::QuotaWarning => "投稿数の上限接近",
+            WebhookEvent::Test => "テスト",
+        }
+    }
+}
+
+// 登録した Webhook
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Webhook {
+    pub id: i64,
+    pub name: String,
+    pub url: String,
+    pub format: String,            // "json"・"slack"・"discord"
+    pub events: Vec<String>,       // 送るイベント
+    pub secret: Option<String>,    // 署名用（空なら署名しない）
+    pub is_active: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+// Webhook の登録・更新内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookRequest {
+    pub id: Option<i64>,           // 指定した場合は更新
+    pub name: String,
+    pub url: String,
+    pub format: String,
+    pub events: Vec<String>,
+    pub secret: Option<String>,
+    pub is_active: bool,
+}
+
+// 送信する内容（汎用 JSON 形式ではそのまま本文になる）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookPayload {
+    pub event: WebhookEvent,
+    pub account_id: Option<i64>,
+    pub account_name: Option<String>,
+    pub message: String,
+    pub tweet_id: Option<String>,
+    pub tweet_content: Option<String>,
+    pub occurred_at: String,
+}
+
+// 送信の記録
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    pub id: i64,
+    pub webhook_id: i64,
+    pub event: String,
+    pub payload: String,
+    pub status: String,              // "pending"・"sending"・"success"・"failed"
+    pub attempts: i64,
+    pub last_http_status: Option<i64>,
+    pub last_error: Option<String>,
+    pub next_attempt_at: Option<String>,
+    pub created_at: String,
+    pub delivered_at: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct WebhookDeliveryRun {
+    pub attempted: i32,
+    pub delivered: i32,
+    pub retrying: i32,
+    pub failed: i32,
+}
+
+// Webhook のテーブル（マイグレーションから呼ばれる）
+pub fn create_webhook_tables(conn: &Connection) -> SqliteResult<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS webhooks (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            url TEXT NOT NULL,
+            format TEXT NOT NULL DEFAULT 'json',
+            events TEXT NOT NULL DEFAULT '[]',
+            secret TEXT,
+            is_active BOOLEAN NOT NULL DEFAULT 1,
+            created_at TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS webhook_deliveries (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            webhook_id INTEGER NOT NULL,
+            event TEXT NOT NULL,
+            payload TEXT NOT NULL,
+            dedupe_key TEXT,
+            status TEXT NOT NULL DEFAULT 'pending',
+            attempts INTEGER NOT NULL DEFAULT 0,
+            last_http_status INTEGER,
+            last_error TEXT,
+            next_attempt_at TEXT,
+            created_at TEXT NOT NULL,
+            delivered_at TEXT,
+            FOREIGN KEY (webhook_id) REFERENCES webhooks(id) ON DELETE CASCADE
+        );
+        CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_pending ON webhook_deliveries (status, next_attempt_at);
+        CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_dedupe ON webhook_deliveries (webhook_id, dedupe_key);"
+    )
+}
+
+// 送信待ちが増えたことを送信ループに知らせる
+fn wakeup() -> &'static Notify {
+    static WAKEUP: OnceLock<Notify> = OnceLock::new();
+    WAKEUP.get_or_init(Notify::new)
+}
+
+fn http_client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .unwrap_or_default())
+}
+
+fn webhook_from_row(row: &rusqlite::Row) -> SqliteResult<Webhook> {
+    let events_json: String = row.get(4)?;
+    Ok(Webhook {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        url: row.get(2)?,
+        format: row.get(3)?,
+        events: serde_json::from_str(&events_json).unwrap_or_default(),
+        secret: row.get(5)?,
+        is_active: row.get(6)?,
+        created_at: row.get(7)?,
+        updated_at: row.get(8)?,
+    })
+}
+
+const WEBHOOK_COLUMNS: &str = "id, name, url, format, events, secret, is_active, created_at, updated_at";
+
+pub(crate) fn load_webhooks(conn: &Connection) -> SqliteResult<Vec<Webhook>> {
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM webhooks ORDER BY id", WEBHOOK_COLUMNS))?;
+    let webhooks = stmt.query_map([], webhook_from_row)?.collect();
+    webhooks
+}
+
+fn load_webhook(conn: &Connection, id: i64) -> SqliteResult<Option<Webhook>> {
+    conn.query_row(
+        &format!("SELECT {} FROM webhooks WHERE id = ?", WEBHOOK_COLUMNS),
+        params![id],
+        webhook_from_row
+    ).optional()
+}
+
+// Webhook を保存（id を指定した場合は更新）
+pub(crate) fn save_webhook_row(conn: &Connection, webhook: &WebhookRequest) -> Result<i64, String> {
+    if webhook.name.trim().is_empty() {
+        return Err("Webhook の名前が空です".to_string());
+    }
+    let url = reqwest::Url::parse(webhook.url.trim()).map_err(|_| format!("URL「{}」が正しくありません", webhook.url))?;
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return Err("URL は http:// または https:// で指定してください".to_string());
+    }
+    if !["json", "slack", "discord"].contains(&webhook.format.as_str()) {
+        return Err(format!("形式「{}」は指定できません（json / slack / discord）", webhook.format));
+    }
+    if webhook.events.is_empty() {
+        return Err("送信するイベントを1つ以上選んでください".to_string());
+    }
+    if let Some(unknown) = webhook.events.iter().find(|e| WebhookEvent::parse(e).is_none()) {
+        return Err(format!("イベント「{}」は指定できません", unknown));
+    }
+
+    let now = Utc::now().to_rfc3339();
+    let events_json = serde_json::to_string(&webhook.events).map_err(|e| format!("JSON変換エラー: {}", e))?;
+    let secret = webhook.secret.as_deref().map(str::trim).filter(|s| !s.is_empty());
+
+    if let Some(id) = webhook.id {
+        let updated = conn.execute(
+            "UPDATE webhooks SET name = ?, url = ?, format = ?, events = ?, secret = ?, is_active = ?, updated_at = ? WHERE id = ?",
+            params![webhook.name.trim(), url.as_str(), webhook.format, events_json, secret, webhook.is_active, now, id],
+        ).map_err(|e| e.to_string())?;
+        if updated == 0 {
+            return Err(format!("Webhook ID {} が存在しません", id));
+        }
+        Ok(id)
+    } else {
+        conn.execute(
+            "INSERT INTO webhooks (name, url, format, events, secret, is_active, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            params![webhook.name.trim(), url.as_str(), webhook.format, events_json, secret, webhook.is_active, now, now],
+        ).map_err(|e| e.to_string())?;
+        Ok(conn.last_insert_rowid())
+    }
+}
+
+// イベントの内容を作る（アカウント名を添える）
+pub(crate) fn payload(conn: &Connection, event: WebhookEvent, account_id: Option<i64>, message: &str) -> WebhookPayload {
+    let account_name = account_id.and_then(|id| conn.query_row(
+        "SELECT account_name FROM bot_accounts WHERE id = ?",
+        params![id],
+        |row| row.get(0)
+    ).ok());
+
+    WebhookPayload {
+        event,
+        account_id,
+        account_name,
+        message: message.to_string(),
+        tweet_id: None,
+        tweet_content: None,
+        occurred_at: Utc::now().to_rfc3339(),
+    }
+}
+
+fn insert_delivery(conn: &Connection, webhook_id: i64, payload: &WebhookPayload, dedupe_key: Option<&str>) -> SqliteResult<i64> {
+    let payload_json = serde_json::to_string(payload)
+        .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+    let now = Utc::now().to_rfc3339();
+    conn.execute(
+        "INSERT INTO webhook_deliveries (webhook_id, event, payload, dedupe_key, status, attempts, next_attempt_at, created_at)
+         VALUES (?, ?, ?, ?, 'pending', 0, ?, ?)",
+        params![webhook_id, payload.event.as_str(), payload_json, dedupe_key, now, now],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+// イベントを購読している有効な Webhook ごとに送信待ちを記録する
+// dedupe_key を指定すると、同じキーを送ったことのある Webhook には送らない
+pub(crate) fn enqueue(conn: &Connection, payload: WebhookPayload, dedupe_key: Option<&str>) -> SqliteResult<usize> {
+    let mut queued = 0;
+    for webhook in load_webhooks(conn)? {
+        if !webhook.is_active || !webhook.events.iter().any(|e| e == payload.event.as_str()) {
+            continue;
+        }
+        if let Some(key) = dedupe_key {
+            let sent: Option<i64> = conn.query_row(
+                "SELECT id FROM webhook_deliveries WHERE webhook_id = ? AND dedupe_key = ? LIMIT 1",
+                params![webhook.id, key],
+                |row| row.get(0)
+            ).optional()?;
+            if sent.is_some() {
+                continue;
+            }
+        }
+        insert_delivery(conn, webhook.id, &payload, dedupe_key)?;
+        queued += 1;
+    }
+
+    if queued > 0 {
+        wakeup().notify_one();
+    }
+    Ok(queued)
+}
+
+// 実行ログを書き込んだ後に呼ばれ、投稿・返信の成功と投稿の失敗を送信待ちにする
+pub(crate) fn observe_log(conn: &Connection, log: &ExecutionLog) -> SqliteResult<()> {
+    let event = match (log.status.as_str(), log.log_type.as_str()) {
+        ("success", "tweet") => WebhookEvent::PostSucceeded,
+        ("success", "reply") => WebhookEvent::ReplySent,
+        ("error", _) if log.details.is_automated_post() => WebhookEvent::PostFailed,
+        _ => return Ok(()),
+    };
+
+    let mut payload = payload(conn, event, Some(log.account_id), &log.message);
+    payload.tweet_id = log.tweet_id.clone();
+    payload.tweet_content = log.tweet_content.clone();
+    enqueue(conn, payload, None)?;
+    Ok(())
+}
+
+// チャットに表示する1メッセージ
+fn chat_text(payload: &WebhookPayload) -> String {
+    let mut text = format!("【{}】", payload.event.label());
+    if let Some(name) = &payload.account_name {
+        text.push_str(&format!("{}: ", name));
+    }
+    text.push_str(&payload.message);
+    if let Some(content) = payload.tweet_content.as_ref().filter(|c| !c.is_empty()) {
+        text.push_str(&format!("\n> {}", content.replace('\n', "\n> ")));
+    }
+    if let Some(tweet_id) = &payload.tweet_id {
+        text.push_str(&format!("\nhttps://x.com/i/web/status/{}", tweet_id));
+    }
+    text
+}
+
+// 形式に合わせた送信本文
+pub(crate) fn render_body(format: &str, payload: &WebhookPayload) -> String {
+    let body = match format {
+        "slack" => serde_json::json!({ "text": chat_text(payload) }),
+        "discord" => serde_json::json!({ "content": chat_text(payload).chars().take(DISCORD_MAX_CHARS).collect::<String>() }),
+        _ => serde_json::to_value(payload).unwrap_or_default(),
+    };
+    body.to_string()
+}
+
+// 署名（"{タイムスタンプ}.{本文}" の HMAC-SHA256 を16進数で）
+pub(crate) fn sign(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC can take key of any size");
+    mac.update(format!("{}.{}", timestamp, body).as_bytes());
+    mac.finalize().into_bytes().iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// 送信に失敗したときの次の再送時刻（1分・5分・30分・2時間）
+fn retry_delay(attempts: i64) -> Duration {
+    match attempts {
+        1 => Duration::minutes(1),
+        2 => Duration::minutes(5),
+        3 => Duration::minutes(30),
+        _ => Duration::hours(2),
+    }
+}
+
+async fn post(webhook: &Webhook, delivery_id: i64, event: &str, body: String) -> Result<u16, (Option<u16>, String)> {
+    let timestamp = Utc::now().timestamp();
+    let mut request = http_client().post(&webhook.url)
+        .header("Content-Type", "application/json")
+        .header("X-Webhook-Event", event)
+        .header("X-Webhook-Delivery", delivery_id.to_string())
+        .header("X-Webhook-Timestamp", timestamp.to_string());
+    if let Some(secret) = webhook.secret.as_deref().filter(|s| !s.is_empty()) {
+        request = request.header("X-Webhook-Signature", format!("sha256={}", sign(secret, timestamp, &body)));
+    }
+
+    let response = request.body(body).send().await.map_err(|e| (None, e.to_string()))?;
+    let status = response.status();
+    if status.is_success() {
+        Ok(status.as_u16())
+    } else {
+        let text = response.text().await.unwrap_or_default();
+        Err((Some(status.as_u16()), format!("HTTP {}: {}", status.as_u16(), text.chars().take(200).collect::<String>())))
+    }
+}
+
+// 確保した送信（id, webhook_id, event, payload, attempts）
+type ClaimedDelivery = (i64, i64, String, String, i64);
+
+// 送信時刻を過ぎた送信待ちを「送信中」にして確保する（only を指定するとその送信だけ）
+fn claim_due(conn: &Connection, now: DateTime<Utc>, only: Option<i64>) -> SqliteResult<Vec<ClaimedDelivery>> {
+    let mut stmt = conn.prepare(
+        "UPDATE webhook_deliveries SET status = 'sending'
+         WHERE id IN (
+            SELECT id FROM webhook_deliveries
+            WHERE status = 'pending' AND (next_attempt_at IS NULL OR next_attempt_at <= ?1) AND (?2 IS NULL OR id = ?2)
+            ORDER BY id LIMIT ?3
+         )
+         RETURNING id, webhook_id, event, payload, attempts"
+    )?;
+    let mut claimed = stmt.query_map(params![now.to_rfc3339(), only, BATCH_SIZE], |row| {
+        Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?))
+    })?
+    .collect::<SqliteResult<Vec<ClaimedDelivery>>>()?;
+    claimed.sort_by_key(|delivery| delivery.0);
+    Ok(claimed)
+}
+
+// 送信中のまま終了した送信を送信待ちに戻す（起動時に呼ばれる）
+fn release_interrupted(conn: &Connection) -> SqliteResult<usize> {
+    conn.execute("UPDATE webhook_deliveries SET status = 'pending' WHERE status = 'sending'", [])
+}
+
+// 送信時刻を過ぎた送信待ちを送る
+pub(crate) async fn deliver_pending(state: &AppState, now: DateTime<Utc>) -> Result<WebhookDeliveryRun, String> {
+    let due = {
+        let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+        claim_due(&conn, now, None).map_err(|e| e.to_string())?
+    };
+    deliver(state, due, now).await
+}
+
+// 確保した送信を送り、結果を記録する
+async fn deliver(state: &AppState, due: Vec<ClaimedDelivery>, now: DateTime<Utc>) -> Result<WebhookDeliveryRun, String> {
+    let mut run = WebhookDeliveryRun::default();
+    for (delivery_id, webhook_id, event, payload_json, attempts) in due {
+        let webhook = {
+            let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+            load_webhook(&conn, webhook_id).map_err(|e| e.to_string())?
+        };
+        // 送信待ちの間に削除・無効化された Webhook には送らない
+        let Some(webhook) = webhook.filter(|w| w.is_active) else {
+            let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+            conn.execute(
+                "UPDATE webhook_deliveries SET status = 'failed', last_error = ?, next_attempt_at = NULL WHERE id = ?",
+                params!["Webhook が無効です", delivery_id],
+            ).map_err(|e| e.to_string())?;
+            run.failed += 1;
+            continue;
+        };
+
+        let body = match serde_json::from_str::<WebhookPayload>(&payload_json) {
+            Ok(payload) => render_body(&webhook.format, &payload),
+            Err(e) => {
+                let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+                conn.execute(
+                    "UPDATE webhook_deliveries SET status = 'failed', last_error = ?, next_attempt_at = NULL WHERE id = ?",
+                    params![format!("送信内容を読み込めません: {}", e), delivery_id],
+                ).map_err(|e| e.to_string())?;
+                run.failed += 1;
+                continue;
+            }
+        };
+
+        run.attempted += 1;
+        let result = post(&webhook, delivery_id, &event, body).await;
+        let attempts = attempts + 1;
+
+        let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+        match result {
+            Ok(status) => {
+                conn.execute(
+                    "UPDATE webhook_deliveries SET status = 'success', attempts = ?, last_http_status = ?, last_error = NULL,
+                     next_attempt_at = NULL, delivered_at = ? WHERE id = ?",
+                    params![attempts, status, Utc::now().to_rfc3339(), delivery_id],
+                ).map_err(|e| e.to_string())?;
+                run.delivered += 1;
+            }
+            Err((status, error)) => {
+                let (status_text, next_attempt_at) = if attempts >= MAX_ATTEMPTS {
+                    run.failed += 1;
+                    ("failed", None)
+                } else {
+                    run.retrying += 1;
+                    ("pending", Some((now + retry_delay(attempts)).to_rfc3339()))
+                };
+                conn.execute(
+                    "UPDATE webhook_deliveries SET status = ?, attempts = ?, last_http_status = ?, last_error = ?, next_attempt_at = ? WHERE id = ?",
+                    params![status_text, attempts, status, error, next_attempt_at, delivery_id],
+                ).map_err(|e| e.to_string())?;
+                eprintln!("Webhook delivery {} failed (attempt {}): {}", delivery_id, attempts, error);
+            }
+        }
+    }
+
+    Ok(run)
+}
+
+// 送信ループを起動（送信待ちが増えたときと、再送のため一定間隔で）
+pub fn spawn_webhook_dispatcher(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        if let Ok(conn) = app.state::<AppState>().db.lock() {
+            if let Err(e) = release_interrupted(&conn) {
+                eprintln!("Webhook dispatcher error: {}", e);
+            }
+        }
+        loop {
+            let state = app.state::<AppState>();
+            if let Err(e) = deliver_pending(&state, Utc::now()).await {
+                eprintln!("Webhook dispatcher error: {}", e);
+            }
+
+            tokio::select! {
+                _ = wakeup().notified() => {}
+                _ = tokio::time::sleep(LOOP_TICK) => {}
+            }
+        }
+    });
+}
+
+pub(crate) fn load_deliveries(conn: &Connection, webhook_id: Option<i64>, limit: i64) -> Result<Vec<WebhookDelivery>, String> {
+    let mut stmt = conn.prepare(
+        "SELECT id, webhook_id, event, payload, status, attempts, last_http_status, last_error, next_attempt_at, created_at, delivered_at
+         FROM webhook_deliveries
+         WHERE (?1 IS NULL OR webhook_id = ?1)
+         ORDER BY id DESC
+         LIMIT ?2"
+    ).map_err(|e| e.to_string())?;
+
+    let deliveries = stmt.query_map(params![webhook_id, limit], |row| {
+        Ok(WebhookDelivery {
+            id: row.get(0)?,
+            webhook_id: row.get(1)?,
+            event: row.get(2)?,
+            payload: row.get(3)?,
+            status: row.get(4)?,
+            attempts: row.get(5)?,
+            last_http_status: row.get(6)?,
+            last_error: row.get(7)?,
+            next_attempt_at: row.get(8)?,
+            created_at: row.get(9)?,
+            delivered_at: row.get(10)?,
+        })
+    })
+    .map_err(|e| e.to_string())?
+    .collect::<SqliteResult<Vec<_>>>()
+    .map_err(|e| e.to_string())?;
+
+    Ok(deliveries)
+}
+
+#[tauri::command]
+pub fn get_webhooks(state: State<AppState>) -> Result<Vec<Webhook>, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    load_webhooks(&conn).map_err(|e| e.to_string())
+}
+
+// Webhook を保存（id を指定した場合は更新）
+#[tauri::command]
+pub fn save_webhook(request: WebhookRequest, state: State<AppState>) -> Result<i64, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    save_webhook_row(&conn, &request)
+}
+
+#[tauri::command]
+pub fn delete_webhook(id: i64, state: State<AppState>) -> Result<(), String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+
+    conn.execute("DELETE FROM webhook_deliveries WHERE webhook_id = ?", params![id])
+        .map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM webhooks WHERE id = ?", params![id])
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+// 送信の記録（新しい順、webhook_id を省略するとすべて）
+#[tauri::command]
+pub fn get_webhook_deliveries(webhook_id: Option<i64>, limit: Option<i64>, state: State<AppState>) -> Result<Vec<WebhookDelivery>, String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    load_deliveries(&conn, webhook_id, limit.unwrap_or(100))
+}
+
+// 失敗した送信をもう一度送信待ちにする
+#[tauri::command]
+pub fn retry_webhook_delivery(id: i64, state: State<AppState>) -> Result<(), String> {
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    let updated = conn.execute(
+        "UPDATE webhook_deliveries SET status = 'pending', attempts = 0, next_attempt_at = ? WHERE id = ? AND status = 'failed'",
+        params![Utc::now().to_rfc3339(), id],
+    ).map_err(|e| e.to_string())?;
+    if updated == 0 {
+        return Err(format!("失敗した送信 ID {} が存在しません", id));
+    }
+
+    wakeup().notify_one();
+    Ok(())
+}
+
+// テストイベントをすぐに送り、その結果を返す
+#[tauri::command]
+pub async fn send_test_webhook(id: i64, state: State<'_, AppState>) -> Result<WebhookDelivery, String> {
+    send_test(&state, id).await
+}
+
+// テストイベントだけを送る（他の送信待ちは送信ループに任せる）
+pub(crate) async fn send_test(state: &AppState, id: i64) -> Result<WebhookDelivery, String> {
+    let (delivery_id, claimed, now) = {
+        let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+        if load_webhook(&conn, id).map_err(|e| e.to_string())?.is_none() {
+            return Err(format!("Webhook ID {} が存在しません", id));
+        }
+        let payload = payload(&conn, WebhookEvent::Test, None, "Webhook の送信テストです");
+        let delivery_id = insert_delivery(&conn, id, &payload, None).map_err(|e| e.to_string())?;
+        // 送信ループに先に送られないよう、記録と同じロックの中で確保する
+        let now = Utc::now();
+        let claimed = claim_due(&conn, now, Some(delivery_id)).map_err(|e| e.to_string())?;
+        (delivery_id, claimed, now)
+    };
+
+    deliver(state, claimed, now).await?;
+
+    let conn = state.db.lock().map_err(|_| "Failed to lock database")?;
+    load_deliveries(&conn, Some(id), BATCH_SIZE)?
+        .into_iter()
+        .find(|d| d.id == delivery_id)
+        .ok_or_else(|| "送信の記録が見つかりません".to_string())
+}